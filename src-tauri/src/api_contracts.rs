@@ -128,6 +128,59 @@ pub struct PlayerInfo {
     pub result: String,
 }
 
+// =============================================================================
+// Resumable Chunked Upload Endpoints
+// =============================================================================
+
+/// Request to begin (or resume) a chunked upload of a single replay.
+///
+/// The server keys an in-flight upload by `hash`, so re-sending this for a hash
+/// that is already partway uploaded resumes it: the response reports which
+/// chunk indices have already been received so the client only sends the rest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadInitRequest {
+    pub hash: String,           // SHA-256 hash (64 hex chars)
+    pub filesize: u64,          // Total file size in bytes
+    pub chunk_size: u32,        // Bytes per chunk the client intends to send
+}
+
+/// Response from the upload-init endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadInitResponse {
+    pub upload_id: String,              // Opaque id the client persists to resume
+    pub chunk_size: u32,                // Authoritative chunk size (may clamp the request)
+    pub received_chunks: Vec<u32>,      // Chunk indices the server already holds
+}
+
+impl UploadInitResponse {
+    /// Chunk indices still missing for a file split into `total_chunks` chunks.
+    pub fn missing_chunks(&self, total_chunks: u32) -> Vec<u32> {
+        (0..total_chunks)
+            .filter(|idx| !self.received_chunks.contains(idx))
+            .collect()
+    }
+}
+
+/// Metadata for a single chunk. The chunk's bytes travel as the raw request
+/// body (like the multipart replay upload) rather than inside the JSON, so
+/// multi-megabyte chunks are not re-encoded as a JSON number array; `upload_id`
+/// and `index` are sent as query parameters built from this struct.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadChunkRequest {
+    pub upload_id: String,
+    pub index: u32,
+}
+
+/// Request to finalize a fully-uploaded replay.
+///
+/// The server reassembles the chunks, recomputes the SHA-256, and must match it
+/// against `hash` before accepting the replay.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadFinalizeRequest {
+    pub upload_id: String,
+    pub hash: String,
+}
+
 // =============================================================================
 // Manifest Version Endpoint
 // =============================================================================
@@ -147,16 +200,20 @@ pub struct ManifestVersionResponse {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DeviceAuthRequest {
     pub client_id: String,
-}
-
-/// Response from device auth initiation
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct DeviceAuthResponse {
-    pub device_code: String,         // Unique code for this device
-    pub user_code: String,           // Code user enters in browser
-    pub verification_uri: String,    // URL user visits
-    pub expires_in: u32,             // Seconds until device_code expires
-    pub interval: u32,               // Seconds between poll requests
+    /// base64-encoded ed25519 public key for this uploader instance. The
+    /// server records it so later uploads signed by the matching private key
+    /// can be authenticated; see [`crate::device_identity`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_public_key: Option<String>,
+    /// PKCE (RFC 7636) challenge derived from a client-generated verifier;
+    /// see [`crate::device_auth::PkceChallenge`]. The server must bind it to
+    /// the issued `device_code` and require the matching verifier at poll
+    /// time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_challenge: Option<String>,
+    /// Always `"S256"` when [`Self::code_challenge`] is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_challenge_method: Option<String>,
 }
 
 /// User info from OAuth
@@ -167,41 +224,6 @@ pub struct UserInfo {
     pub avatar_url: Option<String>,
 }
 
-/// Discriminated union for device poll response
-/// Uses internally tagged enum with "status" field
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "status", rename_all = "lowercase")]
-pub enum DevicePollResponse {
-    Pending,
-    Expired,
-    Denied,
-    #[serde(rename_all = "snake_case")]
-    Success {
-        access_token: String,
-        refresh_token: String,
-        token_type: String,
-        expires_in: u32,
-        user: Option<UserInfo>,
-    },
-}
-
-impl DevicePollResponse {
-    /// Check if authorization is complete
-    pub fn is_success(&self) -> bool {
-        matches!(self, DevicePollResponse::Success { .. })
-    }
-
-    /// Get tokens if successful
-    pub fn tokens(&self) -> Option<(&str, &str)> {
-        match self {
-            DevicePollResponse::Success { access_token, refresh_token, .. } => {
-                Some((access_token.as_str(), refresh_token.as_str()))
-            }
-            _ => None,
-        }
-    }
-}
-
 // =============================================================================
 // User Settings Endpoint
 // =============================================================================
@@ -283,32 +305,26 @@ mod tests {
     }
 
     #[test]
-    fn test_device_poll_pending() {
-        let json = r#"{"status": "pending"}"#;
-        let response: DevicePollResponse = serde_json::from_str(json).unwrap();
-        assert!(!response.is_success());
-    }
-
-    #[test]
-    fn test_device_poll_success() {
+    fn test_upload_init_response_missing_chunks() {
         let json = r#"{
-            "status": "success",
-            "access_token": "token123",
-            "refresh_token": "refresh456",
-            "token_type": "Bearer",
-            "expires_in": 3600,
-            "user": {
-                "id": "123",
-                "username": "testuser",
-                "avatar_url": null
-            }
+            "upload_id": "up-123",
+            "chunk_size": 1048576,
+            "received_chunks": [0, 2]
         }"#;
 
-        let response: DevicePollResponse = serde_json::from_str(json).unwrap();
-        assert!(response.is_success());
-        let (access, refresh) = response.tokens().unwrap();
-        assert_eq!(access, "token123");
-        assert_eq!(refresh, "refresh456");
+        let response: UploadInitResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.upload_id, "up-123");
+        assert_eq!(response.missing_chunks(4), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_upload_init_response_all_received() {
+        let response = UploadInitResponse {
+            upload_id: "up-9".to_string(),
+            chunk_size: 1024,
+            received_chunks: vec![0, 1, 2],
+        };
+        assert!(response.missing_chunks(3).is_empty());
     }
 
     #[test]
@@ -0,0 +1,224 @@
+//! Typed, versioned application configuration.
+//!
+//! This replaces the ad-hoc `serde_json::Value` round-tripping that individual
+//! settings commands used to do against `config.json`. Preferences now live in
+//! a single [`AppConfig`] struct with a `schema_version` so the on-disk format
+//! can evolve: [`load_app_config`] migrates older files forward,
+//! [`save_app_config`] writes through the atomic helper in
+//! [`crate::config_utils`], and — rather than failing a
+//! command when the file is corrupt — moves the unparseable file aside to
+//! `config.json.bak` and continues with defaults.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::debug_logger::DebugLogger;
+
+/// Name of the on-disk config file, relative to the app config directory.
+const CONFIG_FILE: &str = "config.json";
+
+/// Where a corrupt `config.json` is moved so the user can recover it manually.
+const CONFIG_BACKUP_FILE: &str = "config.json.bak";
+
+/// Current on-disk schema version for [`AppConfig`].
+///
+/// Bump this whenever the persisted shape changes and add a matching step to
+/// [`migrate_forward`] so older files load cleanly.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// All persisted application preferences.
+///
+/// Fields mirror the subset of [`crate::api_contracts::UserSettings`] we want
+/// to remember locally plus app-only toggles. Keys managed by other commands
+/// (e.g. `proxy_url`, `scan_interval_secs`, `http_client`) are preserved in
+/// [`AppConfig::extra`] so a typed write never clobbers them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Schema version of this config; see [`CURRENT_SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Whether the app launches on login.
+    pub autostart_enabled: bool,
+    /// Remembered replay folder, if the user has picked one.
+    pub replay_folder: Option<String>,
+    /// Preferred number of concurrent uploads; `None` uses the built-in default.
+    pub upload_concurrency: Option<usize>,
+    /// Default race, mirroring `UserSettings.default_race`.
+    pub default_race: Option<String>,
+    /// Per-folder region overrides, keyed by the absolute path of a watched
+    /// replay folder (or an ancestor of it) and mapping to a region code
+    /// (e.g. `"EU"`). Consulted by
+    /// [`crate::services::upload_executor::extract_replay_region`] before
+    /// falling back to parsing the Battle.net account folder name, for
+    /// installs where that heuristic gets the wrong answer.
+    pub region_overrides: HashMap<String, String>,
+    /// Unix timestamp (seconds) of the last successful scan of each watched
+    /// replay folder, keyed the same way as [`Self::region_overrides`].
+    /// Consulted by [`crate::services::replay_scanner::ReplayScanner`] to
+    /// skip files that haven't changed since, unless a full rescan is forced.
+    pub folder_scan_times: HashMap<String, u64>,
+    /// Any other top-level keys found in the file, preserved verbatim across
+    /// typed reads and writes.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            autostart_enabled: false,
+            replay_folder: None,
+            upload_concurrency: None,
+            default_race: None,
+            region_overrides: HashMap::new(),
+            folder_scan_times: HashMap::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Migrate a raw config object forward to [`CURRENT_SCHEMA_VERSION`].
+///
+/// A missing or `0` version is treated as the pre-versioning format written by
+/// the old untyped settings commands, which is structurally compatible, so the
+/// only step today is stamping the current version. Future format changes add
+/// their own arms here.
+fn migrate_forward(obj: &mut Map<String, Value>) {
+    let version = obj
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    // Placeholder for per-version rewrites as the schema grows:
+    //   if version < 2 { /* rename / reshape fields */ }
+    let _ = version;
+
+    obj.insert(
+        "schema_version".to_string(),
+        Value::from(CURRENT_SCHEMA_VERSION),
+    );
+}
+
+/// Load the typed application config, migrating and self-healing as needed.
+///
+/// Returns [`AppConfig::default`] when the file is absent. When it exists but
+/// cannot be parsed as JSON, the file is moved to `config.json.bak` and the
+/// defaults are returned so the caller can carry on rather than surfacing an
+/// error to the front end.
+pub async fn load_app_config(logger: &DebugLogger) -> AppConfig {
+    let config_file = match crate::config_utils::config_file_path(CONFIG_FILE) {
+        Ok(path) => path,
+        Err(e) => {
+            logger.error(format!("Could not resolve config path: {}", e));
+            return AppConfig::default();
+        }
+    };
+
+    if !config_file.exists() {
+        logger.debug("No config file; using default AppConfig".to_string());
+        return AppConfig::default();
+    }
+
+    let contents = match tokio::fs::read_to_string(&config_file).await {
+        Ok(contents) => contents,
+        Err(e) => {
+            logger.error(format!("Failed to read config: {}", e));
+            return AppConfig::default();
+        }
+    };
+
+    let mut value: Value = match serde_json::from_str(&contents) {
+        Ok(value) => value,
+        Err(e) => {
+            logger.warn(format!(
+                "Config file is corrupt ({}); backing up to {}",
+                e, CONFIG_BACKUP_FILE
+            ));
+            if let Ok(backup) = crate::config_utils::config_file_path(CONFIG_BACKUP_FILE) {
+                if let Err(e) = tokio::fs::rename(&config_file, &backup).await {
+                    logger.error(format!("Failed to back up corrupt config: {}", e));
+                }
+            }
+            return AppConfig::default();
+        }
+    };
+
+    if let Some(obj) = value.as_object_mut() {
+        migrate_forward(obj);
+    }
+
+    match serde_json::from_value(value) {
+        Ok(config) => config,
+        Err(e) => {
+            logger.warn(format!(
+                "Config file has unexpected shape ({}); backing up to {}",
+                e, CONFIG_BACKUP_FILE
+            ));
+            if let Ok(backup) = crate::config_utils::config_file_path(CONFIG_BACKUP_FILE) {
+                let _ = tokio::fs::rename(&config_file, &backup).await;
+            }
+            AppConfig::default()
+        }
+    }
+}
+
+/// Persist the typed application config atomically.
+pub async fn save_app_config(config: &AppConfig) -> Result<(), String> {
+    crate::config_utils::save_config_file(CONFIG_FILE, config)
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_carries_current_schema_version() {
+        assert_eq!(AppConfig::default().schema_version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_stamps_version_on_unversioned_config() {
+        // An old file written by the untyped settings commands: no version, a
+        // single known key, plus a key only other commands understand.
+        let mut obj = Map::new();
+        obj.insert("autostart_enabled".to_string(), Value::Bool(true));
+        obj.insert("proxy_url".to_string(), Value::from("socks5://127.0.0.1:9050"));
+
+        migrate_forward(&mut obj);
+
+        assert_eq!(
+            obj.get("schema_version").and_then(Value::as_u64),
+            Some(CURRENT_SCHEMA_VERSION as u64)
+        );
+
+        let config: AppConfig = serde_json::from_value(Value::Object(obj)).unwrap();
+        assert!(config.autostart_enabled);
+        // Unknown-to-AppConfig keys survive in `extra`.
+        assert_eq!(
+            config.extra.get("proxy_url").and_then(Value::as_str),
+            Some("socks5://127.0.0.1:9050")
+        );
+    }
+
+    #[test]
+    fn test_extra_keys_round_trip_through_serialization() {
+        let mut config = AppConfig::default();
+        config.autostart_enabled = true;
+        config
+            .extra
+            .insert("scan_interval_secs".to_string(), Value::from(900));
+
+        let json = serde_json::to_string(&config).unwrap();
+        let reloaded: AppConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded, config);
+        assert_eq!(
+            reloaded.extra.get("scan_interval_secs").and_then(Value::as_u64),
+            Some(900)
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Backup and restore of detected SC2 replay folders.
+//!
+//! Archives the `.SC2Replay` files in an [`SC2ReplayFolder`] into a tar.gz
+//! snapshot alongside a JSON manifest recording enough metadata
+//! (account_id/region/region_code, original relative paths, sizes, mtimes,
+//! content hashes) to recreate the folder elsewhere with
+//! [`restore_snapshot`]. [`create_snapshot`] is incremental: a file whose
+//! size and mtime still match the newest snapshot's manifest entry is left
+//! out of the new archive entirely, and its manifest entry just points back
+//! at the archive that already holds its bytes, so repeated backups of an
+//! unchanged folder are nearly free.
+
+use crate::replay_tracker::{scan_replay_folder, ReplayTracker};
+use crate::sc2_detector::SC2ReplayFolder;
+use crate::services::hash_cache::modified_time_to_unix_secs;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Schema version recorded in every manifest, bumped whenever
+/// [`SnapshotManifest`]'s shape changes.
+const SNAPSHOT_SCHEMA_VERSION: &str = "1";
+
+/// One archived file within a [`SnapshotManifest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// Path of the file relative to the folder's root, e.g. `"game.SC2Replay"`.
+    pub relative_path: String,
+    pub size: u64,
+    pub modified_unix_secs: u64,
+    pub hash: String,
+    /// Name of the archive file (in the same snapshot directory) that
+    /// actually holds this file's bytes. Usually this snapshot's own
+    /// archive, but an unchanged file carries forward the archive name of
+    /// the snapshot that last saw it change.
+    pub source_archive: String,
+}
+
+/// Manifest for one [`create_snapshot`] call, written as
+/// `<dest>/snapshot-<created_at_unix_secs>.json` next to its archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: String,
+    pub account_id: String,
+    pub region: String,
+    pub region_code: String,
+    pub created_at_unix_secs: u64,
+    /// Name of the tar.gz archive this snapshot wrote, relative to the
+    /// snapshot directory. Entries unchanged since an earlier snapshot
+    /// reference that snapshot's archive instead; see
+    /// [`SnapshotEntry::source_archive`].
+    pub archive_file: String,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// Archive every `.SC2Replay` file in `folder` into a new incremental
+/// snapshot under `dest`, creating `dest` if needed.
+///
+/// A file whose size and modification time still match its entry in the
+/// newest existing manifest (per [`list_snapshots`]) is skipped — neither
+/// re-hashed nor re-copied into the new archive — and its manifest entry is
+/// carried forward pointing at whichever archive already holds it.
+pub fn create_snapshot(folder: &SC2ReplayFolder, dest: &Path) -> Result<SnapshotManifest, String> {
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create snapshot directory: {}", e))?;
+
+    let previous_by_path = latest_manifest(dest)?
+        .map(|m| {
+            m.entries
+                .into_iter()
+                .map(|e| (e.relative_path.clone(), e))
+                .collect::<HashMap<_, _>>()
+        })
+        .unwrap_or_default();
+
+    let replays = scan_replay_folder(&folder.path)?;
+
+    // Names are keyed by whole seconds; step past a second that already has a
+    // snapshot so a quick re-run never overwrites an earlier archive.
+    let mut created_at = modified_time_to_unix_secs(SystemTime::now());
+    while dest.join(format!("snapshot-{}.tar.gz", created_at)).exists() {
+        created_at += 1;
+    }
+    let archive_file = format!("snapshot-{}.tar.gz", created_at);
+    let archive_path = dest.join(&archive_file);
+
+    let file = fs::File::create(&archive_path)
+        .map_err(|e| format!("Failed to create snapshot archive: {}", e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let mut entries = Vec::with_capacity(replays.len());
+    for replay in &replays {
+        let relative_path = replay.filename.clone();
+        let modified_unix_secs = modified_time_to_unix_secs(replay.modified_time);
+
+        if let Some(prev) = previous_by_path.get(&relative_path) {
+            if prev.size == replay.filesize && prev.modified_unix_secs == modified_unix_secs {
+                entries.push(prev.clone());
+                continue;
+            }
+        }
+
+        let hash = ReplayTracker::calculate_hash(&replay.path)?;
+        builder
+            .append_path_with_name(&replay.path, format!("replays/{}", relative_path))
+            .map_err(|e| format!("Failed to archive {}: {}", relative_path, e))?;
+        entries.push(SnapshotEntry {
+            relative_path,
+            size: replay.filesize,
+            modified_unix_secs,
+            hash,
+            source_archive: archive_file.clone(),
+        });
+    }
+
+    builder
+        .into_inner()
+        .map_err(|e| format!("Failed to finalize snapshot archive: {}", e))?
+        .finish()
+        .map_err(|e| format!("Failed to flush snapshot archive: {}", e))?;
+
+    let manifest = SnapshotManifest {
+        version: SNAPSHOT_SCHEMA_VERSION.to_string(),
+        account_id: folder.account_id.clone(),
+        region: folder.region.clone(),
+        region_code: folder.region_code.clone(),
+        created_at_unix_secs: created_at,
+        archive_file,
+        entries,
+    };
+
+    let manifest_path = dest.join(format!("snapshot-{}.json", created_at));
+    let data = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize snapshot manifest: {}", e))?;
+    fs::write(&manifest_path, data)
+        .map_err(|e| format!("Failed to write snapshot manifest: {}", e))?;
+
+    Ok(manifest)
+}
+
+/// Parse every `snapshot-*.json` manifest in `dest`, oldest first.
+pub fn list_snapshots(dest: &Path) -> Result<Vec<SnapshotManifest>, String> {
+    if !dest.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(dest).map_err(|e| format!("Failed to read snapshot directory: {}", e))?;
+
+    let mut manifests = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json")
+            || !path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .is_some_and(|s| s.starts_with("snapshot-"))
+        {
+            continue;
+        }
+
+        let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let manifest: SnapshotManifest = serde_json::from_slice(&data)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        manifests.push(manifest);
+    }
+
+    manifests.sort_by_key(|m| m.created_at_unix_secs);
+    Ok(manifests)
+}
+
+/// Return the most recent manifest in `dest`, if any snapshot has been taken yet.
+fn latest_manifest(dest: &Path) -> Result<Option<SnapshotManifest>, String> {
+    Ok(list_snapshots(dest)?.into_iter().next_back())
+}
+
+/// Recreate `manifest`'s folder structure under `target`, pulling each
+/// file's bytes from whichever archive in `dest` its entry names (see
+/// [`SnapshotEntry::source_archive`]). Returns the number of files restored.
+///
+/// Treats the archive as untrusted input, same as
+/// [`crate::replay_tracker`]'s own tar extraction: every entry's path is
+/// traversal-checked via [`crate::replay_tracker::normalized_relative_path`]
+/// before it's joined onto `target`, so a crafted `..`/absolute entry name
+/// can't escape the restore target. An entry that fails the check is skipped
+/// with a warning rather than extracted.
+pub fn restore_snapshot(manifest: &SnapshotManifest, dest: &Path, target: &Path) -> Result<usize, String> {
+    fs::create_dir_all(target).map_err(|e| format!("Failed to create restore target: {}", e))?;
+
+    let mut wanted_by_archive: HashMap<&str, HashSet<String>> = HashMap::new();
+    for entry in &manifest.entries {
+        wanted_by_archive
+            .entry(entry.source_archive.as_str())
+            .or_default()
+            .insert(format!("replays/{}", entry.relative_path));
+    }
+
+    let mut restored = 0;
+    for (archive_file, wanted_names) in wanted_by_archive {
+        let archive_path = dest.join(archive_file);
+        let file = fs::File::open(&archive_path)
+            .map_err(|e| format!("Failed to open snapshot archive {}: {}", archive_path.display(), e))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+        let tar_entries = archive
+            .entries()
+            .map_err(|e| format!("Failed to read snapshot archive {}: {}", archive_path.display(), e))?;
+        for tar_entry in tar_entries {
+            let mut tar_entry = tar_entry.map_err(|e| format!("Failed to read archive entry: {}", e))?;
+            let name_in_tar = tar_entry
+                .path()
+                .map_err(|e| format!("Invalid path in archive: {}", e))?
+                .to_string_lossy()
+                .to_string();
+            if !wanted_names.contains(&name_in_tar) {
+                continue;
+            }
+
+            let stripped = name_in_tar.strip_prefix("replays/").unwrap_or(&name_in_tar);
+            let Some(relative) = crate::replay_tracker::normalized_relative_path(Path::new(stripped)) else {
+                eprintln!("Warning: skipping archive entry with an unsafe path: {}", name_in_tar);
+                continue;
+            };
+            let out_path = target.join(&relative);
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create directory {}: {}", parent.display(), e))?;
+            }
+            tar_entry
+                .unpack(&out_path)
+                .map_err(|e| format!("Failed to extract {}: {}", relative.display(), e))?;
+            restored += 1;
+        }
+    }
+
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn fake_folder(path: PathBuf) -> SC2ReplayFolder {
+        SC2ReplayFolder {
+            path,
+            account_id: "12345678".to_string(),
+            region: "NA".to_string(),
+            region_code: "1-S2-1-123456".to_string(),
+            account_folder: crate::sc2_detector::parse_account_folder_name("1-S2-1-123456"),
+        }
+    }
+
+    /// Build a gzip-compressed tar with the given raw `(entry_name, contents)`
+    /// pairs, bypassing `create_snapshot` so tests can craft entries a real
+    /// snapshot would never produce (path traversal, ...).
+    fn build_raw_tar_gz(path: &Path, entries: &[(&str, &[u8])]) {
+        let file = fs::File::create(path).unwrap();
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, *name, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_restore_snapshot_rejects_path_traversal_entry() {
+        let dest = TempDir::new().unwrap();
+        let archive_path = dest.path().join("snapshot-1.tar.gz");
+        build_raw_tar_gz(
+            &archive_path,
+            &[("replays/../../../../etc/evil.SC2Replay", b"pwn")],
+        );
+
+        let manifest = SnapshotManifest {
+            version: SNAPSHOT_SCHEMA_VERSION.to_string(),
+            account_id: "12345678".to_string(),
+            region: "NA".to_string(),
+            region_code: "1-S2-1-123456".to_string(),
+            created_at_unix_secs: 1,
+            archive_file: "snapshot-1.tar.gz".to_string(),
+            entries: vec![SnapshotEntry {
+                relative_path: "../../../../etc/evil.SC2Replay".to_string(),
+                size: 3,
+                modified_unix_secs: 1,
+                hash: "deadbeef".to_string(),
+                source_archive: "snapshot-1.tar.gz".to_string(),
+            }],
+        };
+
+        let target = TempDir::new().unwrap();
+        let restored = restore_snapshot(&manifest, dest.path(), target.path()).unwrap();
+        assert_eq!(restored, 0, "traversal entry must not be extracted");
+        assert!(
+            !target.path().join("../../../../etc/evil.SC2Replay").exists(),
+            "traversal entry must not be written outside target"
+        );
+    }
+
+    #[test]
+    fn test_create_snapshot_roundtrip() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("game1.SC2Replay"), b"replay one").unwrap();
+        let folder = fake_folder(source.path().to_path_buf());
+
+        let dest = TempDir::new().unwrap();
+        let manifest = create_snapshot(&folder, dest.path()).unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.account_id, "12345678");
+        assert_eq!(manifest.region, "NA");
+
+        let target = TempDir::new().unwrap();
+        let restored = restore_snapshot(&manifest, dest.path(), target.path()).unwrap();
+        assert_eq!(restored, 1);
+        assert_eq!(
+            fs::read(target.path().join("game1.SC2Replay")).unwrap(),
+            b"replay one"
+        );
+    }
+
+    #[test]
+    fn test_list_snapshots_returns_oldest_first() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("game1.SC2Replay"), b"one").unwrap();
+        let folder = fake_folder(source.path().to_path_buf());
+        let dest = TempDir::new().unwrap();
+
+        let first = create_snapshot(&folder, dest.path()).unwrap();
+        fs::write(source.path().join("game2.SC2Replay"), b"two").unwrap();
+        let second = create_snapshot(&folder, dest.path()).unwrap();
+
+        let snapshots = list_snapshots(dest.path()).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].created_at_unix_secs, first.created_at_unix_secs);
+        assert_eq!(snapshots[1].created_at_unix_secs, second.created_at_unix_secs);
+    }
+
+    #[test]
+    fn test_create_snapshot_skips_unchanged_files() {
+        let source = TempDir::new().unwrap();
+        fs::write(source.path().join("game1.SC2Replay"), b"replay one").unwrap();
+        let folder = fake_folder(source.path().to_path_buf());
+        let dest = TempDir::new().unwrap();
+
+        let first = create_snapshot(&folder, dest.path()).unwrap();
+        let second = create_snapshot(&folder, dest.path()).unwrap();
+
+        // Unchanged file's entry should still point at the first snapshot's
+        // archive rather than being re-copied into the second.
+        assert_eq!(second.entries.len(), 1);
+        assert_eq!(second.entries[0].source_archive, first.archive_file);
+        assert_eq!(second.entries[0].hash, first.entries[0].hash);
+    }
+
+    #[test]
+    fn test_list_snapshots_empty_dest() {
+        let dest = TempDir::new().unwrap();
+        assert!(list_snapshots(dest.path()).unwrap().is_empty());
+    }
+}
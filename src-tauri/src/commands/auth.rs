@@ -12,6 +12,9 @@ pub async fn request_device_code(
     state_manager.debug_logger.info("Requesting device code for authentication".to_string());
     match state_manager.api_client.request_device_code().await {
         Ok(response) => {
+            // Mask the device code and PKCE verifier everywhere they might be logged.
+            state_manager.debug_logger.register_secret(&response.device_code);
+            state_manager.debug_logger.register_secret(&response.code_verifier);
             state_manager.debug_logger.info(format!("Device code received, expires in {}s", response.expires_in));
             Ok(response)
         }
@@ -27,9 +30,14 @@ pub async fn request_device_code(
 pub async fn poll_device_authorization(
     state_manager: State<'_, AppStateManager>,
     device_code: String,
+    code_verifier: String,
 ) -> Result<device_auth::AuthResponse, String> {
     state_manager.debug_logger.debug("Polling for device authorization".to_string());
-    match state_manager.api_client.poll_authorization(&device_code).await {
+    match state_manager
+        .api_client
+        .poll_authorization(&device_code, &code_verifier)
+        .await
+    {
         Ok(response) => {
             state_manager.debug_logger.info(format!("Authorization successful for user: {}", response.user.username));
             Ok(response)
@@ -46,6 +54,43 @@ pub async fn poll_device_authorization(
     }
 }
 
+/// Poll for device authorization until it completes, fails, or expires.
+///
+/// Unlike [`poll_device_authorization`], which performs one poll per
+/// invocation and leaves the sleep/backoff/slow-down bookkeeping to the
+/// caller, this delegates to [`device_auth::ApiClient::await_authorization`],
+/// which owns that loop itself and blocks for the duration of the device
+/// flow.
+#[tauri::command]
+pub async fn await_device_authorization(
+    state_manager: State<'_, AppStateManager>,
+    device: device_auth::DeviceCodeResponse,
+) -> Result<device_auth::AuthResponse, String> {
+    state_manager.debug_logger.info("Awaiting device authorization".to_string());
+    match state_manager.api_client.await_authorization(&device).await {
+        Ok(response) => {
+            state_manager.debug_logger.info(format!("Authorization successful for user: {}", response.user.username));
+            Ok(response)
+        }
+        Err(e) => {
+            state_manager.debug_logger.error(format!("Authorization failed: {}", e));
+            Err(e)
+        }
+    }
+}
+
+/// Refresh the stored access token now and re-arm the background timer.
+///
+/// Delegates to [`crate::session::refresh_once`], which exchanges the stored
+/// refresh token, persists the rotated tokens, and updates the app state; on
+/// failure the app is moved to [`AppState::NeedsAuth`](crate::state::AppState).
+#[tauri::command]
+pub async fn refresh_auth_token(app: tauri::AppHandle) -> Result<(), String> {
+    crate::session::refresh_once(&app).await.map(|_| ())?;
+    crate::session::start(app);
+    Ok(())
+}
+
 /// Verify if an auth token is still valid
 #[tauri::command]
 pub async fn verify_auth_token(
@@ -0,0 +1,77 @@
+//! Replay folder backup/restore commands.
+//!
+//! Snapshotting and restoring happen off the async runtime's worker threads
+//! via `spawn_blocking`, the same way [`crate::commands::detection`] handles
+//! the disk-heavy folder detection scan.
+
+use crate::backup::{self, SnapshotManifest};
+use crate::sc2_detector;
+use crate::state::AppStateManager;
+use std::path::PathBuf;
+use tauri::State;
+
+/// Snapshot every currently detected SC2 replay folder into `dest`, one
+/// sub-directory per `account_id/region_code` so accounts and regions never
+/// collide. Re-runs detection rather than trusting a caller-supplied folder
+/// list, so the account_id/region recorded in each manifest is always
+/// accurate.
+#[tauri::command]
+pub async fn create_replay_backup(
+    state_manager: State<'_, AppStateManager>,
+    dest: String,
+) -> Result<Vec<SnapshotManifest>, String> {
+    let logger = state_manager.debug_logger.clone();
+    let dest = PathBuf::from(dest);
+
+    tokio::task::spawn_blocking(move || {
+        let folders = sc2_detector::detect_all_sc2_folders(Some(logger));
+        folders
+            .iter()
+            .map(|folder| {
+                let folder_dest = dest.join(&folder.account_id).join(&folder.region_code);
+                backup::create_snapshot(folder, &folder_dest)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Backup task failed: {}", e))?
+}
+
+/// List the snapshots taken so far for one account/region under `dest`,
+/// oldest first.
+#[tauri::command]
+pub async fn list_replay_backups(
+    dest: String,
+    account_id: String,
+    region_code: String,
+) -> Result<Vec<SnapshotManifest>, String> {
+    let snapshot_dir = PathBuf::from(dest).join(account_id).join(region_code);
+    tokio::task::spawn_blocking(move || backup::list_snapshots(&snapshot_dir))
+        .await
+        .map_err(|e| format!("List snapshots task failed: {}", e))?
+}
+
+/// Restore the snapshot matching `created_at_unix_secs` for one
+/// account/region under `dest` into `target`. Returns the number of files
+/// restored.
+#[tauri::command]
+pub async fn restore_replay_backup(
+    dest: String,
+    account_id: String,
+    region_code: String,
+    created_at_unix_secs: u64,
+    target: String,
+) -> Result<usize, String> {
+    let snapshot_dir = PathBuf::from(dest).join(account_id).join(region_code);
+    let target = PathBuf::from(target);
+
+    tokio::task::spawn_blocking(move || {
+        let manifest = backup::list_snapshots(&snapshot_dir)?
+            .into_iter()
+            .find(|m| m.created_at_unix_secs == created_at_unix_secs)
+            .ok_or_else(|| format!("No snapshot taken at {}", created_at_unix_secs))?;
+        backup::restore_snapshot(&manifest, &snapshot_dir, &target)
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+}
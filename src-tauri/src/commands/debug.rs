@@ -39,6 +39,159 @@ pub async fn export_debug_log(
     Ok(log_path.to_string_lossy().to_string())
 }
 
+/// Gather diagnostics into a compressed, shippable bundle and return its path.
+///
+/// Mirrors [`export_debug_log`] for the state it collects, but produces a single
+/// `.tar.gz` (report plus rotated logs) rather than a bare JSON.
+async fn build_debug_bundle(
+    state_manager: &State<'_, AppStateManager>,
+) -> Result<std::path::PathBuf, String> {
+    let replay_folder = load_folder_path(state_manager.clone()).await.ok().flatten();
+    let discord_user_id = load_auth_tokens(state_manager.clone())
+        .await
+        .ok()
+        .flatten()
+        .and_then(|tokens| tokens.user)
+        .map(|user| user.username);
+    let replays_found = if let Some(ref folder) = replay_folder {
+        std::path::Path::new(folder)
+            .read_dir()
+            .ok()
+            .map(|entries| entries.filter_map(|e| e.ok()).count())
+    } else {
+        None
+    };
+
+    state_manager
+        .debug_logger
+        .create_debug_bundle(replay_folder, replays_found, discord_user_id)
+}
+
+/// Create a compressed debug bundle on disk and return its path.
+#[tauri::command]
+pub async fn create_debug_bundle(
+    state_manager: State<'_, AppStateManager>,
+) -> Result<String, String> {
+    let path = build_debug_bundle(&state_manager).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Create and upload a debug bundle in one step, returning the support
+/// reference id on success or the error string on failure.
+///
+/// The frontend awaits this command directly: the returned future resolves with
+/// the reference id or rejects with the error, so diagnostics can be submitted
+/// with a single click. Dropping the awaiting call cancels the in-flight upload.
+#[tauri::command]
+pub async fn upload_debug_bundle(
+    state_manager: State<'_, AppStateManager>,
+) -> Result<String, String> {
+    let access_token = load_auth_tokens(state_manager.clone())
+        .await
+        .ok()
+        .flatten()
+        .map(|tokens| tokens.access_token.expose_secret().to_string())
+        .ok_or("Not authenticated; cannot upload debug bundle")?;
+
+    let path = build_debug_bundle(&state_manager).await?;
+
+    state_manager.debug_logger.info("Uploading debug bundle to support".to_string());
+    let reference_id = state_manager
+        .api_client
+        .upload_debug_bundle(&access_token, &path)
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?;
+
+    state_manager
+        .debug_logger
+        .info(format!("Debug bundle uploaded (reference {})", reference_id));
+    Ok(reference_id)
+}
+
+/// Set the minimum level persisted to the on-disk log.
+///
+/// Accepts `DEBUG`, `INFO`, `WARN`, `ERROR`, or `FATAL` (case-insensitive).
+/// Raising to `DEBUG` captures verbose diagnostics on disk without flooding the
+/// console, which stays at INFO and above.
+#[tauri::command]
+pub async fn set_log_level(
+    state_manager: State<'_, AppStateManager>,
+    level: String,
+) -> Result<(), String> {
+    use crate::debug_logger::LogLevel;
+    let parsed = match level.to_uppercase().as_str() {
+        "DEBUG" => LogLevel::Debug,
+        "INFO" => LogLevel::Info,
+        "WARN" => LogLevel::Warn,
+        "ERROR" => LogLevel::Error,
+        "FATAL" => LogLevel::Fatal,
+        other => return Err(format!("Unknown log level: {}", other)),
+    };
+    state_manager.debug_logger.set_min_level(parsed);
+    state_manager.debug_logger.info(format!("On-disk log level set to {}", level.to_uppercase()));
+    Ok(())
+}
+
+/// Stream log entries to a named event, honoring the requested mode.
+///
+/// `Snapshot` emits the currently-buffered entries and returns. `Subscribe`
+/// and `SnapshotThenSubscribe` spawn a background task that forwards live
+/// entries to `event` until the app exits, so the debug panel can show history
+/// immediately and then follow upload activity without polling. Returns the
+/// number of buffered entries emitted synchronously before live forwarding.
+#[tauri::command]
+pub async fn stream_logs(
+    app: tauri::AppHandle,
+    state_manager: State<'_, AppStateManager>,
+    mode: crate::debug_logger::LogStreamMode,
+    event: String,
+) -> Result<usize, String> {
+    use crate::debug_logger::LogStreamMode;
+    use tauri::Emitter;
+
+    // Subscribe before snapshotting so an entry logged between the two steps is
+    // delivered live rather than dropped.
+    let mut receiver = match mode {
+        LogStreamMode::Snapshot => None,
+        LogStreamMode::Subscribe | LogStreamMode::SnapshotThenSubscribe => {
+            Some(state_manager.debug_logger.subscribe())
+        }
+    };
+
+    let emitted = if matches!(mode, LogStreamMode::Snapshot | LogStreamMode::SnapshotThenSubscribe) {
+        let snapshot = state_manager.debug_logger.snapshot();
+        for entry in &snapshot {
+            if let Err(e) = app.emit(&event, entry) {
+                state_manager.debug_logger.warn(format!("Failed to emit {}: {}", event, e));
+            }
+        }
+        snapshot.len()
+    } else {
+        0
+    };
+
+    if let Some(mut receiver) = receiver.take() {
+        let logger = std::sync::Arc::clone(&state_manager.debug_logger);
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(entry) => {
+                        if let Err(e) = app.emit(&event, &entry) {
+                            logger.warn(format!("Failed to emit {}: {}", event, e));
+                        }
+                    }
+                    // Fell behind the ring; resume from the next entry.
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    // Sender gone (app shutting down); stop forwarding.
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    Ok(emitted)
+}
+
 /// Get debug log statistics
 #[tauri::command]
 pub async fn get_debug_stats(
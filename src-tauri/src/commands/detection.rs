@@ -9,7 +9,15 @@ use super::folders::save_folder_paths;
 #[tauri::command]
 pub async fn detect_replay_folders(state_manager: State<'_, AppStateManager>) -> Result<Vec<String>, String> {
     state_manager.debug_logger.info("Starting SC2 folder detection".to_string());
-    let folders = sc2_detector::detect_all_sc2_folders(Some(state_manager.debug_logger.clone()));
+
+    // The scan walks several candidate directories off disk, so run it on a
+    // blocking thread to keep the async runtime's workers free.
+    let logger = state_manager.debug_logger.clone();
+    let folders = tokio::task::spawn_blocking(move || {
+        sc2_detector::detect_all_sc2_folders(Some(logger))
+    })
+    .await
+    .map_err(|e| format!("Folder detection task failed: {}", e))?;
 
     if folders.is_empty() {
         state_manager.debug_logger.warn("Could not find any SC2 folders".to_string());
@@ -20,6 +28,15 @@ pub async fn detect_replay_folders(state_manager: State<'_, AppStateManager>) ->
         .map(|f| f.path.to_string_lossy().to_string())
         .collect();
 
+    // Persist each detected folder's account_id/region/region_code so the
+    // `folders` table reflects real detection runs; an already-known folder
+    // keeps whatever `enabled`/`groups` the user has set for it.
+    for folder in &folders {
+        if let Err(e) = state_manager.db.upsert_folder(folder) {
+            state_manager.debug_logger.warn(format!("Failed to record detected folder: {}", e));
+        }
+    }
+
     state_manager.debug_logger.info(format!("Found {} SC2 folder(s)", paths.len()));
     for path in &paths {
         state_manager.debug_logger.debug(format!("  - {}", path));
@@ -28,7 +28,7 @@ pub async fn pick_replay_folder_manual(
                 return Err("Selected folder does not exist".to_string());
             }
 
-            if let Err(e) = save_folder_path(state_manager.clone(), &path_str).await {
+            if let Err(e) = save_folder_path(state_manager.clone(), app.clone(), &path_str).await {
                 state_manager.debug_logger.warn(format!("Failed to save folder path: {}", e));
             }
             state_manager.debug_logger.info(format!("Saved folder path: {}", path_str));
@@ -41,30 +41,54 @@ pub async fn pick_replay_folder_manual(
     }
 }
 
-/// Save multiple replay folder paths to config
+/// Save multiple replay folder paths to config. If a folder watcher is
+/// currently running, it's restarted against the new list so additions and
+/// removals take effect without an app restart.
 #[tauri::command]
 pub async fn save_folder_paths(
     state_manager: State<'_, AppStateManager>,
+    app: tauri::AppHandle,
     paths: Vec<String>,
 ) -> Result<(), String> {
     state_manager.debug_logger.info(format!("Saving {} folder path(s)", paths.len()));
     let config = serde_json::json!({ "replay_folders": paths });
 
     config_utils::save_config_file("config.json", &config)
+        .await
         .inspect_err(|e| {
             state_manager.debug_logger.error(e.clone());
         })?;
 
     state_manager.debug_logger.debug("Folder paths saved successfully".to_string());
+
+    let manager = {
+        let upload_manager = state_manager.upload_manager.lock()
+            .map_err(|_| "Upload manager mutex poisoned")?;
+        upload_manager.as_ref().cloned()
+    };
+    if let Some(manager) = manager {
+        let folders: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+        manager.set_replay_folders(folders);
+
+        if manager.get_state().is_watching {
+            state_manager.debug_logger.info("Folder list changed, restarting watcher".to_string());
+            manager.stop_watching();
+            if let Err(e) = crate::commands::upload::start_watch_internal(manager, app).await {
+                state_manager.debug_logger.warn(format!("Failed to restart folder watcher: {}", e));
+            }
+        }
+    }
+
     Ok(())
 }
 
 /// Legacy function for backwards compatibility - saves single path as array
 pub async fn save_folder_path(
     state_manager: State<'_, AppStateManager>,
+    app: tauri::AppHandle,
     path: &str,
 ) -> Result<(), String> {
-    save_folder_paths(state_manager, vec![path.to_string()]).await
+    save_folder_paths(state_manager, app, vec![path.to_string()]).await
 }
 
 /// Load all replay folder paths from config
@@ -73,6 +97,7 @@ pub async fn load_folder_paths(state_manager: State<'_, AppStateManager>) -> Res
     state_manager.debug_logger.debug("Loading folder paths from config".to_string());
 
     let config: Option<serde_json::Value> = config_utils::load_config_file("config.json")
+        .await
         .inspect_err(|e| {
             state_manager.debug_logger.error(e.clone());
         })?;
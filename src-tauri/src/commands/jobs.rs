@@ -0,0 +1,88 @@
+//! Tracked background scan-and-upload job commands.
+//!
+//! Unlike [`crate::commands::upload::scan_and_upload_replays`], which runs a
+//! single batch and returns when it's done, these commands start a job that
+//! keeps running in the background, reports progress via
+//! [`crate::services::job_manager::JOB_PROGRESS_EVENT`], and can be cancelled
+//! or polled independently of the command invocation that started it.
+
+use crate::services::job_manager::JobReport;
+use crate::state::AppStateManager;
+use crate::upload_manager::{fetch_player_names, SCHEDULER_SCAN_LIMIT};
+use std::sync::Arc;
+use tauri::State;
+
+/// Start a tracked scan-and-upload job in the background, returning
+/// immediately. Fails if the upload manager hasn't been initialized yet, or
+/// if a job is already running.
+#[tauri::command]
+pub async fn start_scan_job(
+    app: tauri::AppHandle,
+    state_manager: State<'_, AppStateManager>,
+    full_rescan: bool,
+) -> Result<(), String> {
+    if let Some(report) = state_manager.job_manager.current_report() {
+        if report.state == crate::services::job_manager::JobState::Running {
+            return Err("A scan job is already running".to_string());
+        }
+    }
+
+    // Clone the Arc to avoid holding the lock across the awaits below.
+    let manager = {
+        let upload_manager = state_manager.upload_manager.lock()
+            .map_err(|_| "Upload manager mutex poisoned")?;
+        match upload_manager.as_ref() {
+            Some(m) => Arc::clone(m),
+            None => return Err("Upload manager not initialized".to_string()),
+        }
+    };
+
+    let tracker = manager.tracker_snapshot()?;
+    let uploader = manager.uploader();
+    let player_names = fetch_player_names(&uploader, &state_manager.debug_logger).await;
+    let replay_folders = manager.replay_folders();
+
+    let job_manager = Arc::clone(&state_manager.job_manager);
+
+    state_manager
+        .debug_logger
+        .info("Starting tracked scan job".to_string());
+
+    tauri::async_runtime::spawn(async move {
+        job_manager
+            .run_scan_job(
+                app,
+                replay_folders,
+                tracker,
+                uploader,
+                player_names,
+                SCHEDULER_SCAN_LIMIT,
+                full_rescan,
+            )
+            .await;
+    });
+
+    Ok(())
+}
+
+/// Request cancellation of the currently running tracked job, if any. Reuses
+/// the same flag as [`crate::commands::queue::cancel_current_upload`]; the
+/// job checks it between the scan step and before every upload.
+#[tauri::command]
+pub fn cancel_job(state_manager: State<'_, AppStateManager>) {
+    state_manager
+        .upload_cancelled
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    state_manager
+        .debug_logger
+        .info("Scan job cancellation requested".to_string());
+}
+
+/// Return the most recently known report for the tracked job, if one has
+/// ever run this install.
+#[tauri::command]
+pub async fn get_job_report(
+    state_manager: State<'_, AppStateManager>,
+) -> Result<Option<JobReport>, String> {
+    Ok(state_manager.job_manager.current_report())
+}
@@ -7,10 +7,13 @@
 //! in the `generate_handler!` macro in lib.rs.
 
 pub mod auth;
+pub mod backup;
 pub mod browser;
 pub mod debug;
 pub mod detection;
 pub mod folders;
+pub mod jobs;
+pub mod queue;
 pub mod settings;
 pub mod state_cmd;
 pub mod tokens;
@@ -0,0 +1,76 @@
+//! Upload queue inspection and control commands.
+//!
+//! These commands let the frontend render the durable upload queue and control
+//! it: inspect pending/dead-lettered jobs, pause/resume draining, cancel the
+//! in-flight batch, and requeue a dead-lettered job for another round of
+//! retries.
+
+use crate::state::AppStateManager;
+use crate::upload_queue::{emit_queue_changed, UploadQueue};
+use tauri::State;
+
+/// Return the current queue state (pending + dead-letter + paused flag).
+#[tauri::command]
+pub async fn get_upload_queue(
+    state_manager: State<'_, AppStateManager>,
+) -> Result<UploadQueue, String> {
+    state_manager.debug_logger.debug("Reading upload queue".to_string());
+    UploadQueue::load().await
+}
+
+/// Pause or resume draining of the queue; the change is persisted immediately.
+#[tauri::command]
+pub async fn set_upload_queue_paused(
+    app: tauri::AppHandle,
+    state_manager: State<'_, AppStateManager>,
+    paused: bool,
+) -> Result<(), String> {
+    let mut queue = UploadQueue::load().await?;
+    queue.paused = paused;
+    queue.save().await?;
+    state_manager
+        .debug_logger
+        .info(format!("Upload queue paused set to {}", paused));
+    emit_queue_changed(Some(&app), &state_manager.debug_logger, &queue);
+    Ok(())
+}
+
+/// Request cancellation of the batch currently running in
+/// [`crate::services::UploadExecutor::execute`], if any. The executor checks
+/// this flag between replays (not mid-upload) and clears it once consumed,
+/// so it never carries over to a later batch.
+#[tauri::command]
+pub fn cancel_current_upload(state_manager: State<'_, AppStateManager>) {
+    state_manager
+        .upload_cancelled
+        .store(true, std::sync::atomic::Ordering::Relaxed);
+    state_manager
+        .debug_logger
+        .info("Upload batch cancellation requested".to_string());
+}
+
+/// Move a dead-lettered job back into the pending list so it is retried from
+/// scratch. Returns `true` if a matching job was found.
+#[tauri::command]
+pub async fn retry_dead_lettered_job(
+    app: tauri::AppHandle,
+    state_manager: State<'_, AppStateManager>,
+    hash: String,
+) -> Result<bool, String> {
+    let mut queue = UploadQueue::load().await?;
+    if let Some(pos) = queue.dead_letter.iter().position(|j| j.hash == hash) {
+        let mut job = queue.dead_letter.remove(pos);
+        job.attempts = 0;
+        job.next_attempt_at = 0;
+        job.last_error = None;
+        queue.pending.push(job);
+        queue.save().await?;
+        state_manager
+            .debug_logger
+            .info(format!("Requeued dead-lettered upload job {}", hash));
+        emit_queue_changed(Some(&app), &state_manager.debug_logger, &queue);
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
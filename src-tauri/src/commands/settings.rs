@@ -1,43 +1,17 @@
 //! Application settings commands (autostart, etc).
 
-use std::fs;
 use tauri::State;
 use tauri_plugin_autostart::ManagerExt;
+use crate::app_config::AppConfig;
 use crate::state::AppStateManager;
 
 /// Check if autostart is enabled
 #[tauri::command]
 pub async fn get_autostart_enabled(state_manager: State<'_, AppStateManager>) -> Result<bool, String> {
     state_manager.debug_logger.debug("Getting autostart enabled status".to_string());
-    let config_dir = dirs::config_dir()
-        .ok_or("Could not find config directory")?;
-    let config_file = config_dir.join("ladder-legends-uploader").join("config.json");
-
-    if !config_file.exists() {
-        state_manager.debug_logger.debug("No config file, autostart defaulting to disabled".to_string());
-        return Ok(false); // Default to disabled
-    }
-
-    let contents = fs::read_to_string(&config_file)
-        .map_err(|e| {
-            let error_msg = format!("Failed to read config: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
-
-    let config: serde_json::Value = serde_json::from_str(&contents)
-        .map_err(|e| {
-            let error_msg = format!("Failed to parse config: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
-
-    let enabled = config.get("autostart_enabled")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
-
-    state_manager.debug_logger.debug(format!("Autostart enabled: {}", enabled));
-    Ok(enabled)
+    let config = crate::app_config::load_app_config(&state_manager.debug_logger).await;
+    state_manager.debug_logger.debug(format!("Autostart enabled: {}", config.autostart_enabled));
+    Ok(config.autostart_enabled)
 }
 
 /// Enable or disable autostart
@@ -69,56 +43,217 @@ pub async fn set_autostart_enabled(
         state_manager.debug_logger.debug("Autostart disabled in system".to_string());
     }
 
-    // Save preference to config for persistence
-    let config_dir = dirs::config_dir()
-        .ok_or("Could not find config directory")?;
-    let app_config_dir = config_dir.join("ladder-legends-uploader");
-    fs::create_dir_all(&app_config_dir)
-        .map_err(|e| {
-            let error_msg = format!("Failed to create config directory: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
-
-    let config_file = app_config_dir.join("config.json");
-
-    // Read existing config or create new one
-    let mut config: serde_json::Value = if config_file.exists() {
-        let contents = fs::read_to_string(&config_file)
-            .map_err(|e| {
-                let error_msg = format!("Failed to read config: {}", e);
-                state_manager.debug_logger.error(error_msg.clone());
-                error_msg
-            })?;
-        serde_json::from_str(&contents)
-            .map_err(|e| {
-                let error_msg = format!("Failed to parse config: {}", e);
-                state_manager.debug_logger.error(error_msg.clone());
-                error_msg
-            })?
-    } else {
-        serde_json::json!({})
-    };
+    // Persist the preference through the typed config so a single field change
+    // doesn't re-parse and pretty-print the whole file by hand.
+    let mut config = crate::app_config::load_app_config(&state_manager.debug_logger).await;
+    config.autostart_enabled = enabled;
+    crate::app_config::save_app_config(&config)
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?;
+
+    state_manager.debug_logger.debug("Autostart preference saved to config".to_string());
+    Ok(())
+}
+
+/// Get the full typed application config.
+///
+/// The front end calls this instead of round-tripping loose JSON; corrupt
+/// files are self-healed to defaults by [`crate::app_config::load_app_config`].
+#[tauri::command]
+pub async fn get_config(state_manager: State<'_, AppStateManager>) -> Result<AppConfig, String> {
+    state_manager.debug_logger.debug("Getting application config".to_string());
+    Ok(crate::app_config::load_app_config(&state_manager.debug_logger).await)
+}
+
+/// Replace the persisted application config, written atomically.
+#[tauri::command]
+pub async fn update_config(
+    state_manager: State<'_, AppStateManager>,
+    config: AppConfig,
+) -> Result<(), String> {
+    state_manager.debug_logger.info("Updating application config".to_string());
+    crate::app_config::save_app_config(&config)
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?;
+    Ok(())
+}
+
+/// Read the persisted proxy URL from `config.json`, if any.
+///
+/// Returns `None` when no proxy is configured, which the upload manager treats
+/// as "use the system proxy" (reqwest then honours `HTTP_PROXY` / `HTTPS_PROXY`
+/// / `NO_PROXY`). An empty or whitespace-only value is normalised to `None`.
+pub(crate) async fn load_proxy_url() -> Option<String> {
+    let config: Option<serde_json::Value> =
+        crate::config_utils::load_config_file("config.json").await.ok().flatten();
+    config
+        .as_ref()
+        .and_then(|c| c.get("proxy_url"))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Get the configured upload proxy URL, or `None` for "use system proxy".
+#[tauri::command]
+pub async fn get_proxy_url(state_manager: State<'_, AppStateManager>) -> Result<Option<String>, String> {
+    state_manager.debug_logger.debug("Getting proxy URL from config".to_string());
+    Ok(load_proxy_url().await)
+}
+
+/// Persist the upload proxy URL to `config.json`.
+///
+/// Passing `None` (or an empty string) clears the explicit proxy and falls back
+/// to the system proxy environment variables on the next client build.
+#[tauri::command]
+pub async fn set_proxy_url(
+    state_manager: State<'_, AppStateManager>,
+    proxy_url: Option<String>,
+) -> Result<(), String> {
+    let normalised = proxy_url
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    state_manager.debug_logger.info(format!(
+        "Setting upload proxy to: {}",
+        normalised.as_deref().unwrap_or("<system>")
+    ));
+
+    // Read-modify-write so we don't clobber the folder paths or other keys.
+    let mut config: serde_json::Value = crate::config_utils::load_config_file("config.json")
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?
+        .unwrap_or_else(|| serde_json::json!({}));
 
-    // Update autostart_enabled field
     if let Some(obj) = config.as_object_mut() {
-        obj.insert("autostart_enabled".to_string(), serde_json::Value::Bool(enabled));
+        match &normalised {
+            Some(url) => {
+                obj.insert("proxy_url".to_string(), serde_json::Value::String(url.clone()));
+            }
+            None => {
+                obj.remove("proxy_url");
+            }
+        }
     }
 
-    let config_json = serde_json::to_string_pretty(&config)
-        .map_err(|e| {
-            let error_msg = format!("Failed to serialize config: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
+    crate::config_utils::save_config_file("config.json", &config)
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?;
 
-    fs::write(&config_file, config_json)
-        .map_err(|e| {
-            let error_msg = format!("Failed to save config: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
+    state_manager.debug_logger.debug("Proxy setting saved to config".to_string());
+    Ok(())
+}
+
+/// Default periodic re-scan interval in seconds (15 minutes) used when the
+/// config has no explicit value.
+pub(crate) const DEFAULT_SCAN_INTERVAL_SECS: u64 = 15 * 60;
+
+/// Smallest interval we allow so a misconfigured value can't hammer the server.
+pub(crate) const MIN_SCAN_INTERVAL_SECS: u64 = 60;
+
+/// Read the persisted periodic-scan interval, falling back to the default.
+pub(crate) async fn load_scan_interval_secs() -> u64 {
+    let config: Option<serde_json::Value> =
+        crate::config_utils::load_config_file("config.json").await.ok().flatten();
+    config
+        .as_ref()
+        .and_then(|c| c.get("scan_interval_secs"))
+        .and_then(|v| v.as_u64())
+        .map(|secs| secs.max(MIN_SCAN_INTERVAL_SECS))
+        .unwrap_or(DEFAULT_SCAN_INTERVAL_SECS)
+}
+
+/// Get the periodic background-scan interval in seconds.
+#[tauri::command]
+pub async fn get_scan_interval(state_manager: State<'_, AppStateManager>) -> Result<u64, String> {
+    state_manager.debug_logger.debug("Getting scan interval from config".to_string());
+    Ok(load_scan_interval_secs().await)
+}
+
+/// Persist the periodic background-scan interval in seconds. Values below
+/// [`MIN_SCAN_INTERVAL_SECS`] are clamped up; the running scheduler picks up
+/// the new value on its next cycle.
+#[tauri::command]
+pub async fn set_scan_interval(
+    state_manager: State<'_, AppStateManager>,
+    seconds: u64,
+) -> Result<(), String> {
+    let seconds = seconds.max(MIN_SCAN_INTERVAL_SECS);
+    state_manager.debug_logger.info(format!("Setting scan interval to {}s", seconds));
+
+    let mut config: serde_json::Value = crate::config_utils::load_config_file("config.json")
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("scan_interval_secs".to_string(), serde_json::json!(seconds));
+    }
+
+    crate::config_utils::save_config_file("config.json", &config)
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?;
 
-    state_manager.debug_logger.debug("Autostart preference saved to config".to_string());
     Ok(())
 }
+
+/// Load the persisted HTTP-client configuration for the [`ReplayUploader`].
+///
+/// Reads the optional `http_client` object from `config.json`, falling back to
+/// [`ReplayUploaderConfig::default`] (today's behaviour) when it is absent or
+/// unparseable. The persisted proxy URL is merged in from the top-level
+/// `proxy_url` key so the existing proxy setting keeps working.
+pub(crate) async fn load_uploader_config() -> crate::replay_uploader::ReplayUploaderConfig {
+    let config: Option<serde_json::Value> =
+        crate::config_utils::load_config_file("config.json").await.ok().flatten();
+
+    let mut uploader_config = config
+        .as_ref()
+        .and_then(|c| c.get("http_client"))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+
+    // The proxy has its own top-level key (get_proxy_url/set_proxy_url); honour
+    // it unless the http_client block already carries an explicit proxy.
+    if uploader_config.proxy_url.is_none() {
+        uploader_config.proxy_url = load_proxy_url().await;
+    }
+
+    uploader_config
+}
+
+/// Persist the HTTP-client configuration under the `http_client` key.
+#[tauri::command]
+pub async fn set_http_client_config(
+    state_manager: State<'_, AppStateManager>,
+    config: crate::replay_uploader::ReplayUploaderConfig,
+) -> Result<(), String> {
+    state_manager.debug_logger.info("Updating HTTP client configuration".to_string());
+
+    let mut stored: serde_json::Value = crate::config_utils::load_config_file("config.json")
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(obj) = stored.as_object_mut() {
+        obj.insert(
+            "http_client".to_string(),
+            serde_json::to_value(&config).map_err(|e| e.to_string())?,
+        );
+    }
+
+    crate::config_utils::save_config_file("config.json", &stored)
+        .await
+        .inspect_err(|e| state_manager.debug_logger.error(e.clone()))?;
+
+    Ok(())
+}
+
+/// Get the persisted HTTP-client configuration.
+#[tauri::command]
+pub async fn get_http_client_config(
+    state_manager: State<'_, AppStateManager>,
+) -> Result<crate::replay_uploader::ReplayUploaderConfig, String> {
+    state_manager.debug_logger.debug("Getting HTTP client configuration".to_string());
+    Ok(load_uploader_config().await)
+}
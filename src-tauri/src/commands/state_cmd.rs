@@ -4,12 +4,31 @@ use tauri::State;
 use crate::state::{AppState, AppStateManager};
 
 /// Get the current application state
+///
+/// Still backed by the in-memory mutex for real-time auth-flow transitions,
+/// but when the app hasn't moved past [`AppState::DetectingFolder`] yet (e.g.
+/// right after a restart) this consults [`crate::db::ReplayDb`] for a
+/// previously enabled folder so a known account doesn't re-run detection
+/// before the folder list even reaches the UI.
 #[tauri::command]
 pub async fn get_app_state(state_manager: State<'_, AppStateManager>) -> Result<AppState, String> {
     state_manager.debug_logger.debug("Getting app state".to_string());
-    let state = state_manager.state.lock()
-        .map_err(|_| "State mutex poisoned")?;
-    Ok(state.clone())
+    let current = {
+        let state = state_manager.state.lock()
+            .map_err(|_| "State mutex poisoned")?;
+        state.clone()
+    };
+
+    if matches!(current, AppState::DetectingFolder) {
+        let folders = state_manager.db.list_folders()
+            .map_err(|e| state_manager.debug_logger.warn(format!("Failed to read folder settings: {}", e)))
+            .unwrap_or_default();
+        if let Some(folder) = folders.into_iter().find(|f| f.enabled) {
+            return Ok(AppState::FolderFound { path: folder.path });
+        }
+    }
+
+    Ok(current)
 }
 
 /// Set the application state
@@ -1,9 +1,15 @@
 //! Auth token storage and management commands.
 
-use std::fs;
 use tauri::State;
-use crate::types::{AuthTokens, UserData};
+use crate::types::{AuthTokens, SecretString, UserData};
 use crate::state::AppStateManager;
+use crate::token_store::SecureTokenStore;
+
+/// Open the encrypted token store backed by `auth.json` in the config dir.
+fn open_store() -> Result<SecureTokenStore, String> {
+    let config_file = crate::config_utils::config_file_path("auth.json")?;
+    SecureTokenStore::open(config_file)
+}
 
 /// Save authentication tokens to persistent storage
 #[tauri::command]
@@ -16,41 +22,32 @@ pub async fn save_auth_tokens(
     avatar_url: Option<String>,
 ) -> Result<(), String> {
     state_manager.debug_logger.info(format!("Saving auth tokens for user: {:?}", username));
-    let config_dir = dirs::config_dir()
-        .ok_or("Could not find config directory")?;
-    let app_config_dir = config_dir.join("ladder-legends-uploader");
-    fs::create_dir_all(&app_config_dir)
-        .map_err(|e| {
-            let error_msg = format!("Failed to create config directory: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
 
-    let config_file = app_config_dir.join("auth.json");
+    // Register the tokens so any incidental logging around token handling is
+    // masked before it reaches the in-memory buffer or an exported report.
+    state_manager.debug_logger.register_secret(&access_token);
+    if let Some(refresh) = refresh_token.as_deref() {
+        state_manager.debug_logger.register_secret(refresh);
+    }
+
     let user = username.map(|un| UserData {
+        id: None,
         username: un,
         avatar_url,
     });
 
     let tokens = AuthTokens {
-        access_token,
-        refresh_token,
+        access_token: SecretString::new(access_token),
+        refresh_token: refresh_token.map(SecretString::new),
         expires_at,
         user,
     };
 
-    let tokens_json = serde_json::to_string_pretty(&tokens)
+    let store = open_store()?;
+    store.save(&tokens)
         .map_err(|e| {
-            let error_msg = format!("Failed to serialize auth tokens: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
-
-    fs::write(&config_file, tokens_json)
-        .map_err(|e| {
-            let error_msg = format!("Failed to save auth tokens: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
+            state_manager.debug_logger.error(e.clone());
+            e
         })?;
 
     state_manager.debug_logger.debug("Auth tokens saved successfully".to_string());
@@ -61,28 +58,18 @@ pub async fn save_auth_tokens(
 #[tauri::command]
 pub async fn load_auth_tokens(state_manager: State<'_, AppStateManager>) -> Result<Option<AuthTokens>, String> {
     state_manager.debug_logger.debug("Loading auth tokens from storage".to_string());
-    let config_dir = dirs::config_dir()
-        .ok_or("Could not find config directory")?;
-    let config_file = config_dir.join("ladder-legends-uploader").join("auth.json");
-
-    if !config_file.exists() {
-        state_manager.debug_logger.debug("No auth tokens file exists yet".to_string());
-        return Ok(None);
-    }
-
-    let contents = fs::read_to_string(&config_file)
-        .map_err(|e| {
-            let error_msg = format!("Failed to read auth tokens: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
-
-    let tokens: AuthTokens = serde_json::from_str(&contents)
-        .map_err(|e| {
-            let error_msg = format!("Failed to parse auth tokens: {}", e);
-            state_manager.debug_logger.error(error_msg.clone());
-            error_msg
-        })?;
+    let store = open_store()?;
+    let tokens = match store.load() {
+        Ok(Some(tokens)) => tokens,
+        Ok(None) => {
+            state_manager.debug_logger.debug("No auth tokens file exists yet".to_string());
+            return Ok(None);
+        }
+        Err(e) => {
+            state_manager.debug_logger.error(e.clone());
+            return Err(e);
+        }
+    };
 
     if let Some(ref user) = tokens.user {
         state_manager.debug_logger.info(format!("Loaded auth tokens for user: {}", user.username));
@@ -93,25 +80,36 @@ pub async fn load_auth_tokens(state_manager: State<'_, AppStateManager>) -> Resu
     Ok(Some(tokens))
 }
 
-/// Clear authentication tokens from storage (logout)
+/// Clear authentication tokens from storage (logout).
+///
+/// Best-effort revokes the stored token with the server first — the refresh
+/// token when one is present, since revoking it also invalidates the access
+/// token issued alongside it, or the access token alone otherwise — so a
+/// shared machine doesn't keep a long-lived credential valid after sign-out.
+/// A failed revoke (e.g. the server is unreachable) doesn't block clearing
+/// local storage; the user is logged out of this device regardless.
 #[tauri::command]
 pub async fn clear_auth_tokens(state_manager: State<'_, AppStateManager>) -> Result<(), String> {
     state_manager.debug_logger.info("Clearing auth tokens".to_string());
-    let config_dir = dirs::config_dir()
-        .ok_or("Could not find config directory")?;
-    let config_file = config_dir.join("ladder-legends-uploader").join("auth.json");
 
-    if config_file.exists() {
-        fs::remove_file(&config_file)
-            .map_err(|e| {
-                let error_msg = format!("Failed to delete auth tokens: {}", e);
-                state_manager.debug_logger.error(error_msg.clone());
-                error_msg
-            })?;
-        state_manager.debug_logger.debug("Auth tokens file deleted".to_string());
-    } else {
-        state_manager.debug_logger.debug("No auth tokens file to delete".to_string());
+    let store = open_store()?;
+    if let Ok(Some(tokens)) = store.load() {
+        let (token, hint) = match &tokens.refresh_token {
+            Some(refresh) => (refresh.expose_secret().to_string(), "refresh_token"),
+            None => (tokens.access_token.expose_secret().to_string(), "access_token"),
+        };
+        if let Err(e) = state_manager.api_client.revoke_token(&token, Some(hint)).await {
+            state_manager
+                .debug_logger
+                .warn(format!("Failed to revoke token on logout: {}", e));
+        }
     }
 
+    store.clear()
+        .map_err(|e| {
+            state_manager.debug_logger.error(e.clone());
+            e
+        })?;
+    state_manager.debug_logger.debug("Auth tokens cleared".to_string());
     Ok(())
 }
@@ -1,7 +1,8 @@
 //! Upload manager commands for scanning and uploading replays.
 
 use std::sync::Arc;
-use tauri::{State, Emitter};
+use tauri::{State, Emitter, Manager};
+use crate::services::SkippedReplay;
 use crate::upload_manager::{UploadManager, UploadManagerState};
 use crate::state::AppStateManager;
 
@@ -12,12 +13,23 @@ pub async fn initialize_upload_manager(
     replay_folders: Vec<String>,
     base_url: String,
     access_token: String,
+    proxy_url: Option<String>,
 ) -> Result<(), String> {
     state_manager.debug_logger.info(format!("Initializing upload manager for {} folder(s)", replay_folders.len()));
     for folder in &replay_folders {
         state_manager.debug_logger.debug(format!("  - {}", folder));
     }
 
+    // Fall back to the persisted proxy when the caller doesn't supply one, so
+    // the setting survives restarts without the frontend having to re-send it.
+    let proxy_url = match proxy_url {
+        Some(url) => Some(url),
+        None => crate::commands::settings::load_proxy_url().await,
+    };
+    if let Some(url) = &proxy_url {
+        state_manager.debug_logger.debug(format!("Using proxy: {}", url));
+    }
+
     let paths: Vec<std::path::PathBuf> = replay_folders.iter()
         .map(std::path::PathBuf::from)
         .collect();
@@ -26,7 +38,9 @@ pub async fn initialize_upload_manager(
         paths,
         base_url.clone(),
         access_token,
+        proxy_url,
         Arc::clone(&state_manager.debug_logger),
+        Arc::clone(&state_manager.db),
     ) {
         Ok(manager) => {
             let mut upload_manager = state_manager.upload_manager.lock()
@@ -65,14 +79,36 @@ pub async fn get_upload_state(
     }
 }
 
+/// Get the replays (and folders) skipped by the most recent scan, with why.
+#[tauri::command]
+pub async fn get_last_scan_skips(
+    state_manager: State<'_, AppStateManager>,
+) -> Result<Vec<SkippedReplay>, String> {
+    let upload_manager = state_manager.upload_manager.lock()
+        .map_err(|_| "Upload manager mutex poisoned")?;
+
+    match upload_manager.as_ref() {
+        Some(manager) => Ok(manager.get_state().last_skipped),
+        None => Err("Upload manager not initialized".to_string()),
+    }
+}
+
 /// Scan for and upload replay files
 #[tauri::command]
 pub async fn scan_and_upload_replays(
     app: tauri::AppHandle,
     state_manager: State<'_, AppStateManager>,
     limit: usize,
+    full_rescan: bool,
 ) -> Result<usize, String> {
-    state_manager.debug_logger.info(format!("Starting replay scan and upload (limit: {})", limit));
+    state_manager.debug_logger.info(format!("Starting replay scan and upload (limit: {}, full_rescan: {})", limit, full_rescan));
+
+    // Respect the tray pause toggle: while paused we neither scan nor upload.
+    if state_manager.uploads_paused.load(std::sync::atomic::Ordering::Relaxed) {
+        state_manager.debug_logger.info("Uploads are paused; skipping scan".to_string());
+        crate::tray::refresh(&app);
+        return Ok(0);
+    }
 
     // Clone the Arc to avoid holding the lock across await
     let manager = {
@@ -87,7 +123,18 @@ pub async fn scan_and_upload_replays(
         }
     };
 
-    match manager.scan_and_upload(limit, &app).await {
+    // Coalesce with the periodic scheduler so the same files aren't scanned
+    // twice concurrently; a caller arriving mid-scan just no-ops.
+    if state_manager.scan_in_progress.swap(true, std::sync::atomic::Ordering::AcqRel) {
+        state_manager.debug_logger.info("A scan is already in progress; coalescing".to_string());
+        return Ok(0);
+    }
+
+    let result = manager.scan_and_upload(limit, full_rescan, &app).await;
+    state_manager.scan_in_progress.store(false, std::sync::atomic::Ordering::Release);
+    // Reflect the post-run state (idle or error) in the tray.
+    crate::tray::refresh(&app);
+    match result {
         Ok(count) => {
             state_manager.debug_logger.info(format!("Scan and upload completed: {} replays uploaded", count));
             Ok(count)
@@ -99,13 +146,74 @@ pub async fn scan_and_upload_replays(
     }
 }
 
-/// Start watching replay folders for new files
+/// Start the watcher on `manager`'s folders: notify the frontend as soon as a
+/// replay settles, then auto-upload it. Shared by the [`start_watch`] command
+/// and by `save_folder_paths`, which restarts the watcher when the folder
+/// list changes.
+pub(crate) async fn start_watch_internal(
+    manager: Arc<UploadManager>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    let app_for_refresh = app.clone();
+    let manager_for_upload = Arc::clone(&manager);
+    let manager_for_watch = Arc::clone(&manager);
+    manager.start_watching(move |path| {
+        // Don't surface or auto-upload new files while paused; the tray
+        // reflects the pause.
+        if app.state::<AppStateManager>().uploads_paused.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        // Recover which configured folder this replay came from so the
+        // event can carry its account_id/region alongside the path, without
+        // having to re-run detection or persist the mapping separately.
+        let owning_folder = manager_for_watch
+            .replay_folders()
+            .into_iter()
+            .find(|folder| path.starts_with(folder));
+        let (account_id, region) = owning_folder
+            .as_deref()
+            .and_then(crate::sc2_detector::folder_account_and_region)
+            .unzip();
+
+        let _ = app.emit("new-replay-detected", serde_json::json!({
+            "path": path.to_string_lossy().to_string(),
+            "account_id": account_id,
+            "region": region,
+        }));
+
+        let manager = Arc::clone(&manager_for_upload);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            // Share the scan guard with the scheduler and the scan command so
+            // they never process this file at the same time; wait out a scan
+            // already in flight (its uploads are then in the tracker).
+            let state_manager = app.state::<AppStateManager>();
+            while state_manager.scan_in_progress.swap(true, std::sync::atomic::Ordering::AcqRel) {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+            let result = manager.upload_paths(vec![path], &app).await;
+            state_manager.scan_in_progress.store(false, std::sync::atomic::Ordering::Release);
+            if let Err(e) = result {
+                state_manager
+                    .debug_logger
+                    .warn(format!("Auto-upload of watched replay failed: {}", e));
+            }
+        });
+    }).await?;
+
+    crate::tray::refresh(&app_for_refresh);
+    Ok(())
+}
+
+/// Start watching replay folders for new files and auto-upload each one as
+/// it settles.
 #[tauri::command]
-pub async fn start_file_watcher(
+pub async fn start_watch(
     state_manager: State<'_, AppStateManager>,
     app: tauri::AppHandle,
 ) -> Result<(), String> {
-    state_manager.debug_logger.info("Starting file watcher for new replays".to_string());
+    state_manager.debug_logger.info("Starting replay folder watcher".to_string());
 
     let manager = {
         let upload_manager = state_manager.upload_manager.lock()
@@ -113,22 +221,75 @@ pub async fn start_file_watcher(
         match upload_manager.as_ref() {
             Some(m) => Arc::clone(m),
             None => {
-                state_manager.debug_logger.error("Upload manager not initialized for file watcher".to_string());
+                state_manager.debug_logger.error("Upload manager not initialized for folder watcher".to_string());
                 return Err("Upload manager not initialized".to_string());
             }
         }
     };
 
-    match manager.start_watching(move |path| {
-        let _ = app.emit("new-replay-detected", path.to_string_lossy().to_string());
-    }).await {
-        Ok(_) => {
-            state_manager.debug_logger.info("File watcher started successfully".to_string());
+    match start_watch_internal(manager, app).await {
+        Ok(()) => {
+            state_manager.debug_logger.info("Folder watcher started successfully".to_string());
             Ok(())
         }
         Err(e) => {
-            state_manager.debug_logger.error(format!("Failed to start file watcher: {}", e));
+            state_manager.debug_logger.error(format!("Failed to start folder watcher: {}", e));
             Err(e)
         }
     }
 }
+
+/// Stop the active folder watcher, if one is running. A no-op if the upload
+/// manager isn't initialized or nothing is currently watching.
+#[tauri::command]
+pub async fn stop_watch(
+    state_manager: State<'_, AppStateManager>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    state_manager.debug_logger.info("Stopping replay folder watcher".to_string());
+
+    let upload_manager = state_manager.upload_manager.lock()
+        .map_err(|_| "Upload manager mutex poisoned")?;
+    if let Some(manager) = upload_manager.as_ref() {
+        manager.stop_watching();
+    }
+    crate::tray::refresh(&app);
+    Ok(())
+}
+
+/// Start the periodic background re-scan scheduler.
+///
+/// Idempotent: repeated calls after the first are no-ops so the loop isn't
+/// spawned more than once. The interval is read from config on each cycle, so
+/// `set_scan_interval` takes effect without a restart.
+#[tauri::command]
+pub async fn start_scan_scheduler(
+    state_manager: State<'_, AppStateManager>,
+    app: tauri::AppHandle,
+) -> Result<(), String> {
+    use std::sync::atomic::Ordering;
+
+    if state_manager.scan_scheduler_started.swap(true, Ordering::AcqRel) {
+        state_manager.debug_logger.debug("Scan scheduler already running".to_string());
+        return Ok(());
+    }
+
+    let manager = {
+        let upload_manager = state_manager.upload_manager.lock()
+            .map_err(|_| "Upload manager mutex poisoned")?;
+        match upload_manager.as_ref() {
+            Some(m) => Arc::clone(m),
+            None => {
+                // Roll back the flag so a later call can retry once the manager
+                // has been initialized.
+                state_manager.scan_scheduler_started.store(false, Ordering::Release);
+                state_manager.debug_logger.error("Upload manager not initialized for scan scheduler".to_string());
+                return Err("Upload manager not initialized".to_string());
+            }
+        }
+    };
+
+    UploadManager::start_scan_scheduler(manager, app);
+    state_manager.debug_logger.info("Periodic scan scheduler started".to_string());
+    Ok(())
+}
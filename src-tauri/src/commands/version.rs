@@ -1,9 +1,47 @@
 //! Version and update management commands.
 
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 use tauri_plugin_updater::UpdaterExt;
 use crate::state::AppStateManager;
+use crate::replay_uploader::{ReplayUploader, UpdateReport};
+
+/// Best-effort submission of an update outcome to the server before restart.
+///
+/// Builds a short-lived uploader from the persisted auth token and the API
+/// client's base URL. Any failure (no token yet, network down) is only logged:
+/// update telemetry must never block or fail the update itself.
+async fn report_update_outcome(
+    state_manager: &AppStateManager,
+    report: UpdateReport,
+) {
+    let stored = crate::config_utils::config_file_path("auth.json")
+        .ok()
+        .and_then(|path| crate::token_store::SecureTokenStore::open(path).ok())
+        .and_then(|store| store.load().ok().flatten());
+    let token = match stored {
+        Some(auth) => auth.access_token.expose_secret().to_string(),
+        None => {
+            state_manager
+                .debug_logger
+                .debug("No auth token available; skipping update report".to_string());
+            return;
+        }
+    };
+
+    let uploader = ReplayUploader::with_logger(
+        state_manager.api_client.base_url().to_string(),
+        token,
+        None,
+        Some(Arc::clone(&state_manager.debug_logger)),
+    );
+
+    if let Err(e) = uploader.report_update_result(&report).await {
+        state_manager
+            .debug_logger
+            .warn(format!("Failed to report update outcome: {}", e));
+    }
+}
 
 /// Get the current app version
 #[tauri::command]
@@ -79,31 +117,88 @@ pub async fn install_update(
         Ok(Some(update)) => {
             state_manager.debug_logger.info(format!("Downloading and installing update: {}", update.version));
 
-            // Clone logger for progress callback
+            // Version context for the server-side update report.
+            let attempted_version = update.version.clone();
+            let previous_version = update.current_version.clone();
+            let platform = std::env::consts::OS.to_string();
+
+            // Track bytes seen so a failure report can note partial progress.
+            let downloaded = Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+            // Clone logger + app handle for the download callbacks so the
+            // frontend can render a real progress bar and installing state.
             let logger_for_progress = Arc::clone(&state_manager.debug_logger);
             let logger_for_complete = Arc::clone(&state_manager.debug_logger);
+            let downloaded_for_progress = Arc::clone(&downloaded);
+            let app_for_progress = app.clone();
+            let app_for_complete = app.clone();
 
-            update.download_and_install(
+            let install_result = update.download_and_install(
                 move |chunk_length, content_length| {
-                    if let Some(total) = content_length {
-                        logger_for_progress.debug(format!("Download progress: {}/{} bytes", chunk_length, total));
-                    } else {
-                        logger_for_progress.debug(format!("Downloaded {} bytes", chunk_length));
+                    let done = downloaded_for_progress
+                        .fetch_add(chunk_length as u64, std::sync::atomic::Ordering::Relaxed)
+                        + chunk_length as u64;
+                    // Percentage is only meaningful when the server sends a
+                    // Content-Length; otherwise the UI shows an indeterminate bar.
+                    let percent = content_length
+                        .filter(|total| *total > 0)
+                        .map(|total| (done as f64 / total as f64 * 100.0).clamp(0.0, 100.0));
+                    if let Err(e) = app_for_progress.emit("update://progress", serde_json::json!({
+                        "downloaded": done,
+                        "total": content_length,
+                        "percent": percent,
+                        "phase": "downloading",
+                    })) {
+                        logger_for_progress.warn(format!("Failed to emit update://progress: {}", e));
+                    }
+                    match content_length {
+                        Some(total) => logger_for_progress.debug(format!("Download progress: {}/{} bytes", done, total)),
+                        None => logger_for_progress.debug(format!("Downloaded {} bytes", done)),
                     }
                 },
                 move || {
                     logger_for_complete.debug("Download complete, installing...".to_string());
+                    if let Err(e) = app_for_complete.emit("update://installing", serde_json::json!({
+                        "phase": "installing",
+                    })) {
+                        logger_for_complete.warn(format!("Failed to emit update://installing: {}", e));
+                    }
                 }
             )
-            .await
-            .map_err(|e| {
+            .await;
+
+            if let Err(e) = install_result {
                 let error_msg = format!("Failed to install update: {}", e);
                 state_manager.debug_logger.error(error_msg.clone());
-                error_msg
-            })?;
+                let bytes = downloaded.load(std::sync::atomic::Ordering::Relaxed);
+                report_update_outcome(&state_manager, UpdateReport {
+                    attempted_version,
+                    previous_version,
+                    outcome: "install-error".to_string(),
+                    platform,
+                    error: Some(format!("{} (downloaded {} bytes)", e, bytes)),
+                }).await;
+                return Err(error_msg);
+            }
 
             state_manager.debug_logger.info("Update installed successfully, restarting app...".to_string());
 
+            if let Err(e) = app.emit("update://complete", serde_json::json!({
+                "phase": "complete",
+                "version": attempted_version.clone(),
+            })) {
+                state_manager.debug_logger.warn(format!("Failed to emit update://complete: {}", e));
+            }
+
+            // Let the server know the update landed before we hand control off.
+            report_update_outcome(&state_manager, UpdateReport {
+                attempted_version,
+                previous_version,
+                outcome: "success".to_string(),
+                platform,
+                error: None,
+            }).await;
+
             // Explicitly restart the app to apply the update
             // Tauri v2 updater may not auto-restart depending on platform
             // Note: restart() never returns, so no code after this executes
@@ -5,8 +5,8 @@
 //! under "ladder-legends-uploader/".
 
 use serde::{de::DeserializeOwned, Serialize};
-use std::fs;
 use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
 
 const APP_DIR_NAME: &str = "ladder-legends-uploader";
 
@@ -39,30 +39,54 @@ pub fn config_file_path(filename: &str) -> Result<PathBuf, String> {
 }
 
 /// Ensure the config directory exists.
-pub fn ensure_config_dir() -> Result<PathBuf, String> {
+pub async fn ensure_config_dir() -> Result<PathBuf, String> {
     let dir = get_config_dir()?;
-    fs::create_dir_all(&dir)
+    tokio::fs::create_dir_all(&dir)
+        .await
         .map_err(|e| format!("Failed to create config directory: {}", e))?;
     Ok(dir)
 }
 
 /// Save data to a config file as JSON.
 ///
+/// The write is atomic and crash-safe: the serialized JSON is first written to
+/// a sibling `<filename>.tmp` in the same directory (so the rename stays on one
+/// filesystem), the handle is flushed and `sync_all`'d to get the bytes onto
+/// disk, and only then is the temp file renamed over the real path. A reader
+/// therefore only ever observes a fully-written file, never a truncated one.
+///
 /// # Arguments
 /// * `filename` - Name of the config file (e.g., "config.json")
 /// * `data` - Data to serialize and save
 ///
 /// # Returns
 /// The path where the file was saved
-pub fn save_config_file<T: Serialize>(filename: &str, data: &T) -> Result<PathBuf, String> {
-    let config_dir = ensure_config_dir()?;
+pub async fn save_config_file<T: Serialize>(filename: &str, data: &T) -> Result<PathBuf, String> {
+    let config_dir = ensure_config_dir().await?;
     let config_file = config_dir.join(filename);
+    let temp_file = config_dir.join(format!("{}.tmp", filename));
 
     let json = serde_json::to_string_pretty(data)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    fs::write(&config_file, json)
-        .map_err(|e| format!("Failed to write config file: {}", e))?;
+    // Write to the sibling temp file, then flush + fsync so the bytes are
+    // durable before we expose them via the rename.
+    let mut handle = tokio::fs::File::create(&temp_file)
+        .await
+        .map_err(|e| format!("Failed to create temp config file: {}", e))?;
+    handle
+        .write_all(json.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write temp config file: {}", e))?;
+    handle
+        .sync_all()
+        .await
+        .map_err(|e| format!("Failed to sync temp config file: {}", e))?;
+    drop(handle);
+
+    tokio::fs::rename(&temp_file, &config_file)
+        .await
+        .map_err(|e| format!("Failed to rename config file into place: {}", e))?;
 
     Ok(config_file)
 }
@@ -76,19 +100,32 @@ pub fn save_config_file<T: Serialize>(filename: &str, data: &T) -> Result<PathBu
 /// * `Ok(Some(data))` if file exists and was parsed successfully
 /// * `Ok(None)` if file doesn't exist
 /// * `Err(...)` if file exists but couldn't be read/parsed
-pub fn load_config_file<T: DeserializeOwned>(filename: &str) -> Result<Option<T>, String> {
+pub async fn load_config_file<T: DeserializeOwned>(filename: &str) -> Result<Option<T>, String> {
     let config_file = config_file_path(filename)?;
+    let temp_file = config_file_path(&format!("{}.tmp", filename))?;
 
     if !config_file.exists() {
+        // A leftover `.tmp` means a previous write crashed before the rename.
+        // It is not a trustworthy config, so clean it up and report "no file".
+        if temp_file.exists() {
+            let _ = tokio::fs::remove_file(&temp_file).await;
+        }
         return Ok(None);
     }
 
-    let contents = fs::read_to_string(&config_file)
+    let contents = tokio::fs::read_to_string(&config_file)
+        .await
         .map_err(|e| format!("Failed to read config file: {}", e))?;
 
     let data = serde_json::from_str(&contents)
         .map_err(|e| format!("Failed to parse config file: {}", e))?;
 
+    // The real file parsed cleanly; drop any stale temp file from an aborted
+    // write so it can't confuse a later load.
+    if temp_file.exists() {
+        let _ = tokio::fs::remove_file(&temp_file).await;
+    }
+
     Ok(Some(data))
 }
 
@@ -114,4 +151,44 @@ mod tests {
         let path = result.unwrap();
         assert!(path.to_string_lossy().contains("test.json"));
     }
+
+    #[tokio::test]
+    async fn test_atomic_write_roundtrip_and_leaves_no_temp() {
+        // Unique name so concurrent test runs don't collide.
+        let filename = "atomic_roundtrip_test.json";
+        let data = serde_json::json!({ "replay_folder": "/some/path" });
+
+        let saved = save_config_file(filename, &data).await.expect("save should succeed");
+        let temp = config_file_path(&format!("{}.tmp", filename)).unwrap();
+
+        // After a successful save the temp file must have been renamed away.
+        assert!(!temp.exists(), "temp file should not linger after save");
+
+        let loaded: Option<serde_json::Value> =
+            load_config_file(filename).await.expect("load should succeed");
+        assert_eq!(loaded, Some(data));
+
+        let _ = std::fs::remove_file(saved);
+    }
+
+    #[tokio::test]
+    async fn test_partial_write_leaves_config_recoverable() {
+        // Simulate a crash mid-write: a good config already on disk plus a
+        // leftover truncated `.tmp`. The loader must return the good config
+        // and clean up the orphaned temp file.
+        let filename = "partial_write_test.json";
+        let good = serde_json::json!({ "replay_folder": "/good/path" });
+        save_config_file(filename, &good).await.expect("initial save should succeed");
+
+        let temp = config_file_path(&format!("{}.tmp", filename)).unwrap();
+        std::fs::write(&temp, "{ \"replay_folder\": \"/trunc").unwrap();
+
+        let loaded: Option<serde_json::Value> =
+            load_config_file(filename).await.expect("load should fall back to the good file");
+        assert_eq!(loaded, Some(good));
+        assert!(!temp.exists(), "loader should clean up the orphaned temp file");
+
+        let real = config_file_path(filename).unwrap();
+        let _ = std::fs::remove_file(real);
+    }
 }
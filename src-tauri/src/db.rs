@@ -0,0 +1,323 @@
+//! SQLite-backed store for uploaded replays and watched-folder settings.
+//!
+//! Complements (rather than replaces) the existing JSON stores: the
+//! [`crate::replay_tracker::ReplayTracker`] hash ledger and
+//! [`crate::app_config::AppConfig`] remain the source of truth for the
+//! upload pipeline itself. `ReplayDb` is the durable home for the data a
+//! restart should be able to resume from at a glance — which replays have
+//! already gone up and which detected folders the user has actually enabled
+//! — queried directly instead of having to deserialize and scan the whole
+//! tracker file.
+//!
+//! Schema changes are applied as ordered migrations tracked in SQLite's
+//! built-in `user_version` pragma, the same "bump a version, add a step"
+//! discipline [`crate::replay_tracker`] uses for its own JSON schema.
+
+use crate::debug_logger::DebugLogger;
+use crate::sc2_detector::SC2ReplayFolder;
+use rusqlite::{params, Connection};
+use std::sync::Mutex;
+
+/// Ordered schema migrations, applied starting from `PRAGMA user_version`.
+/// Add new statements here (never edit an existing entry) and they run once,
+/// in order, the next time the app opens the database.
+const MIGRATIONS: &[&str] = &[
+    r#"
+    CREATE TABLE replays (
+        hash        TEXT PRIMARY KEY,
+        path        TEXT NOT NULL,
+        account_id  TEXT NOT NULL,
+        region      TEXT NOT NULL,
+        status      TEXT NOT NULL,
+        uploaded_at INTEGER NOT NULL
+    );
+    CREATE TABLE folders (
+        path        TEXT PRIMARY KEY,
+        account_id  TEXT NOT NULL,
+        region      TEXT NOT NULL,
+        region_code TEXT NOT NULL,
+        enabled     INTEGER NOT NULL DEFAULT 1,
+        groups      TEXT NOT NULL DEFAULT '[]'
+    );
+    "#,
+];
+
+/// Filename the database is stored under, alongside `config.json` and the
+/// other per-install files in [`crate::config_utils::get_config_dir`].
+const DB_FILE: &str = "uploads.db";
+
+/// Status of a tracked replay's upload, stored as a lowercase string so the
+/// column stays human-readable in the raw `.db` file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadRecordStatus {
+    Uploaded,
+    Failed,
+}
+
+impl UploadRecordStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            UploadRecordStatus::Uploaded => "uploaded",
+            UploadRecordStatus::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "uploaded" => Some(UploadRecordStatus::Uploaded),
+            "failed" => Some(UploadRecordStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One row of the `folders` table: a detected [`SC2ReplayFolder`] plus the
+/// user-facing settings the raw detection result doesn't carry.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FolderRecord {
+    pub path: String,
+    pub account_id: String,
+    pub region: String,
+    pub region_code: String,
+    pub enabled: bool,
+    /// Free-form tags for organizing accounts (e.g. "main", "smurf"),
+    /// round-tripped as a JSON array in the `groups` column.
+    pub groups: Vec<String>,
+}
+
+pub struct ReplayDb {
+    conn: Mutex<Connection>,
+}
+
+impl ReplayDb {
+    /// Open (creating if needed) the database under the app's config
+    /// directory and bring it up to the latest schema.
+    ///
+    /// Runs on `std::fs`/blocking `rusqlite` calls because, like
+    /// [`crate::services::job_manager::JobManager::new`], this is called
+    /// from [`crate::state::AppStateManager::new`] before the async runtime
+    /// is handling commands. Any failure to resolve the config directory or
+    /// open the file falls back to a private in-memory database rather than
+    /// failing startup — the app still works for the session, just without
+    /// durability, and the failure is logged.
+    pub fn open_sync(logger: &DebugLogger) -> Self {
+        let conn = Self::open_file_sync(logger).unwrap_or_else(|| {
+            logger.warn("Falling back to an in-memory upload database".to_string());
+            Connection::open_in_memory().expect("sqlite in-memory open should never fail")
+        });
+
+        if let Err(e) = migrate(&conn) {
+            logger.warn(format!("Failed to migrate upload database: {}", e));
+        }
+
+        Self { conn: Mutex::new(conn) }
+    }
+
+    fn open_file_sync(logger: &DebugLogger) -> Option<Connection> {
+        let path = crate::config_utils::config_file_path(DB_FILE)
+            .inspect_err(|e| logger.warn(format!("Could not resolve upload database path: {}", e)))
+            .ok()?;
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                logger.warn(format!("Failed to create config directory: {}", e));
+                return None;
+            }
+        }
+
+        Connection::open(&path)
+            .inspect_err(|e| logger.warn(format!("Failed to open {}: {}", path.display(), e)))
+            .ok()
+    }
+
+    /// Record a replay's upload outcome by content hash, overwriting any
+    /// earlier record for the same hash (e.g. a retry that later succeeds).
+    pub fn record_upload(
+        &self,
+        hash: &str,
+        path: &str,
+        account_id: &str,
+        region: &str,
+        status: UploadRecordStatus,
+        uploaded_at: u64,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT INTO replays (hash, path, account_id, region, status, uploaded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(hash) DO UPDATE SET
+                path = excluded.path,
+                account_id = excluded.account_id,
+                region = excluded.region,
+                status = excluded.status,
+                uploaded_at = excluded.uploaded_at",
+            params![hash, path, account_id, region, status.as_str(), uploaded_at as i64],
+        )
+        .map_err(|e| format!("Failed to record upload: {}", e))?;
+        Ok(())
+    }
+
+    /// Whether `hash` has a recorded successful upload.
+    pub fn is_uploaded(&self, hash: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.query_row(
+            "SELECT status FROM replays WHERE hash = ?1",
+            params![hash],
+            |row| row.get::<_, String>(0),
+        )
+        .map(|status| UploadRecordStatus::parse(&status) == Some(UploadRecordStatus::Uploaded))
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(false),
+            e => Err(format!("Failed to look up upload status: {}", e)),
+        })
+    }
+
+    /// Insert or update a detected folder's settings row, leaving `enabled`
+    /// and `groups` untouched if the folder is already known (re-running
+    /// detection shouldn't silently re-enable a folder the user turned off).
+    pub fn upsert_folder(&self, folder: &SC2ReplayFolder) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT INTO folders (path, account_id, region, region_code, enabled, groups)
+             VALUES (?1, ?2, ?3, ?4, 1, '[]')
+             ON CONFLICT(path) DO UPDATE SET
+                account_id = excluded.account_id,
+                region = excluded.region,
+                region_code = excluded.region_code",
+            params![
+                folder.path.to_string_lossy(),
+                folder.account_id,
+                folder.region,
+                folder.region_code,
+            ],
+        )
+        .map_err(|e| format!("Failed to upsert folder: {}", e))?;
+        Ok(())
+    }
+
+    /// Enable or disable a previously-upserted folder by path.
+    pub fn set_folder_enabled(&self, path: &str, enabled: bool) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "UPDATE folders SET enabled = ?2 WHERE path = ?1",
+            params![path, enabled as i64],
+        )
+        .map_err(|e| format!("Failed to update folder: {}", e))?;
+        Ok(())
+    }
+
+    /// Every known folder, enabled or not.
+    pub fn list_folders(&self) -> Result<Vec<FolderRecord>, String> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn
+            .prepare("SELECT path, account_id, region, region_code, enabled, groups FROM folders ORDER BY path")
+            .map_err(|e| format!("Failed to prepare folder query: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let groups_json: String = row.get(5)?;
+                Ok(FolderRecord {
+                    path: row.get(0)?,
+                    account_id: row.get(1)?,
+                    region: row.get(2)?,
+                    region_code: row.get(3)?,
+                    enabled: row.get::<_, i64>(4)? != 0,
+                    groups: serde_json::from_str(&groups_json).unwrap_or_default(),
+                })
+            })
+            .map_err(|e| format!("Failed to read folders: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read folder row: {}", e))
+    }
+}
+
+/// Bring `conn` up to the latest schema, running any migration past its
+/// current `user_version` in order.
+fn migrate(conn: &Connection) -> Result<(), String> {
+    let current: u32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        conn.execute_batch(migration)
+            .map_err(|e| format!("Migration {} failed: {}", i + 1, e))?;
+        conn.pragma_update(None, "user_version", (i + 1) as u32)
+            .map_err(|e| format!("Failed to record schema version {}: {}", i + 1, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fake_db() -> ReplayDb {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        ReplayDb { conn: Mutex::new(conn) }
+    }
+
+    fn fake_folder() -> SC2ReplayFolder {
+        SC2ReplayFolder {
+            path: PathBuf::from("/home/user/.wine/drive_c/users/user/Documents/StarCraft II/Accounts/12345678/1-S2-1-123456/Replays/Multiplayer"),
+            account_id: "12345678".to_string(),
+            region: "NA".to_string(),
+            region_code: "1-S2-1-123456".to_string(),
+            account_folder: crate::sc2_detector::parse_account_folder_name("1-S2-1-123456"),
+        }
+    }
+
+    #[test]
+    fn test_record_and_check_upload() {
+        let db = fake_db();
+        assert!(!db.is_uploaded("abc123").unwrap());
+
+        db.record_upload("abc123", "/path/game.SC2Replay", "12345678", "NA", UploadRecordStatus::Uploaded, 1000).unwrap();
+        assert!(db.is_uploaded("abc123").unwrap());
+    }
+
+    #[test]
+    fn test_record_upload_overwrites_status() {
+        let db = fake_db();
+        db.record_upload("abc123", "/path/game.SC2Replay", "12345678", "NA", UploadRecordStatus::Failed, 1000).unwrap();
+        assert!(!db.is_uploaded("abc123").unwrap());
+
+        db.record_upload("abc123", "/path/game.SC2Replay", "12345678", "NA", UploadRecordStatus::Uploaded, 2000).unwrap();
+        assert!(db.is_uploaded("abc123").unwrap());
+    }
+
+    #[test]
+    fn test_upsert_folder_defaults_enabled() {
+        let db = fake_db();
+        db.upsert_folder(&fake_folder()).unwrap();
+
+        let folders = db.list_folders().unwrap();
+        assert_eq!(folders.len(), 1);
+        assert!(folders[0].enabled);
+        assert_eq!(folders[0].account_id, "12345678");
+    }
+
+    #[test]
+    fn test_upsert_folder_preserves_disabled_flag() {
+        let db = fake_db();
+        let folder = fake_folder();
+        db.upsert_folder(&folder).unwrap();
+        db.set_folder_enabled(&folder.path.to_string_lossy(), false).unwrap();
+
+        // Re-running detection upserts the same folder again; it should stay disabled.
+        db.upsert_folder(&folder).unwrap();
+
+        let folders = db.list_folders().unwrap();
+        assert_eq!(folders.len(), 1);
+        assert!(!folders[0].enabled);
+    }
+
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        migrate(&conn).unwrap();
+    }
+}
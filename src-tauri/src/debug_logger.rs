@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use sysinfo::{System, Disks};
 use chrono::Utc;
+use regex::Regex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebugLogEntry {
@@ -14,6 +17,54 @@ pub struct DebugLogEntry {
     pub context: Option<serde_json::Value>,
 }
 
+/// Severity levels, ordered so a minimum-level filter is a simple comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+    Fatal = 4,
+}
+
+impl LogLevel {
+    /// Map a level string (as used by [`DebugLogger::log`]) to a [`LogLevel`].
+    /// Unknown levels are treated as [`LogLevel::Info`].
+    fn from_str(level: &str) -> Self {
+        match level {
+            "DEBUG" => LogLevel::Debug,
+            "WARN" => LogLevel::Warn,
+            "ERROR" => LogLevel::Error,
+            "FATAL" => LogLevel::Fatal,
+            _ => LogLevel::Info,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Maximum entries read back into a [`DebugReport`] across a session's files.
+const REPORT_HISTORY_LIMIT: usize = 1000;
+
+/// Default bound on the in-memory ring buffer of recent log entries. Caps the
+/// memory held during long watch sessions; also sizes the broadcast channel
+/// that feeds live subscribers.
+const DEFAULT_RING_CAPACITY: usize = 1000;
+
+/// How a consumer wants to receive log entries from [`DebugLogger`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogStreamMode {
+    /// Return everything currently buffered, then stop.
+    Snapshot,
+    /// Ignore the buffer; deliver only entries logged from now on.
+    Subscribe,
+    /// Drain the buffer first, then continue with live entries.
+    SnapshotThenSubscribe,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub os: String,
@@ -51,22 +102,181 @@ pub struct DebugReport {
     pub log_entries: Vec<DebugLogEntry>,
 }
 
+/// Minimum length of a registered secret. Shorter values (e.g. a one-character
+/// device code fragment) would mask far too much benign text, so they are
+/// ignored.
+const MIN_SECRET_LEN: usize = 4;
+
+/// Compiled patterns for common secret shapes, used in addition to the runtime
+/// registry so token-like values are scrubbed even when a flow forgot to
+/// register them. Matches are replaced with a placeholder that records their length.
+fn secret_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS
+        .get_or_init(|| {
+            [
+                // JSON Web Tokens (header.payload.signature).
+                r"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+                // `Bearer <token>` in an Authorization header.
+                r"(?i)bearer\s+[A-Za-z0-9._~+/=-]+",
+                // Long opaque tokens: 32+ base64url/hex-ish characters.
+                r"[A-Za-z0-9_-]{32,}",
+            ]
+            .iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect()
+        })
+        .as_slice()
+}
+
+/// Build the mask for a matched secret, recording only its length.
+fn mask(len: usize) -> String {
+    format!("***REDACTED(len={})***", len)
+}
+
 pub struct DebugLogger {
     logs: Arc<Mutex<Vec<DebugLogEntry>>>,
     error_count: Arc<Mutex<usize>>,
+    /// Runtime registry of exact secret strings (access/refresh tokens, device
+    /// codes) registered by the auth flows via [`DebugLogger::register_secret`].
+    secrets: Arc<Mutex<HashSet<String>>>,
+    /// Durable rotating per-session file backend. A new session directory is
+    /// opened at construction (i.e. once per app launch).
+    session: Arc<Mutex<crate::session_log::SessionLogger>>,
+    /// Minimum level written to disk; defaults to [`LogLevel::Debug`] so the
+    /// file captures everything. Console output is held at INFO and above so
+    /// raising disk verbosity doesn't flood the terminal.
+    min_level: Arc<AtomicU8>,
+    /// Upper bound on entries retained in [`Self::logs`]; caps RAM during long
+    /// watch sessions.
+    ring_capacity: usize,
+    /// Fan-out channel for live subscribers. Each new entry is published here
+    /// after being buffered; dropped if no receivers are listening.
+    live: tokio::sync::broadcast::Sender<DebugLogEntry>,
 }
 
 impl DebugLogger {
     pub fn new() -> Self {
+        Self::with_ring_capacity(DEFAULT_RING_CAPACITY)
+    }
+
+    /// Construct a logger with an explicit ring-buffer bound. The broadcast
+    /// channel is sized to match so a briefly-lagging subscriber still sees the
+    /// same window of history the buffer holds.
+    pub fn with_ring_capacity(ring_capacity: usize) -> Self {
+        let dir = crate::config_utils::get_logs_dir()
+            .unwrap_or_else(|_| PathBuf::from(".ladder-legends-uploader/logs"));
+        let session =
+            crate::session_log::SessionLogger::new(dir, crate::session_log::SessionConfig::default());
+        let capacity = ring_capacity.max(1);
+        let (live, _) = tokio::sync::broadcast::channel(capacity);
         Self {
             logs: Arc::new(Mutex::new(Vec::new())),
             error_count: Arc::new(Mutex::new(0)),
+            secrets: Arc::new(Mutex::new(HashSet::new())),
+            session: Arc::new(Mutex::new(session)),
+            min_level: Arc::new(AtomicU8::new(LogLevel::Debug.as_u8())),
+            ring_capacity: capacity,
+            live,
+        }
+    }
+
+    /// Snapshot the entries currently held in the in-memory ring buffer.
+    pub fn snapshot(&self) -> Vec<DebugLogEntry> {
+        self.logs
+            .lock()
+            .map(|logs| logs.clone())
+            .unwrap_or_default()
+    }
+
+    /// Subscribe to entries logged from now on. Pair with [`Self::snapshot`] for
+    /// snapshot-then-subscribe consumers (subscribe first to avoid a gap).
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<DebugLogEntry> {
+        self.live.subscribe()
+    }
+
+    /// Path of the current session's log directory, for support-bundle export.
+    pub fn session_dir(&self) -> PathBuf {
+        self.session
+            .lock()
+            .map(|s| s.session_dir().to_path_buf())
+            .unwrap_or_else(|e| e.into_inner().session_dir().to_path_buf())
+    }
+
+    /// Raise or lower the minimum level persisted to disk. For example, set it
+    /// to [`LogLevel::Debug`] to capture verbose diagnostics on disk while the
+    /// console stays quiet.
+    pub fn set_min_level(&self, level: LogLevel) {
+        self.min_level.store(level.as_u8(), Ordering::Relaxed);
+    }
+
+    /// Register a secret so any later log message or context value containing it
+    /// is masked. Values shorter than [`MIN_SECRET_LEN`] are ignored.
+    pub fn register_secret(&self, value: &str) {
+        if value.len() < MIN_SECRET_LEN {
+            return;
+        }
+        if let Ok(mut secrets) = self.secrets.lock() {
+            secrets.insert(value.to_string());
+        }
+    }
+
+    /// Forget a previously-registered secret (e.g. on logout/rotation).
+    pub fn forget_secret(&self, value: &str) {
+        if let Ok(mut secrets) = self.secrets.lock() {
+            secrets.remove(value);
+        }
+    }
+
+    /// Scrub a string: first replace exact registered secrets, then apply the
+    /// shape patterns. Both replace matches with a mask that records their length.
+    fn redact_str(&self, input: &str) -> String {
+        let mut out = input.to_string();
+        if let Ok(secrets) = self.secrets.lock() {
+            for secret in secrets.iter() {
+                if out.contains(secret.as_str()) {
+                    out = out.replace(secret.as_str(), &mask(secret.len()));
+                }
+            }
+        }
+        for pattern in secret_patterns() {
+            out = pattern
+                .replace_all(&out, |caps: &regex::Captures| mask(caps[0].len()))
+                .into_owned();
+        }
+        out
+    }
+
+    /// Recursively scrub a context JSON tree, redacting every string value.
+    fn redact_value(&self, value: &serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(s) => Value::String(self.redact_str(s)),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|v| self.redact_value(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.redact_value(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
         }
     }
 
     pub fn log(&self, level: &str, message: String, context: Option<serde_json::Value>) {
-        // Print to console first
-        eprintln!("[{}] {}", level, message);
+        // Scrub secrets at insertion time so nothing unredacted ever sits in the
+        // in-memory buffer or the console output.
+        let message = self.redact_str(&message);
+        let context = context.map(|c| self.redact_value(&c));
+
+        let level_rank = LogLevel::from_str(level);
+
+        // Console stays at INFO and above so raising the on-disk level to DEBUG
+        // doesn't flood the terminal.
+        if level_rank >= LogLevel::Info {
+            eprintln!("[{}] {}", level, message);
+        }
 
         let entry = DebugLogEntry {
             timestamp: Utc::now().to_rfc3339(),
@@ -81,13 +291,28 @@ impl DebugLogger {
             }
         }
 
+        // Persist to the rotating file backend when at or above the configured
+        // minimum level; failures here must never break logging.
+        if level_rank.as_u8() >= self.min_level.load(Ordering::Relaxed) {
+            if let Ok(line) = serde_json::to_string(&entry) {
+                if let Ok(mut session) = self.session.lock() {
+                    let _ = session.append(&line);
+                }
+            }
+        }
+
         if let Ok(mut logs) = self.logs.lock() {
-            // Keep last 1000 entries to avoid memory issues
-            if logs.len() >= 1000 {
+            // Keep the most recent `ring_capacity` entries in RAM for quick
+            // access; the full history lives in the rotating files on disk.
+            while logs.len() >= self.ring_capacity {
                 logs.remove(0);
             }
-            logs.push(entry);
+            logs.push(entry.clone());
         }
+
+        // Fan out to any live subscribers. Errs only when there are none, which
+        // is the common case, so the result is intentionally ignored.
+        let _ = self.live.send(entry);
     }
 
     pub fn info(&self, message: String) {
@@ -164,7 +389,16 @@ impl DebugLogger {
         replays_found: Option<usize>,
         discord_user_id: Option<String>,
     ) -> DebugReport {
-        let logs = self.logs.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        // Prefer the durable history on disk (survives restarts and spans more
+        // than the RAM ring); fall back to the in-memory buffer if nothing has
+        // been persisted yet.
+        let mut logs = crate::session_log::collect_session(&self.session_dir());
+        if logs.is_empty() {
+            logs = self.logs.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        }
+        if logs.len() > REPORT_HISTORY_LIMIT {
+            logs = logs.split_off(logs.len() - REPORT_HISTORY_LIMIT);
+        }
         let error_count = self.get_error_count();
 
         DebugReport {
@@ -213,6 +447,78 @@ impl DebugLogger {
 
         Ok(log_path)
     }
+
+    /// Gather the current [`DebugReport`] plus any rotated log files into a
+    /// single `gzip`-compressed tar archive and return its path.
+    ///
+    /// Unlike [`save_report_to_file`], which drops a bare JSON next to earlier
+    /// logs, this produces one self-contained, shippable artifact: a
+    /// `report.json` alongside every `debug_log_*.json` already on disk. The
+    /// archive is the payload streamed by the `upload_debug_bundle` command.
+    ///
+    /// [`save_report_to_file`]: Self::save_report_to_file
+    pub fn create_debug_bundle(
+        &self,
+        replay_folder: Option<String>,
+        replays_found: Option<usize>,
+        discord_user_id: Option<String>,
+    ) -> Result<PathBuf, String> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let report = self.generate_report(replay_folder, replays_found, discord_user_id);
+        let report_json = serde_json::to_vec_pretty(&report)
+            .map_err(|e| format!("Failed to serialize report: {}", e))?;
+
+        let home_dir = dirs::home_dir()
+            .ok_or_else(|| "Could not find home directory".to_string())?;
+        let logs_dir = home_dir.join(".ladder-legends-uploader").join("logs");
+        fs::create_dir_all(&logs_dir)
+            .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let bundle_path = logs_dir.join(format!("debug_bundle_{}.tar.gz", timestamp));
+
+        let file = File::create(&bundle_path)
+            .map_err(|e| format!("Failed to create bundle file: {}", e))?;
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+
+        // The freshly-generated report.
+        let mut header = tar::Header::new_gnu();
+        header.set_size(report_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, "report.json", report_json.as_slice())
+            .map_err(|e| format!("Failed to add report to bundle: {}", e))?;
+
+        // Any rotated JSON log files already on disk.
+        if let Ok(entries) = fs::read_dir(&logs_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let is_log = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|name| name.starts_with("debug_log_") && name.ends_with(".json"))
+                    .unwrap_or(false);
+                if is_log {
+                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                        let name = name.to_string();
+                        if let Err(e) = tar.append_path_with_name(&path, &name) {
+                            self.warn(format!("Skipping log file {} in bundle: {}", name, e));
+                        }
+                    }
+                }
+            }
+        }
+
+        tar.into_inner()
+            .map_err(|e| format!("Failed to finalize bundle archive: {}", e))?
+            .finish()
+            .map_err(|e| format!("Failed to flush bundle gzip stream: {}", e))?;
+
+        Ok(bundle_path)
+    }
 }
 
 impl Default for DebugLogger {
@@ -1,10 +1,153 @@
+use base64::Engine;
+use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::env;
+use std::time::{Duration, Instant, SystemTime};
+
+/// URL-safe, unpadded base64, as RFC 7636 requires for `code_verifier` and
+/// `code_challenge`.
+const B64URL: base64::engine::general_purpose::GeneralPurpose =
+    base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+/// Number of seconds added to the poll interval each time the server returns
+/// `slow_down`, as mandated by RFC 8628 §3.5.
+const SLOW_DOWN_INCREMENT_SECS: u64 = 5;
+
+/// Number of random bytes backing a generated `code_verifier`. 64 bytes of
+/// unpadded base64url encode to 86 characters, comfortably inside RFC 7636's
+/// required 43-128 character range.
+const PKCE_VERIFIER_BYTES: usize = 64;
+
+/// RFC 7636 PKCE verifier/challenge pair binding a device-authorization
+/// request to the client that initiated it, so intercepting the `device_code`
+/// (e.g. from a shared clipboard or a logged redirect) isn't enough to
+/// complete the login on an attacker's behalf.
+pub struct PkceChallenge {
+    /// High-entropy secret generated by the client and never sent until the
+    /// poll step, which proves to the server that this poll came from whoever
+    /// made the original `request_device_code` call.
+    pub code_verifier: String,
+    /// `BASE64URL(SHA256(code_verifier))`, safe to send with the initial
+    /// device-code request since it doesn't reveal the verifier.
+    pub code_challenge: String,
+}
+
+impl PkceChallenge {
+    /// The `code_challenge_method` this client uses; PKCE's plain fallback
+    /// isn't worth supporting when the server can always speak S256.
+    pub const METHOD: &'static str = "S256";
+
+    /// Generate a fresh verifier/challenge pair.
+    pub fn generate() -> Self {
+        let mut verifier_bytes = [0u8; PKCE_VERIFIER_BYTES];
+        OsRng.fill_bytes(&mut verifier_bytes);
+        let code_verifier = B64URL.encode(verifier_bytes);
+        let code_challenge = B64URL.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        Self {
+            code_verifier,
+            code_challenge,
+        }
+    }
+}
+
+/// Retry policy for transient failures on [`ApiClient`]'s simple
+/// request/response methods: connection and timeout errors, plus responses
+/// with a retryable status (429/500/502/503/504), are retried up to
+/// `max_retries` times with exponential backoff (doubling `base_delay` each
+/// attempt, capped at `max_delay`) and jitter. A `Retry-After` response
+/// header, when present, is honored exactly instead of the computed backoff.
+/// Statuses the device flow gives protocol meaning to (400/403/410/428) are
+/// never retried here — see [`ApiClient::send_with_retry`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// HTTP statuses worth retrying: rate limiting and server-side failures that
+/// are usually transient.
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 429 | 500 | 502 | 503 | 504)
+}
+
+/// Whether a revocation response means the token is no longer valid: either a
+/// successful revoke, or a 404 meaning the server never had (or no longer
+/// has) this token. RFC 7009 §2.2 says to treat both the same, since either
+/// way the caller's goal — the token doesn't work anymore — is satisfied.
+fn is_revocation_success(status: reqwest::StatusCode) -> bool {
+    status.is_success() || status == reqwest::StatusCode::NOT_FOUND
+}
+
+/// Exponential backoff with jitter for retry attempt `attempt` (0-indexed).
+///
+/// `base_delay` doubles each attempt up to `max_delay`, then up to 50% extra
+/// jitter is added on top so many clients retrying at once don't all land on
+/// the same instant.
+fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let capped = policy
+        .base_delay
+        .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+        .unwrap_or(policy.max_delay)
+        .min(policy.max_delay);
+
+    let mut jitter_byte = [0u8; 1];
+    OsRng.fill_bytes(&mut jitter_byte);
+    let jitter_fraction = jitter_byte[0] as f64 / 256.0 * 0.5;
+    capped.saturating_add(capped.mul_f64(jitter_fraction))
+}
+
+/// Parse a `Retry-After` header value (RFC 7231 §7.1.3): either an integer
+/// number of seconds, or an HTTP-date to wait until.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let target = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let remaining = target.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(remaining.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// RFC 8414 authorization-server metadata, fetched from
+/// `{base_url}/.well-known/oauth-authorization-server` by
+/// [`ApiClient::discover`]. Lets a deployment move its auth routes (e.g. a
+/// staging or self-hosted server mounting them under a different path)
+/// without the uploader being recompiled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMetadata {
+    pub issuer: String,
+    #[serde(default)]
+    pub device_authorization_endpoint: Option<String>,
+    #[serde(default)]
+    pub token_endpoint: Option<String>,
+    #[serde(default)]
+    pub introspection_endpoint: Option<String>,
+    #[serde(default)]
+    pub revocation_endpoint: Option<String>,
+}
 
 /// API client for Ladder Legends Academy
 pub struct ApiClient {
     base_url: String,
     client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    /// Authorization-server metadata from [`ApiClient::discover`], cached
+    /// after the first successful fetch.
+    metadata: std::sync::Mutex<Option<ServerMetadata>>,
 }
 
 impl ApiClient {
@@ -23,13 +166,103 @@ impl ApiClient {
         Self {
             base_url,
             client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            metadata: std::sync::Mutex::new(None),
         }
     }
 
+    /// The API base URL this client targets.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Get the device auth base URL
     fn device_auth_url(&self, path: &str) -> String {
         format!("{}/api/auth/device/{}", self.base_url, path)
     }
+
+    /// Send a request built fresh by `build_request` for each attempt,
+    /// retrying per [`RetryPolicy`] on connection/timeout errors and on a
+    /// retryable status ([`is_retryable_status`]). Any other outcome —
+    /// success, a non-retryable status, or a non-transient request error —
+    /// is returned immediately so callers keep full control over status-code
+    /// semantics (e.g. the device flow's 400/403/410/428).
+    async fn send_with_retry<F>(&self, build_request: F) -> Result<reqwest::Response, String>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            match build_request().send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    if status.is_success()
+                        || !is_retryable_status(status.as_u16())
+                        || attempt >= self.retry_policy.max_retries
+                    {
+                        return Ok(response);
+                    }
+                    let delay = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_retry_after)
+                        .unwrap_or_else(|| backoff_delay(&self.retry_policy, attempt));
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    if !(e.is_timeout() || e.is_connect()) || attempt >= self.retry_policy.max_retries {
+                        return Err(format!("Network error: {}", e));
+                    }
+                    tokio::time::sleep(backoff_delay(&self.retry_policy, attempt)).await;
+                }
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Fetch RFC 8414 authorization-server metadata from
+    /// `{base_url}/.well-known/oauth-authorization-server`, caching it on
+    /// success so later calls reuse it instead of re-fetching. Returns
+    /// `Err` (including on a 404, which means the server doesn't publish
+    /// metadata) so callers can fall back to the hardcoded endpoints.
+    pub async fn discover(&self) -> Result<ServerMetadata, String> {
+        if let Some(metadata) = self.metadata.lock().unwrap().clone() {
+            return Ok(metadata);
+        }
+
+        let url = format!("{}/.well-known/oauth-authorization-server", self.base_url);
+        let response = self.send_with_retry(|| self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Server error: {}", response.status()));
+        }
+
+        let metadata: ServerMetadata = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        *self.metadata.lock().unwrap() = Some(metadata.clone());
+        Ok(metadata)
+    }
+
+    /// Resolve an auth endpoint URL: the discovered metadata's endpoint
+    /// (via `pick`) when [`discover`](Self::discover) succeeds and the
+    /// server actually published it, otherwise the fixed
+    /// `{base_url}/api/auth/device/{fallback_path}` template, so
+    /// deployments that don't mount `.well-known` keep working unmodified.
+    async fn resolve_endpoint(
+        &self,
+        pick: impl Fn(&ServerMetadata) -> Option<String>,
+        fallback_path: &str,
+    ) -> String {
+        self.discover()
+            .await
+            .ok()
+            .and_then(|metadata| pick(&metadata))
+            .unwrap_or_else(|| self.device_auth_url(fallback_path))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +272,12 @@ pub struct DeviceCodeResponse {
     pub verification_uri: String,
     pub expires_in: u64,
     pub interval: u64,
+    /// PKCE verifier generated alongside this device code; absent from the
+    /// server's own response body (hence `skip_deserializing`/`default`) and
+    /// filled in by [`ApiClient::request_device_code`] before this struct is
+    /// returned. Must be presented back to [`ApiClient::poll_authorization`].
+    #[serde(default, skip_deserializing)]
+    pub code_verifier: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +294,12 @@ pub struct AuthResponse {
     pub token_type: String,
     pub expires_in: u64,
     pub user: UserData,
+    /// Stable id the server assigns to the public key registered in
+    /// [`ApiClient::request_device_code`]. Persisted against the local
+    /// [`crate::device_identity::DeviceSigner`] by
+    /// [`ApiClient::await_authorization`] so subsequent uploads can be signed.
+    #[serde(default)]
+    pub device_id: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -64,35 +309,86 @@ pub struct ErrorResponse {
     pub message: Option<String>,
 }
 
+/// OAuth 2.0 token-introspection (RFC 7662) payload for an access token,
+/// returned by [`ApiClient::introspect_token`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenIntrospection {
+    /// Whether the token is currently active. The server's field is named
+    /// `valid` rather than RFC 7662's `active`; renamed here to match the
+    /// vocabulary the rest of this struct (and the spec) uses.
+    #[serde(rename = "valid")]
+    pub active: bool,
+    /// The id of the user this token was issued to.
+    #[serde(rename = "userId", default)]
+    pub user_id: Option<String>,
+    /// Seconds since the Unix epoch when the token expires.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Space-delimited scopes granted to this token, if the server reports them.
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
 impl ApiClient {
     /// Request a device code from the server
     pub async fn request_device_code(&self) -> Result<DeviceCodeResponse, String> {
-        let response = self.client
-            .post(self.device_auth_url("code"))
-            .json(&serde_json::json!({
-                "client_id": "ladder-legends-uploader"
-            }))
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
+        // Register this instance's ed25519 public key so later uploads signed
+        // by the matching private key can be authenticated. Key generation is
+        // best-effort: if the secure store is unavailable we still authenticate,
+        // just without request signing.
+        let device_public_key = crate::device_identity::DeviceSigner::load_or_create()
+            .ok()
+            .map(|signer| signer.public_key_b64());
+
+        let pkce = PkceChallenge::generate();
+        let body = crate::api_contracts::DeviceAuthRequest {
+            client_id: "ladder-legends-uploader".to_string(),
+            device_public_key,
+            code_challenge: Some(pkce.code_challenge.clone()),
+            code_challenge_method: Some(PkceChallenge::METHOD.to_string()),
+        };
+
+        let url = self
+            .resolve_endpoint(|m| m.device_authorization_endpoint.clone(), "code")
+            .await;
+        let response = self
+            .send_with_retry(|| self.client.post(&url).json(&body))
+            .await?;
 
         if !response.status().is_success() {
             return Err(format!("Server error: {}", response.status()));
         }
 
-        let device_code: DeviceCodeResponse = response
+        let mut device_code: DeviceCodeResponse = response
             .json()
             .await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
+        device_code.code_verifier = pkce.code_verifier;
 
         Ok(device_code)
     }
 
-    /// Poll for authorization status (single check, no automatic retry)
-    pub async fn poll_authorization(&self, device_code: &str) -> Result<AuthResponse, String> {
+    /// Poll for authorization status (single check, no automatic retry).
+    ///
+    /// `code_verifier` should be the value generated for this device code by
+    /// [`request_device_code`](Self::request_device_code); pass an empty
+    /// string for a device code obtained before PKCE support existed, and the
+    /// poll falls back to the plain, unbound request the server originally
+    /// advertised.
+    pub async fn poll_authorization(
+        &self,
+        device_code: &str,
+        code_verifier: &str,
+    ) -> Result<AuthResponse, String> {
+        let mut query = vec![("device_code", device_code)];
+        if !code_verifier.is_empty() {
+            query.push(("code_verifier", code_verifier));
+        }
+
+        let url = self.resolve_endpoint(|m| m.token_endpoint.clone(), "poll").await;
         let response = self.client
-            .get(self.device_auth_url("poll"))
-            .query(&[("device_code", device_code)])
+            .get(&url)
+            .query(&query)
             .send()
             .await
             .map_err(|e| format!("Network error: {}", e))?;
@@ -105,11 +401,64 @@ impl ApiClient {
                     .map_err(|e| format!("Failed to parse response: {}", e))?;
                 Ok(auth)
             }
-            428 => Err("pending".to_string()),
+            // A distinct status for `slow_down`, if the server uses one.
+            429 => Err("slow_down".to_string()),
+            // RFC 8628 servers typically signal `slow_down` with the same
+            // status as `authorization_pending`, distinguished only by the
+            // body's `error` field, so check before assuming plain pending.
+            428 => match response.json::<ErrorResponse>().await {
+                Ok(body) if body.error == "slow_down" => Err("slow_down".to_string()),
+                _ => Err("pending".to_string()),
+            },
             410 => Err("expired".to_string()),
             403 => Err("denied".to_string()),
-            _ => {
-                Err(format!("Server error: {}", response.status()))
+            status => Err(format!("Server error: {}", status)),
+        }
+    }
+
+    /// Drive [`poll_authorization`](Self::poll_authorization) to completion,
+    /// implementing the RFC 8628 §3.5 polling algorithm instead of leaving the
+    /// delay/backoff bookkeeping to the caller: sleeps `device.interval`
+    /// seconds between polls, treats `pending` as keep-waiting, and
+    /// permanently adds [`SLOW_DOWN_INCREMENT_SECS`] to the interval whenever
+    /// the server signals `slow_down`. Gives up with `Err("expired")` once
+    /// `device.expires_in` seconds have elapsed since entry, even if the
+    /// server hasn't returned 410 yet; `denied` (and any other error) is
+    /// surfaced immediately.
+    ///
+    /// On success, persists the server-assigned `device_id` (if any) against
+    /// the local [`crate::device_identity::DeviceSigner`], so uploads made
+    /// later in this session can be signed rather than bearer-only.
+    pub async fn await_authorization(&self, device: &DeviceCodeResponse) -> Result<AuthResponse, String> {
+        let deadline = Instant::now() + Duration::from_secs(device.expires_in);
+        let mut interval = Duration::from_secs(device.interval);
+
+        loop {
+            if Instant::now() >= deadline {
+                return Err("expired".to_string());
+            }
+            tokio::time::sleep(interval).await;
+            if Instant::now() >= deadline {
+                return Err("expired".to_string());
+            }
+
+            match self
+                .poll_authorization(&device.device_code, &device.code_verifier)
+                .await
+            {
+                Ok(auth) => {
+                    if let Some(device_id) = auth.device_id.clone() {
+                        if let Ok(mut signer) = crate::device_identity::DeviceSigner::load_or_create() {
+                            let _ = signer.set_device_id(device_id);
+                        }
+                    }
+                    return Ok(auth);
+                }
+                Err(e) if e == "pending" => continue,
+                Err(e) if e == "slow_down" => {
+                    interval += Duration::from_secs(SLOW_DOWN_INCREMENT_SECS);
+                }
+                Err(e) => return Err(e),
             }
         }
     }
@@ -117,14 +466,12 @@ impl ApiClient {
     /// Refresh an access token
     #[allow(dead_code)]
     pub async fn refresh_token(&self, refresh_token: &str) -> Result<String, String> {
-        let response = self.client
-            .post(self.device_auth_url("refresh"))
-            .json(&serde_json::json!({
-                "refresh_token": refresh_token
-            }))
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
+        let body = serde_json::json!({ "refresh_token": refresh_token });
+        let url = self.resolve_endpoint(|m| m.token_endpoint.clone(), "refresh").await;
+
+        let response = self
+            .send_with_retry(|| self.client.post(&url).json(&body))
+            .await?;
 
         if !response.status().is_success() {
             return Err("Failed to refresh token".to_string());
@@ -143,8 +490,94 @@ impl ApiClient {
         Ok(refresh_resp.access_token)
     }
 
-    /// Verify an access token
-    pub async fn verify_token(&self, access_token: &str) -> Result<bool, String> {
+    /// Revoke a token (access or refresh) per OAuth 2.0 Token Revocation
+    /// (RFC 7009), routed through the discovered `revocation_endpoint` when
+    /// available, otherwise `{base_url}/api/auth/device/revoke`.
+    ///
+    /// `token_type_hint` should be `"access_token"` or `"refresh_token"` when
+    /// known, to save the server a lookup. Revoking a refresh token also
+    /// invalidates any access token issued alongside it, so callers logging a
+    /// user out only need to revoke the refresh token (when one is stored)
+    /// and can then drop both from local storage.
+    pub async fn revoke_token(
+        &self,
+        token: &str,
+        token_type_hint: Option<&str>,
+    ) -> Result<(), String> {
+        let mut body = serde_json::json!({ "token": token });
+        if let Some(hint) = token_type_hint {
+            body["token_type_hint"] = serde_json::Value::from(hint);
+        }
+
+        let url = self.resolve_endpoint(|m| m.revocation_endpoint.clone(), "revoke").await;
+        let response = self
+            .send_with_retry(|| self.client.post(&url).json(&body))
+            .await?;
+
+        if is_revocation_success(response.status()) {
+            return Ok(());
+        }
+
+        Err(format!("Server error: {}", response.status()))
+    }
+
+    /// Upload a compressed debug bundle to the support endpoint, returning the
+    /// support reference id the backend assigns.
+    ///
+    /// The archive is read into memory and streamed as a multipart part; bundles
+    /// are small (a capped report plus rotated logs) so a single part is fine.
+    pub async fn upload_debug_bundle(
+        &self,
+        access_token: &str,
+        bundle_path: &std::path::Path,
+    ) -> Result<String, String> {
+        let bytes = tokio::fs::read(bundle_path)
+            .await
+            .map_err(|e| format!("Failed to read debug bundle: {}", e))?;
+        let filename = bundle_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("debug_bundle.tar.gz")
+            .to_string();
+
+        // Rebuilt per attempt since reqwest's multipart Form isn't Clone.
+        let response = self
+            .send_with_retry(|| {
+                let part = reqwest::multipart::Part::bytes(bytes.clone())
+                    .file_name(filename.clone())
+                    .mime_str("application/gzip")
+                    .expect("\"application/gzip\" is a valid mime type");
+                let form = reqwest::multipart::Form::new().part("bundle", part);
+                self.client
+                    .post(format!("{}/api/support/debug-bundle", self.base_url))
+                    .bearer_auth(access_token)
+                    .multipart(form)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Failed to upload debug bundle: {}", response.status()));
+        }
+
+        #[derive(Deserialize)]
+        struct BundleResponse {
+            reference_id: String,
+        }
+
+        let parsed: BundleResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        Ok(parsed.reference_id)
+    }
+
+    /// Introspect an access token, returning the server's full
+    /// OAuth 2.0-style introspection payload rather than just a pass/fail
+    /// boolean. Lets callers pre-emptively refresh ahead of `expires_at`,
+    /// display who's logged in, and enforce `scope` without an extra round
+    /// trip.
+    pub async fn introspect_token(&self, access_token: &str) -> Result<TokenIntrospection, String> {
         let response = self.client
             .post(self.device_auth_url("verify"))
             .json(&serde_json::json!({
@@ -154,17 +587,52 @@ impl ApiClient {
             .await
             .map_err(|e| format!("Network error: {}", e))?;
 
-        #[derive(Deserialize)]
-        struct VerifyResponse {
-            valid: bool,
-        }
+        response
+            .json::<TokenIntrospection>()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
 
-        let verify_resp: VerifyResponse = response
-            .json()
+    /// Verify an access token is still active.
+    pub async fn verify_token(&self, access_token: &str) -> Result<bool, String> {
+        self.introspect_token(access_token)
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+            .map(|introspection| introspection.active)
+    }
+
+    /// Decode the `exp` claim out of a JWT `access_token` locally and return
+    /// how much of its lifetime remains, with no network call. Callers can
+    /// use this to skip a [`verify_token`](Self::verify_token) round trip
+    /// when plenty of time remains, and pre-emptively refresh once only a
+    /// handful of seconds are left.
+    ///
+    /// Returns `None` for opaque (non-JWT) tokens, a payload that doesn't
+    /// base64-decode or parse as JSON, a missing `exp` claim, or a token that
+    /// has already expired — in every case the caller should fall back to
+    /// the server-side `verify_token` check rather than treat `None` as "ok".
+    pub fn token_time_remaining(access_token: &str) -> Option<Duration> {
+        let mut segments = access_token.split('.');
+        let (_header, payload, _signature) =
+            (segments.next()?, segments.next()?, segments.next()?);
+        if segments.next().is_some() {
+            return None; // Not a 3-segment JWT.
+        }
+
+        let payload_bytes = B64URL
+            .decode(payload)
+            .or_else(|_| base64::engine::general_purpose::URL_SAFE.decode(payload))
+            .ok()?;
+        let claims: serde_json::Value = serde_json::from_slice(&payload_bytes).ok()?;
+        let exp = claims.get("exp")?.as_u64()?;
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
 
-        Ok(verify_resp.valid)
+        exp.checked_sub(now)
+            .filter(|&remaining| remaining > 0)
+            .map(Duration::from_secs)
     }
 }
 
@@ -189,6 +657,8 @@ mod tests {
         let client = ApiClient {
             base_url: "https://example.com".to_string(),
             client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            metadata: std::sync::Mutex::new(None),
         };
 
         assert_eq!(
@@ -215,6 +685,7 @@ mod tests {
             verification_uri: "https://example.com/activate?code=ABCD-1234".to_string(),
             expires_in: 900,
             interval: 5,
+            code_verifier: "verifier".to_string(),
         };
 
         let serialized = serde_json::to_string(&response).unwrap();
@@ -309,6 +780,7 @@ mod tests {
             verification_uri: "https://example.com".to_string(),
             expires_in: 900,
             interval: 5,
+            code_verifier: "verifier".to_string(),
         };
 
         let cloned = response.clone();
@@ -343,6 +815,7 @@ mod tests {
                 username: "Test".to_string(),
                 avatar_url: "https://example.com".to_string(),
             },
+            device_id: None,
         };
 
         let cloned = response.clone();
@@ -363,6 +836,50 @@ mod tests {
         assert_eq!(response.valid, true);
     }
 
+    #[test]
+    fn test_pkce_challenge_verifier_in_rfc_range() {
+        let pkce = PkceChallenge::generate();
+        assert!(pkce.code_verifier.len() >= 43 && pkce.code_verifier.len() <= 128);
+        // RFC 7636's unreserved character set: ALPHA / DIGIT / "-" / "." / "_" / "~".
+        assert!(pkce
+            .code_verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_pkce_challenge_is_sha256_of_verifier() {
+        let pkce = PkceChallenge::generate();
+        let expected = B64URL.encode(Sha256::digest(pkce.code_verifier.as_bytes()));
+        assert_eq!(pkce.code_challenge, expected);
+        assert_ne!(pkce.code_challenge, pkce.code_verifier);
+    }
+
+    #[test]
+    fn test_pkce_challenge_verifiers_are_unique() {
+        let a = PkceChallenge::generate();
+        let b = PkceChallenge::generate();
+        assert_ne!(a.code_verifier, b.code_verifier);
+    }
+
+    #[tokio::test]
+    async fn test_await_authorization_expires_immediately() {
+        // With expires_in == 0 the deadline is already in the past, so the loop
+        // bails out as expired before ever touching the network.
+        let client = ApiClient::new();
+        let device = DeviceCodeResponse {
+            device_code: "dc".to_string(),
+            user_code: "uc".to_string(),
+            verification_uri: "https://example.com".to_string(),
+            expires_in: 0,
+            interval: 5,
+            code_verifier: "verifier".to_string(),
+        };
+
+        let result = client.await_authorization(&device).await;
+        assert_eq!(result.unwrap_err(), "expired");
+    }
+
     #[test]
     fn test_verify_response_deserialize_invalid() {
         let json = r#"{"valid": false, "error": "token_expired"}"#;
@@ -375,4 +892,210 @@ mod tests {
         let response: VerifyResponse = serde_json::from_str(json).unwrap();
         assert_eq!(response.valid, false);
     }
+
+    #[test]
+    fn test_token_introspection_deserialize_active() {
+        let json = r#"{"valid": true, "userId": "123", "expires_at": 1234567890}"#;
+
+        let introspection: TokenIntrospection = serde_json::from_str(json).unwrap();
+        assert!(introspection.active);
+        assert_eq!(introspection.user_id, Some("123".to_string()));
+        assert_eq!(introspection.expires_at, Some(1234567890));
+        assert_eq!(introspection.scope, None);
+    }
+
+    #[test]
+    fn test_token_introspection_deserialize_inactive_without_extras() {
+        let json = r#"{"valid": false}"#;
+
+        let introspection: TokenIntrospection = serde_json::from_str(json).unwrap();
+        assert!(!introspection.active);
+        assert_eq!(introspection.user_id, None);
+        assert_eq!(introspection.expires_at, None);
+    }
+
+    /// Build a throwaway JWT with the given `exp` claim (and a dummy header
+    /// and signature) for exercising [`ApiClient::token_time_remaining`]
+    /// without a real signer.
+    fn fake_jwt(exp: u64) -> String {
+        let header = B64URL.encode(r#"{"alg":"none"}"#);
+        let payload = B64URL.encode(format!(r#"{{"exp":{}}}"#, exp));
+        format!("{}.{}.", header, payload)
+    }
+
+    #[test]
+    fn test_token_time_remaining_not_yet_expired() {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let token = fake_jwt(now + 3600);
+
+        let remaining = ApiClient::token_time_remaining(&token).unwrap();
+        assert!(remaining.as_secs() > 3500 && remaining.as_secs() <= 3600);
+    }
+
+    #[test]
+    fn test_token_time_remaining_already_expired() {
+        let token = fake_jwt(1);
+        assert_eq!(ApiClient::token_time_remaining(&token), None);
+    }
+
+    #[test]
+    fn test_token_time_remaining_opaque_token() {
+        assert_eq!(ApiClient::token_time_remaining("not-a-jwt-at-all"), None);
+    }
+
+    #[test]
+    fn test_token_time_remaining_malformed_payload() {
+        // Valid base64url, but not JSON.
+        let token = format!("{}.{}.", B64URL.encode("header"), B64URL.encode("not json"));
+        assert_eq!(ApiClient::token_time_remaining(&token), None);
+    }
+
+    #[test]
+    fn test_token_time_remaining_missing_exp_claim() {
+        let payload = B64URL.encode(r#"{"sub":"123"}"#);
+        let token = format!("{}.{}.", B64URL.encode("header"), payload);
+        assert_eq!(ApiClient::token_time_remaining(&token), None);
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        for status in [429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(status), "{status} should be retryable");
+        }
+        for status in [200, 400, 403, 404, 410, 428] {
+            assert!(!is_retryable_status(status), "{status} should not be retryable");
+        }
+    }
+
+    #[test]
+    fn test_is_revocation_success() {
+        assert!(is_revocation_success(reqwest::StatusCode::OK));
+        assert!(is_revocation_success(reqwest::StatusCode::NO_CONTENT));
+        assert!(is_revocation_success(reqwest::StatusCode::NOT_FOUND));
+        assert!(!is_revocation_success(reqwest::StatusCode::BAD_REQUEST));
+        assert!(!is_revocation_success(reqwest::StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+
+        // Jitter only ever adds, so the un-jittered exponential value is a lower bound.
+        assert!(backoff_delay(&policy, 0) >= Duration::from_millis(100));
+        assert!(backoff_delay(&policy, 1) >= Duration::from_millis(200));
+        assert!(backoff_delay(&policy, 2) >= Duration::from_millis(400));
+        // Large attempt counts clamp to max_delay (plus up to 50% jitter).
+        assert!(backoff_delay(&policy, 40) <= policy.max_delay.mul_f64(1.5));
+        assert!(backoff_delay(&policy, 40) >= policy.max_delay);
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 5 "), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date_in_future() {
+        let future = chrono::Utc::now() + chrono::Duration::seconds(60);
+        let header = future.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+
+        let delay = parse_retry_after(&header).expect("valid HTTP-date should parse");
+        // Allow a little slack for the time it took to format/parse.
+        assert!(delay.as_secs() >= 55 && delay.as_secs() <= 61);
+    }
+
+    #[test]
+    fn test_parse_retry_after_invalid_value() {
+        assert_eq!(parse_retry_after("not-a-valid-value"), None);
+    }
+
+    #[test]
+    fn test_server_metadata_deserialize() {
+        let json = r#"{
+            "issuer": "https://example.com",
+            "device_authorization_endpoint": "https://example.com/oauth/device/code",
+            "token_endpoint": "https://example.com/oauth/token",
+            "introspection_endpoint": "https://example.com/oauth/introspect"
+        }"#;
+
+        let metadata: ServerMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(metadata.issuer, "https://example.com");
+        assert_eq!(
+            metadata.device_authorization_endpoint,
+            Some("https://example.com/oauth/device/code".to_string())
+        );
+        assert_eq!(metadata.revocation_endpoint, None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_endpoint_uses_cached_metadata() {
+        let client = ApiClient {
+            base_url: "https://example.com".to_string(),
+            client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            metadata: std::sync::Mutex::new(Some(ServerMetadata {
+                issuer: "https://example.com".to_string(),
+                device_authorization_endpoint: Some(
+                    "https://example.com/custom/device/code".to_string(),
+                ),
+                token_endpoint: None,
+                introspection_endpoint: None,
+                revocation_endpoint: None,
+            })),
+        };
+
+        let url = client
+            .resolve_endpoint(|m| m.device_authorization_endpoint.clone(), "code")
+            .await;
+        assert_eq!(url, "https://example.com/custom/device/code");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_endpoint_falls_back_when_field_absent_in_metadata() {
+        let client = ApiClient {
+            base_url: "https://example.com".to_string(),
+            client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            metadata: std::sync::Mutex::new(Some(ServerMetadata {
+                issuer: "https://example.com".to_string(),
+                device_authorization_endpoint: None,
+                token_endpoint: None,
+                introspection_endpoint: None,
+                revocation_endpoint: None,
+            })),
+        };
+
+        let url = client.resolve_endpoint(|m| m.token_endpoint.clone(), "poll").await;
+        assert_eq!(url, "https://example.com/api/auth/device/poll");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_endpoint_falls_back_when_discovery_fails() {
+        // Port 9 (discard) has nothing listening, so the connection is
+        // refused immediately instead of hanging; zeroing out the retry
+        // policy keeps the test from waiting through any backoff.
+        let client = ApiClient {
+            base_url: "http://127.0.0.1:9".to_string(),
+            client: reqwest::Client::new(),
+            retry_policy: RetryPolicy {
+                max_retries: 0,
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(1),
+            },
+            metadata: std::sync::Mutex::new(None),
+        };
+
+        let url = client
+            .resolve_endpoint(|m| m.device_authorization_endpoint.clone(), "code")
+            .await;
+        assert_eq!(url, "http://127.0.0.1:9/api/auth/device/code");
+    }
 }
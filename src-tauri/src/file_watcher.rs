@@ -19,12 +19,74 @@
 
 use crate::debug_logger::DebugLogger;
 use notify::{Event, EventKind, RecursiveMode, Watcher};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, mpsc, watch};
+
+/// Which detection path surfaced a replay, carried on every [`WatcherEvent`] so
+/// subscribers can distinguish an OS-native notification from a polling sweep or
+/// a heartbeat-recovery scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSource {
+    /// Delivered by the OS-native watcher.
+    Native,
+    /// Found by the periodic polling fallback or a poll-backend watcher
+    /// (including the one started for folders that fail the cookie probe).
+    Poll,
+    /// Surfaced by a heartbeat-recovery scan after the watcher went silent.
+    HeartbeatRecovery,
+}
+
+/// A single debounced replay detection, broadcast to every subscriber.
+///
+/// The same event feeds the uploader pipeline, any UI status panel, and logging
+/// sinks independently; each [`subscribe`](RobustFileWatcher::subscribe)r gets
+/// its own clone.
+#[derive(Debug, Clone)]
+pub struct WatcherEvent {
+    /// The detected replay path, as it existed when the quiet window elapsed.
+    pub path: PathBuf,
+    /// Which backend surfaced the file.
+    pub source: EventSource,
+    /// Seconds since the Unix epoch when the event was flushed.
+    pub timestamp: u64,
+}
+
+/// Capacity of the broadcast channel. A slow subscriber that lags past this many
+/// detections receives `RecvError::Lagged` and resumes from the newest event —
+/// replay detection is not so dense that this drops anything in practice.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// A detection before debouncing, carrying the source so the flushed
+/// [`WatcherEvent`] can report where it came from.
+#[derive(Debug, Clone)]
+struct RawDetection {
+    path: PathBuf,
+    source: EventSource,
+}
+
+/// Which backend the native watcher task uses to detect changes.
+///
+/// `Native` relies on the OS notification API (`ReadDirectoryChangesW`,
+/// `inotify`, …) via [`notify::recommended_watcher`], which is cheap but
+/// silently delivers nothing on some network drives, SMB shares, and
+/// virtualized install locations. `Poll` forces [`notify::PollWatcher`] at a
+/// fixed cadence for those filesystems. `Auto` starts native but downgrades to
+/// polling for the rest of the session if the heartbeat monitor sees the native
+/// backend repeatedly go silent.
+#[derive(Debug, Clone)]
+pub enum WatcherBackend {
+    Native,
+    Poll(Duration),
+    Auto,
+}
+
+/// Number of consecutive heartbeat timeouts in `Auto` mode before the native
+/// backend is abandoned in favour of polling.
+const AUTO_DOWNGRADE_TIMEOUTS: u32 = 3;
 
 /// Configuration for the robust file watcher
 #[derive(Clone)]
@@ -35,8 +97,26 @@ pub struct WatcherConfig {
     pub heartbeat_timeout_secs: u64,
     /// How often to poll for files as a fallback (seconds)
     pub poll_interval_secs: u64,
-    /// Delay before processing a new file (milliseconds)
+    /// Delay before processing a new file (milliseconds).
+    ///
+    /// Superseded by [`WatcherConfig::debounce_window_ms`]; retained for
+    /// configuration compatibility.
     pub file_processing_delay_ms: u64,
+    /// Quiet window (milliseconds) a file must go without further events before
+    /// the debouncer flushes it to the callback, guaranteeing the replay is
+    /// fully written to disk and collapsing bursty create/modify/rename events.
+    pub debounce_window_ms: u64,
+    /// Which detection backend to use (see [`WatcherBackend`]).
+    pub backend: WatcherBackend,
+    /// Maximum directory depth a poll/recovery scan descends into, relative to
+    /// each watched folder. `None` is unlimited; `Some(0)` scans only the
+    /// top-level folder without recursing into subdirectories. Lets users with
+    /// deeply nested or network-mounted replay trees bound the cost of a sweep.
+    pub max_scan_depth: Option<usize>,
+    /// Maximum number of concurrent `read_dir` tasks a scan runs at once. Caps
+    /// the in-flight fan-out of the bounded walker; defaults to the available
+    /// parallelism of the machine.
+    pub max_concurrent_scanners: usize,
 }
 
 impl Default for WatcherConfig {
@@ -49,10 +129,22 @@ impl Default for WatcherConfig {
             file_processing_delay_ms: 1000,   // Windows needs more time
             #[cfg(not(target_os = "windows"))]
             file_processing_delay_ms: 500,
+            debounce_window_ms: 1000,         // 1s quiet window before flushing
+            backend: WatcherBackend::Auto,
+            max_scan_depth: None,             // Unlimited depth by default
+            max_concurrent_scanners: default_scan_concurrency(),
         }
     }
 }
 
+/// Default number of concurrent scanner tasks: the machine's available
+/// parallelism, falling back to a small fixed pool where it cannot be queried.
+fn default_scan_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
 /// Check if a path is an SC2 replay file (case-insensitive)
 #[inline]
 pub fn is_sc2_replay(path: &Path) -> bool {
@@ -71,6 +163,28 @@ pub struct WatcherStats {
     pub restarts: u64,
     pub poll_scans: u64,
     pub poll_finds: u64,
+    /// Number of times `Auto` mode fell back from native to poll detection.
+    pub poll_downgrades: u64,
+    /// Number of folders whose startup cookie probe timed out (native watcher
+    /// confirmed dead-on-arrival).
+    pub cookie_timeouts: u64,
+}
+
+/// Filename prefix for the startup sentinel files used to confirm the native
+/// watcher actually delivers events. Never matches [`is_sc2_replay`].
+const COOKIE_PREFIX: &str = ".llu-cookie-";
+
+/// How long the cookie handshake waits for the sentinel event per folder.
+const COOKIE_PROBE_TIMEOUT_SECS: u64 = 5;
+
+/// Whether a path is one of our startup cookie sentinels, so event processing
+/// never mistakes it for a replay.
+#[inline]
+fn is_cookie(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .map(|n| n.starts_with(COOKIE_PREFIX))
+        .unwrap_or(false)
 }
 
 /// A robust file watcher that handles Windows ReadDirectoryChangesW issues
@@ -78,7 +192,9 @@ pub struct RobustFileWatcher<F>
 where
     F: Fn(PathBuf) + Send + Sync + 'static,
 {
-    folders: Vec<PathBuf>,
+    /// Watched folders. Behind a mutex so [`reconfigure`](Self::reconfigure) can
+    /// swap the list and rebuild the watcher without leaking the old one.
+    folders: Arc<std::sync::Mutex<Vec<PathBuf>>>,
     config: WatcherConfig,
     logger: Arc<DebugLogger>,
     callback: Arc<F>,
@@ -90,6 +206,39 @@ where
     is_running: Arc<AtomicBool>,
     /// Stats for debugging
     stats: Arc<tokio::sync::Mutex<WatcherStats>>,
+    /// Set once `Auto` mode has downgraded to polling, so the downgrade happens
+    /// at most once per session.
+    downgraded: Arc<AtomicBool>,
+    /// Broadcast sink for debounced detections. Cloned into by every
+    /// [`subscribe`](Self::subscribe)r; the owned closure is itself just one
+    /// subscriber drained by a task spawned in [`start`](Self::start).
+    events_tx: broadcast::Sender<WatcherEvent>,
+    /// Latest stats snapshot, published for live status panels via
+    /// [`subscribe_stats`](Self::subscribe_stats).
+    stats_tx: watch::Sender<WatcherStats>,
+    /// Handle to the primary native watcher thread. Held so [`stop`](Self::stop)
+    /// and the heartbeat monitor can drop it — releasing the OS watch handle and
+    /// terminating the thread — and rebuild it in place.
+    native_handle: Arc<std::sync::Mutex<Option<NativeWatcherHandle>>>,
+    /// Handles to any auxiliary poll watchers spawned for dead folders (cookie
+    /// fallback) or after an `Auto` downgrade. Cleared on [`stop`](Self::stop).
+    poll_handles: Arc<std::sync::Mutex<Vec<NativeWatcherHandle>>>,
+    /// Monotonic start epoch. Each [`start`](Self::start) bumps it and the
+    /// background tasks capture the value they launched under, so a
+    /// [`restart`](Self::restart) — which flips `is_running` back on — leaves no
+    /// stale task from the previous generation running alongside the new ones.
+    generation: Arc<AtomicU64>,
+}
+
+/// Keeps a spawned watcher thread alive.
+///
+/// Dropping the handle closes the shutdown channel, so the watcher thread's
+/// `recv` returns `Disconnected`, breaks out of its keep-alive loop, and drops
+/// the underlying [`notify::Watcher`] — promptly releasing the OS watch handle
+/// instead of leaking it until process exit.
+struct NativeWatcherHandle {
+    #[allow(dead_code)] // Held for its drop side-effect, not read directly.
+    shutdown_tx: std::sync::mpsc::Sender<()>,
 }
 
 impl<F> RobustFileWatcher<F>
@@ -112,8 +261,10 @@ where
         callback: F,
         config: WatcherConfig,
     ) -> Self {
+        let (events_tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let (stats_tx, _) = watch::channel(WatcherStats::default());
         Self {
-            folders,
+            folders: Arc::new(std::sync::Mutex::new(folders)),
             config,
             logger,
             callback: Arc::new(callback),
@@ -121,9 +272,42 @@ where
             last_event_time: Arc::new(AtomicU64::new(current_timestamp())),
             is_running: Arc::new(AtomicBool::new(false)),
             stats: Arc::new(tokio::sync::Mutex::new(WatcherStats::default())),
+            downgraded: Arc::new(AtomicBool::new(false)),
+            events_tx,
+            stats_tx,
+            native_handle: Arc::new(std::sync::Mutex::new(None)),
+            poll_handles: Arc::new(std::sync::Mutex::new(Vec::new())),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Snapshot the current watched-folder list.
+    fn folders(&self) -> Vec<PathBuf> {
+        // Use unwrap_or_else to recover from poisoned mutex
+        self.folders.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Subscribe to debounced replay detections.
+    ///
+    /// Each subscriber receives its own clone of every [`WatcherEvent`], so the
+    /// uploader pipeline, a UI status panel, and a logging sink can all consume
+    /// detections independently of the owned callback. Subscribing before
+    /// [`start`](Self::start) guarantees no early event is missed.
+    #[allow(dead_code)] // Consumed by downstream pipelines (uploader, UI, logging)
+    pub fn subscribe(&self) -> broadcast::Receiver<WatcherEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Subscribe to a live stream of [`WatcherStats`] snapshots.
+    ///
+    /// The returned receiver always holds the most recent snapshot and is woken
+    /// whenever the stats change, which suits a status panel polling for display
+    /// without locking the internal stats mutex.
+    #[allow(dead_code)] // Exposed for UI status panels
+    pub fn subscribe_stats(&self) -> watch::Receiver<WatcherStats> {
+        self.stats_tx.subscribe()
+    }
+
     /// Get current watcher statistics
     #[allow(dead_code)]  // Exposed for debugging purposes
     pub async fn get_stats(&self) -> WatcherStats {
@@ -141,16 +325,38 @@ where
             return Err("File watcher is already running".to_string());
         }
 
+        // Bump the epoch so any task left over from a previous generation exits
+        // even though `is_running` is back on. All tasks spawned below read this
+        // same value.
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
         self.logger.info(format!(
             "Starting robust file watcher for {} folder(s)",
-            self.folders.len()
+            // Use unwrap_or_else to recover from poisoned mutex
+            self.folders.lock().unwrap_or_else(|e| e.into_inner()).len()
         ));
 
         // Channel for file events
-        let (tx, rx) = mpsc::channel::<PathBuf>(100);
+        let (tx, rx) = mpsc::channel::<RawDetection>(100);
+
+        // Drain one subscription into the owned callback, so the closure
+        // constructor is just another consumer of the broadcast stream.
+        self.spawn_callback_drainer();
 
-        // Start the native watcher
-        self.start_native_watcher(tx.clone()).await?;
+        // Publish stats snapshots for live status subscribers.
+        self.spawn_stats_publisher();
+
+        // Start the native watcher, retaining its handle so we can tear it down
+        // and rebuild it in place.
+        let handle = self.start_native_watcher(tx.clone()).await?;
+        // Use unwrap_or_else to recover from poisoned mutex
+        *self.native_handle.lock().unwrap_or_else(|e| e.into_inner()) = Some(handle);
+
+        // Confirm the native backend actually delivers events; fall back to
+        // polling for any folder whose cookie handshake times out.
+        if matches!(self.config.backend, WatcherBackend::Native | WatcherBackend::Auto) {
+            self.probe_and_fallback(tx.clone()).await;
+        }
 
         // Start heartbeat monitor
         self.start_heartbeat_monitor(tx.clone());
@@ -165,37 +371,155 @@ where
         Ok(())
     }
 
-    /// Start the native file system watcher
-    async fn start_native_watcher(
-        &self,
-        tx: mpsc::Sender<PathBuf>,
-    ) -> Result<(), String> {
-        let folders = self.folders.clone();
+    /// Spawn a task that drains one event subscription into the owned callback.
+    fn spawn_callback_drainer(&self) {
+        let callback = self.callback.clone();
         let logger = self.logger.clone();
-        let last_event_time = self.last_event_time.clone();
+        let is_running = self.is_running.clone();
+        let generation = self.generation.clone();
+        let my_gen = generation.load(Ordering::SeqCst);
+        let mut rx = self.events_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => callback(event.path),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        logger.warn(format!(
+                            "Callback drain lagged, skipped {} detection(s)",
+                            skipped
+                        ));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+                if !is_running.load(Ordering::SeqCst)
+                    || generation.load(Ordering::SeqCst) != my_gen
+                {
+                    break;
+                }
+            }
+            logger.debug("Callback drain task ended".to_string());
+        });
+    }
+
+    /// Spawn a task that publishes periodic [`WatcherStats`] snapshots to the
+    /// watch channel for live status subscribers.
+    fn spawn_stats_publisher(&self) {
         let stats = self.stats.clone();
+        let stats_tx = self.stats_tx.clone();
         let is_running = self.is_running.clone();
+        let generation = self.generation.clone();
+        let my_gen = generation.load(Ordering::SeqCst);
+        tokio::spawn(async move {
+            let mut timer = tokio::time::interval(Duration::from_secs(1));
+            loop {
+                timer.tick().await;
+                if !is_running.load(Ordering::SeqCst)
+                    || generation.load(Ordering::SeqCst) != my_gen
+                {
+                    break;
+                }
+                let snapshot = stats.lock().await.clone();
+                // Ignore send errors: with no subscribers there is nothing to do.
+                let _ = stats_tx.send(snapshot);
+            }
+        });
+    }
 
+    /// Start the native file system watcher using the configured backend.
+    ///
+    /// Returns a [`NativeWatcherHandle`] whose lifetime controls the spawned
+    /// watcher thread: dropping it stops the thread and releases the OS watch
+    /// handle, so the heartbeat monitor can rebuild the watcher in place.
+    async fn start_native_watcher(
+        &self,
+        tx: mpsc::Sender<RawDetection>,
+    ) -> Result<NativeWatcherHandle, String> {
+        // In `Auto` mode we start on the native backend and let the heartbeat
+        // monitor downgrade us to poll later if it stays silent.
+        let backend = match &self.config.backend {
+            WatcherBackend::Poll(d) => WatcherBackend::Poll(*d),
+            _ => WatcherBackend::Native,
+        };
+        let handle = Self::spawn_backend_watcher(
+            backend,
+            self.folders(),
+            self.logger.clone(),
+            self.last_event_time.clone(),
+            self.stats.clone(),
+            self.is_running.clone(),
+            tx,
+        );
+        Ok(handle)
+    }
+
+    /// Spawn the OS watcher thread plus its async event pump for the given
+    /// backend. Shared so the heartbeat monitor can re-spawn in poll mode when
+    /// `Auto` downgrades.
+    ///
+    /// The returned [`NativeWatcherHandle`] owns a shutdown sender; dropping it
+    /// disconnects the watcher thread's receiver, which breaks its keep-alive
+    /// loop and drops the `notify::Watcher`.
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_backend_watcher(
+        backend: WatcherBackend,
+        folders: Vec<PathBuf>,
+        logger: Arc<DebugLogger>,
+        last_event_time: Arc<AtomicU64>,
+        stats: Arc<tokio::sync::Mutex<WatcherStats>>,
+        is_running: Arc<AtomicBool>,
+        tx: mpsc::Sender<RawDetection>,
+    ) -> NativeWatcherHandle {
+        // Detections from this watcher are tagged Poll when it is a PollWatcher,
+        // otherwise Native.
+        let source = match &backend {
+            WatcherBackend::Poll(_) => EventSource::Poll,
+            _ => EventSource::Native,
+        };
         // Create the watcher in a separate thread (notify requires sync context)
         let (watcher_tx, mut watcher_rx) = mpsc::channel::<Result<Event, notify::Error>>(100);
+        // Shutdown primitive: dropping the returned sender disconnects this
+        // receiver so the watcher thread can exit promptly and drop the watcher.
+        let (shutdown_tx, shutdown_rx) = std::sync::mpsc::channel::<()>();
 
         let logger_for_watcher = logger.clone();
         std::thread::spawn(move || {
             let watcher_tx_clone = watcher_tx.clone();
             let logger_clone = logger_for_watcher.clone();
 
-            let mut watcher = match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            let handler = move |res: Result<Event, notify::Error>| {
                 // Send event to async channel
                 if let Err(e) = watcher_tx_clone.blocking_send(res) {
                     // Channel closed, watcher should stop
                     logger_clone.debug(format!("Watcher channel closed: {}", e));
                 }
-            }) {
-                Ok(w) => w,
-                Err(e) => {
-                    logger_for_watcher.error(format!("Failed to create watcher: {}", e));
-                    return;
+            };
+
+            // Poll mode uses notify's portable PollWatcher at a chosen cadence;
+            // everything else uses the OS-native recommended backend.
+            let mut watcher: Box<dyn Watcher + Send> = match &backend {
+                WatcherBackend::Poll(interval) => {
+                    logger_for_watcher.info(format!(
+                        "Using poll watcher backend (interval: {:?})",
+                        interval
+                    ));
+                    match notify::PollWatcher::new(
+                        handler,
+                        notify::Config::default().with_poll_interval(*interval),
+                    ) {
+                        Ok(w) => Box::new(w),
+                        Err(e) => {
+                            logger_for_watcher.error(format!("Failed to create poll watcher: {}", e));
+                            return;
+                        }
+                    }
                 }
+                _ => match notify::recommended_watcher(handler) {
+                    Ok(w) => Box::new(w),
+                    Err(e) => {
+                        logger_for_watcher.error(format!("Failed to create watcher: {}", e));
+                        return;
+                    }
+                },
             };
 
             // Watch all folders
@@ -216,13 +540,23 @@ where
 
             logger_for_watcher.debug("Native watcher thread started".to_string());
 
-            // Keep the watcher alive by holding it in this thread
-            // The thread will exit when watcher_rx is dropped (on app shutdown)
+            // Keep the watcher alive by holding it in this thread, waking once a
+            // second unless the shutdown signal arrives first. A disconnected
+            // channel (handle dropped) or an explicit send both tear down.
             loop {
-                std::thread::sleep(std::time::Duration::from_secs(1));
-                // Check if we should stop
-                // In a real app, you'd have a shutdown signal here
+                match shutdown_rx.recv_timeout(std::time::Duration::from_secs(1)) {
+                    Ok(()) | Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                        logger_for_watcher
+                            .debug("Native watcher thread shutting down".to_string());
+                        break;
+                    }
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+                }
             }
+
+            // Drop the watcher explicitly so the OS watch handle is released
+            // immediately rather than leaking until process exit.
+            drop(watcher);
         });
 
         // Process watcher events in async context
@@ -255,7 +589,7 @@ where
                                         let mut s = stats.lock().await;
                                         s.replays_detected += 1;
                                     }
-                                    if let Err(e) = tx_clone.send(path).await {
+                                    if let Err(e) = tx_clone.send(RawDetection { path, source }).await {
                                         logger_clone.warn(format!(
                                             "Failed to queue replay: {}",
                                             e
@@ -292,26 +626,78 @@ where
             logger_clone.warn("Native watcher event loop ended".to_string());
         });
 
-        Ok(())
+        NativeWatcherHandle { shutdown_tx }
+    }
+
+    /// Probe each watched folder with a cookie handshake and fall back to poll
+    /// mode for any folder where the native watcher fails to deliver the event.
+    async fn probe_and_fallback(&self, tx: mpsc::Sender<RawDetection>) {
+        let mut dead_folders: Vec<PathBuf> = Vec::new();
+        for folder in self.folders() {
+            let folder = &folder;
+            if probe_native_delivery(folder, &self.logger, COOKIE_PROBE_TIMEOUT_SECS).await {
+                self.logger.debug(format!(
+                    "Native watcher confirmed live for {}",
+                    folder.display()
+                ));
+            } else {
+                self.logger.warn(format!(
+                    "Cookie probe timed out for {}; falling back to poll mode",
+                    folder.display()
+                ));
+                {
+                    let mut s = self.stats.lock().await;
+                    s.cookie_timeouts += 1;
+                }
+                dead_folders.push(folder.clone());
+            }
+        }
+
+        if !dead_folders.is_empty() {
+            // Mark as downgraded so the heartbeat monitor doesn't also spawn a
+            // second poll watcher for the same session.
+            self.downgraded.store(true, Ordering::SeqCst);
+            let handle = Self::spawn_backend_watcher(
+                WatcherBackend::Poll(Duration::from_secs(self.config.poll_interval_secs)),
+                dead_folders,
+                self.logger.clone(),
+                self.last_event_time.clone(),
+                self.stats.clone(),
+                self.is_running.clone(),
+                tx,
+            );
+            // Use unwrap_or_else to recover from poisoned mutex
+            self.poll_handles.lock().unwrap_or_else(|e| e.into_inner()).push(handle);
+        }
     }
 
     /// Start heartbeat monitor that restarts watcher if it appears dead
-    fn start_heartbeat_monitor(&self, tx: mpsc::Sender<PathBuf>) {
+    fn start_heartbeat_monitor(&self, tx: mpsc::Sender<RawDetection>) {
         let logger = self.logger.clone();
         let last_event_time = self.last_event_time.clone();
         let config = self.config.clone();
         let stats = self.stats.clone();
+        let downgraded = self.downgraded.clone();
         let is_running = self.is_running.clone();
-        let folders = self.folders.clone();
+        let folders = self.folders();
+        let native_handle = self.native_handle.clone();
+        let poll_handles = self.poll_handles.clone();
+        let generation = self.generation.clone();
+        let my_gen = generation.load(Ordering::SeqCst);
 
         tokio::spawn(async move {
             let interval = Duration::from_secs(config.heartbeat_interval_secs);
             let timeout = config.heartbeat_timeout_secs;
+            let is_auto = matches!(config.backend, WatcherBackend::Auto);
+            // Consecutive heartbeat timeouts, used to trigger the Auto downgrade.
+            let mut consecutive_timeouts: u32 = 0;
 
             loop {
                 tokio::time::sleep(interval).await;
 
-                if !is_running.load(Ordering::SeqCst) {
+                if !is_running.load(Ordering::SeqCst)
+                    || generation.load(Ordering::SeqCst) != my_gen
+                {
                     break;
                 }
 
@@ -325,9 +711,10 @@ where
                 ));
 
                 if elapsed > timeout {
+                    consecutive_timeouts += 1;
                     logger.warn(format!(
-                        "Watcher heartbeat timeout ({}s without events), triggering recovery",
-                        elapsed
+                        "Watcher heartbeat timeout ({}s without events, {} consecutive), triggering recovery",
+                        elapsed, consecutive_timeouts
                     ));
 
                     {
@@ -337,15 +724,74 @@ where
 
                     // Trigger a poll scan to catch any missed files
                     logger.info("Performing recovery poll scan...".to_string());
-                    let found = poll_folders_for_replays(&folders, &logger).await;
+                    let found = poll_folders_for_replays(
+                        &folders,
+                        logger.clone(),
+                        config.max_concurrent_scanners,
+                        config.max_scan_depth,
+                    )
+                    .await;
                     for path in found {
-                        if let Err(e) = tx.send(path).await {
+                        let detection = RawDetection {
+                            path,
+                            source: EventSource::HeartbeatRecovery,
+                        };
+                        if let Err(e) = tx.send(detection).await {
                             logger.warn(format!("Failed to queue recovery replay: {}", e));
                         }
                     }
 
+                    // Auto mode: after enough consecutive silent windows the
+                    // native backend is presumed dead on this filesystem, so
+                    // switch to polling for the rest of the session.
+                    if is_auto
+                        && consecutive_timeouts >= AUTO_DOWNGRADE_TIMEOUTS
+                        && !downgraded.swap(true, Ordering::SeqCst)
+                    {
+                        logger.warn(format!(
+                            "Downgrading to poll watcher after {} consecutive timeouts",
+                            consecutive_timeouts
+                        ));
+                        {
+                            let mut s = stats.lock().await;
+                            s.poll_downgrades += 1;
+                        }
+                        // Drop the native handle so its thread stops and the OS
+                        // watch handle is freed before the poll watcher starts.
+                        // Use unwrap_or_else to recover from poisoned mutex
+                        *native_handle.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                        let handle = Self::spawn_backend_watcher(
+                            WatcherBackend::Poll(Duration::from_secs(config.poll_interval_secs)),
+                            folders.clone(),
+                            logger.clone(),
+                            last_event_time.clone(),
+                            stats.clone(),
+                            is_running.clone(),
+                            tx.clone(),
+                        );
+                        poll_handles.lock().unwrap_or_else(|e| e.into_inner()).push(handle);
+                    } else if !downgraded.load(Ordering::SeqCst) {
+                        // Still on native: tear down the stale watcher and
+                        // rebuild it in place so a silently-dead handle is
+                        // replaced rather than leaked.
+                        logger.info("Rebuilding native watcher in place".to_string());
+                        let handle = Self::spawn_backend_watcher(
+                            WatcherBackend::Native,
+                            folders.clone(),
+                            logger.clone(),
+                            last_event_time.clone(),
+                            stats.clone(),
+                            is_running.clone(),
+                            tx.clone(),
+                        );
+                        // Use unwrap_or_else to recover from poisoned mutex
+                        *native_handle.lock().unwrap_or_else(|e| e.into_inner()) = Some(handle);
+                    }
+
                     // Reset the heartbeat
                     last_event_time.store(current_timestamp(), Ordering::SeqCst);
+                } else {
+                    consecutive_timeouts = 0;
                 }
             }
 
@@ -354,14 +800,16 @@ where
     }
 
     /// Start periodic polling as a fallback for missed events
-    fn start_polling_fallback(&self, tx: mpsc::Sender<PathBuf>) {
+    fn start_polling_fallback(&self, tx: mpsc::Sender<RawDetection>) {
         let logger = self.logger.clone();
-        let folders = self.folders.clone();
+        let folders = self.folders();
         let config = self.config.clone();
         let processed_files = self.processed_files.clone();
         let stats = self.stats.clone();
         let is_running = self.is_running.clone();
         let last_event_time = self.last_event_time.clone();
+        let generation = self.generation.clone();
+        let my_gen = generation.load(Ordering::SeqCst);
 
         tokio::spawn(async move {
             let interval = Duration::from_secs(config.poll_interval_secs);
@@ -372,7 +820,9 @@ where
             loop {
                 tokio::time::sleep(interval).await;
 
-                if !is_running.load(Ordering::SeqCst) {
+                if !is_running.load(Ordering::SeqCst)
+                    || generation.load(Ordering::SeqCst) != my_gen
+                {
                     break;
                 }
 
@@ -385,7 +835,13 @@ where
                 // Update heartbeat to show we're still active
                 last_event_time.store(current_timestamp(), Ordering::SeqCst);
 
-                let found = poll_folders_for_replays(&folders, &logger).await;
+                let found = poll_folders_for_replays(
+                    &folders,
+                    logger.clone(),
+                    config.max_concurrent_scanners,
+                    config.max_scan_depth,
+                )
+                .await;
                 let mut new_count = 0;
 
                 for path in found {
@@ -409,7 +865,11 @@ where
                                     new_count += 1;
                                     drop(processed); // Release lock before send
 
-                                    if let Err(e) = tx.send(path).await {
+                                    let detection = RawDetection {
+                                        path,
+                                        source: EventSource::Poll,
+                                    };
+                                    if let Err(e) = tx.send(detection).await {
                                         logger.warn(format!(
                                             "Failed to queue polled replay: {}",
                                             e
@@ -432,64 +892,245 @@ where
         });
     }
 
-    /// Start event processor that handles the callback with delay
-    fn start_event_processor(&self, mut rx: mpsc::Receiver<PathBuf>) {
+    /// Start event processor that debounces incoming events by file identity.
+    ///
+    /// Rather than a flat per-path sleep, each incoming path is keyed by its
+    /// [`file_identity`] into a pending map whose `last_seen` is reset on every
+    /// event. A ticking flush task invokes the callback only once a pending
+    /// entry has been quiet for [`WatcherConfig::debounce_window_ms`], so a save
+    /// that emits several create/modify events — or a `temp.tmp` → `game.SC2Replay`
+    /// rename — collapses to a single logical detection on the fully-flushed file.
+    fn start_event_processor(&self, mut rx: mpsc::Receiver<RawDetection>) {
         let logger = self.logger.clone();
-        let callback = self.callback.clone();
+        let events_tx = self.events_tx.clone();
         let processed_files = self.processed_files.clone();
-        let delay_ms = self.config.file_processing_delay_ms;
+        let quiet_window = Duration::from_millis(self.config.debounce_window_ms);
         let is_running = self.is_running.clone();
+        let generation = self.generation.clone();
+        let my_gen = generation.load(Ordering::SeqCst);
 
         tokio::spawn(async move {
-            while let Some(path) = rx.recv().await {
-                if !is_running.load(Ordering::SeqCst) {
+            let mut pending: HashMap<String, PendingFile> = HashMap::new();
+            // Tick often enough to flush promptly once the quiet window elapses.
+            let tick = (quiet_window / 4).max(Duration::from_millis(100));
+            let mut flush_timer = tokio::time::interval(tick);
+
+            loop {
+                if !is_running.load(Ordering::SeqCst)
+                    || generation.load(Ordering::SeqCst) != my_gen
+                {
                     break;
                 }
 
-                // Check if already processed (dedup from multiple sources)
-                {
-                    let mut processed = processed_files.lock().await;
-                    if processed.contains(&path) {
-                        logger.debug(format!(
-                            "Skipping already processed: {}",
-                            path.display()
-                        ));
-                        continue;
+                tokio::select! {
+                    maybe_detection = rx.recv() => {
+                        let Some(RawDetection { path, source }) = maybe_detection else { break };
+                        let key = file_identity(&path);
+                        let now = Instant::now();
+                        pending
+                            .entry(key)
+                            .and_modify(|e| {
+                                // A rename can change the path under the same
+                                // identity; keep the latest one and extend the
+                                // quiet window. A native event supersedes a poll
+                                // or recovery source so the flushed event reports
+                                // the most specific origin.
+                                e.path = path.clone();
+                                e.last_seen = now;
+                                if source == EventSource::Native {
+                                    e.source = EventSource::Native;
+                                }
+                            })
+                            .or_insert_with(|| PendingFile {
+                                path: path.clone(),
+                                source,
+                                first_seen: now,
+                                last_seen: now,
+                            });
+                        logger.debug(format!("Debouncing event for: {}", path.display()));
                     }
-                    processed.insert(path.clone());
-                }
+                    _ = flush_timer.tick() => {
+                        let now = Instant::now();
+                        let ready: Vec<String> = pending
+                            .iter()
+                            .filter(|(_, e)| now.duration_since(e.last_seen) >= quiet_window)
+                            .map(|(k, _)| k.clone())
+                            .collect();
+
+                        for key in ready {
+                            let Some(entry) = pending.remove(&key) else { continue };
+                            let path = entry.path;
+
+                            // Dedup across sources (native + poll + recovery).
+                            {
+                                let mut processed = processed_files.lock().await;
+                                if !processed.insert(path.clone()) {
+                                    logger.debug(format!(
+                                        "Skipping already processed: {}",
+                                        path.display()
+                                    ));
+                                    continue;
+                                }
+                            }
 
-                // Wait for file to be fully written
-                logger.debug(format!(
-                    "Waiting {}ms before processing: {}",
-                    delay_ms,
-                    path.display()
-                ));
-                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                            if !path.exists() {
+                                logger.warn(format!("File no longer exists: {}", path.display()));
+                                continue;
+                            }
 
-                // Verify file still exists and is readable
-                if !path.exists() {
-                    logger.warn(format!(
-                        "File no longer exists: {}",
-                        path.display()
-                    ));
-                    continue;
+                            logger.info(format!(
+                                "Processing replay: {} (quiet for {}ms, tracked {}ms)",
+                                path.display(),
+                                now.duration_since(entry.last_seen).as_millis(),
+                                now.duration_since(entry.first_seen).as_millis()
+                            ));
+                            // Broadcast to every subscriber; the owned callback
+                            // is driven by the drainer task in `start`.
+                            let event = WatcherEvent {
+                                path,
+                                source: entry.source,
+                                timestamp: current_timestamp(),
+                            };
+                            // With no subscribers the send errors harmlessly.
+                            let _ = events_tx.send(event);
+                        }
+                    }
                 }
-
-                logger.info(format!("Processing replay: {}", path.display()));
-                callback(path);
             }
 
             logger.warn("Event processor ended".to_string());
         });
     }
 
-    /// Stop the file watcher
+    /// Stop the file watcher.
+    ///
+    /// Flips the running flag (so the async tasks wind down) and drops every
+    /// watcher handle, which stops the native/poll threads and releases their
+    /// OS watch handles instead of leaking them until process exit.
     #[allow(dead_code)]  // Exposed for graceful shutdown
     pub fn stop(&self) {
         self.logger.info("Stopping robust file watcher".to_string());
         self.is_running.store(false, Ordering::SeqCst);
+        // Dropping the handles closes each thread's shutdown channel.
+        // Use unwrap_or_else to recover from poisoned mutex
+        *self.native_handle.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.poll_handles.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        // Allow a fresh `Auto` session to re-probe and downgrade again.
+        self.downgraded.store(false, Ordering::SeqCst);
     }
+
+    /// Cleanly stop the watcher and start it again from scratch.
+    ///
+    /// All current watcher threads are torn down before new ones are spawned, so
+    /// no OS watch handle is leaked across the restart.
+    #[allow(dead_code)]  // Exposed for runtime reconfiguration
+    pub async fn restart(&self) -> Result<(), String> {
+        self.stop();
+        // Clear the processed-file set so replays are re-evaluated against the
+        // (possibly new) folder list rather than silently deduped away.
+        self.processed_files.lock().await.clear();
+        self.start().await
+    }
+
+    /// Swap the watched-folder list and rebuild the watcher in place.
+    ///
+    /// Used when the user reconfigures which folders to watch at runtime; the
+    /// old watcher is fully stopped before the new folder set is watched.
+    #[allow(dead_code)]  // Exposed for runtime reconfiguration
+    pub async fn reconfigure(&self, folders: Vec<PathBuf>) -> Result<(), String> {
+        // Use unwrap_or_else to recover from poisoned mutex
+        *self.folders.lock().unwrap_or_else(|e| e.into_inner()) = folders;
+        self.restart().await
+    }
+}
+
+/// A stable identity for a file, used to coalesce events that refer to the same
+/// underlying file even when the path changes (e.g. a tool writing `temp.tmp`
+/// then renaming it to `game.SC2Replay`).
+///
+/// Where the platform exposes a device/inode pair we key on that; otherwise we
+/// fall back to the path, which still collapses repeated events on one path.
+fn file_identity(path: &Path) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(meta) = std::fs::metadata(path) {
+            return format!("id:{}:{}", meta.dev(), meta.ino());
+        }
+    }
+    #[cfg(windows)]
+    {
+        // std exposes no stable file id on Windows; the path is the best key.
+    }
+    format!("path:{}", path.to_string_lossy())
+}
+
+/// A debounced file awaiting a quiet window before its callback fires.
+struct PendingFile {
+    path: PathBuf,
+    source: EventSource,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+/// Write a uniquely-named sentinel into `folder` and wait up to `timeout_secs`
+/// for the native watcher to report a create/modify event for it.
+///
+/// Runs an independent short-lived watcher so the handshake never races the
+/// main event pump. Returns `true` if the cookie event arrived (native backend
+/// confirmed live). The sentinel is always removed before returning.
+async fn probe_native_delivery(folder: &Path, logger: &DebugLogger, timeout_secs: u64) -> bool {
+    let (tx, mut rx) = mpsc::channel::<PathBuf>(16);
+
+    // Unique per-probe name derived from the clock; avoids a uuid dependency.
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let cookie_path = folder.join(format!("{}{}", COOKIE_PREFIX, nanos));
+
+    let handler_tx = tx.clone();
+    let mut watcher = match notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+        if let Ok(event) = res {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                for path in event.paths {
+                    let _ = handler_tx.blocking_send(path);
+                }
+            }
+        }
+    }) {
+        Ok(w) => w,
+        Err(e) => {
+            logger.warn(format!("Cookie probe could not create watcher: {}", e));
+            return false;
+        }
+    };
+
+    if let Err(e) = watcher.watch(folder, RecursiveMode::NonRecursive) {
+        logger.warn(format!("Cookie probe could not watch {}: {}", folder.display(), e));
+        return false;
+    }
+
+    if let Err(e) = tokio::fs::write(&cookie_path, b"llu-cookie").await {
+        logger.warn(format!("Cookie probe could not write sentinel: {}", e));
+        let _ = tokio::fs::remove_file(&cookie_path).await;
+        return false;
+    }
+
+    let seen = tokio::time::timeout(Duration::from_secs(timeout_secs), async {
+        while let Some(path) = rx.recv().await {
+            if is_cookie(&path) {
+                return true;
+            }
+        }
+        false
+    })
+    .await
+    .unwrap_or(false);
+
+    let _ = tokio::fs::remove_file(&cookie_path).await;
+    drop(watcher);
+    seen
 }
 
 /// Get current timestamp in seconds
@@ -500,51 +1141,102 @@ fn current_timestamp() -> u64 {
         .unwrap_or(0)
 }
 
-/// Poll folders for replay files (fallback mechanism)
+/// Poll folders for replay files (fallback mechanism).
+///
+/// Walks every watched folder concurrently with a bounded worker pool: each
+/// directory is scanned by a task that first acquires a permit from a shared
+/// [`tokio::sync::Semaphore`] sized by `max_concurrent_scanners`, so at most
+/// that many `read_dir` calls are in flight at once no matter how wide the tree
+/// is. Discovered subdirectories are pushed as fresh tasks (down to
+/// `max_scan_depth`, `None` = unlimited, `Some(0)` = top-level only) and found
+/// replays are streamed back over an `mpsc` channel rather than accumulated in a
+/// deep recursion, which keeps a 50k-file Seasons/ archive from serialising on a
+/// single box-pinned future.
 async fn poll_folders_for_replays(
     folders: &[PathBuf],
-    logger: &DebugLogger,
+    logger: Arc<DebugLogger>,
+    max_concurrent_scanners: usize,
+    max_scan_depth: Option<usize>,
 ) -> Vec<PathBuf> {
-    let mut replays = Vec::new();
+    let concurrency = max_concurrent_scanners.max(1);
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+    let (tx, mut rx) = mpsc::channel::<PathBuf>(256);
 
     for folder in folders {
-        if let Ok(mut entries) = tokio::fs::read_dir(folder).await {
-            while let Ok(Some(entry)) = entries.next_entry().await {
-                let path = entry.path();
-                if path.is_file() && is_sc2_replay(&path) {
-                    replays.push(path);
-                } else if path.is_dir() {
-                    // Recursively scan subdirectories
-                    if let Ok(sub_replays) = scan_directory_recursive(&path).await {
-                        replays.extend(sub_replays);
-                    }
-                }
-            }
-        } else {
-            logger.warn(format!("Failed to read directory: {}", folder.display()));
-        }
+        spawn_scan_task(
+            folder.clone(),
+            0,
+            max_scan_depth,
+            Arc::clone(&semaphore),
+            tx.clone(),
+            Arc::clone(&logger),
+        );
     }
+    // Drop the caller-held sender so `rx` closes once every scan task (and the
+    // descendants they spawned) has finished and dropped its clone.
+    drop(tx);
 
+    let mut replays = Vec::new();
+    while let Some(path) = rx.recv().await {
+        replays.push(path);
+    }
     replays
 }
 
-/// Recursively scan a directory for replay files
-async fn scan_directory_recursive(dir: &Path) -> Result<Vec<PathBuf>, std::io::Error> {
-    let mut replays = Vec::new();
-    let mut entries = tokio::fs::read_dir(dir).await?;
-
-    while let Some(entry) = entries.next_entry().await? {
-        let path = entry.path();
-        if path.is_file() && is_sc2_replay(&path) {
-            replays.push(path);
-        } else if path.is_dir() {
-            if let Ok(sub_replays) = Box::pin(scan_directory_recursive(&path)).await {
-                replays.extend(sub_replays);
+/// Spawn a bounded scan task for a single directory at the given depth.
+///
+/// The task acquires a semaphore permit before reading the directory, streams
+/// any replays it finds over `tx`, and recurses into subdirectories by spawning
+/// further tasks while `depth` stays within `max_depth`.
+fn spawn_scan_task(
+    dir: PathBuf,
+    depth: usize,
+    max_depth: Option<usize>,
+    semaphore: Arc<tokio::sync::Semaphore>,
+    tx: mpsc::Sender<PathBuf>,
+    logger: Arc<DebugLogger>,
+) {
+    tokio::spawn(async move {
+        // Bound the number of concurrent `read_dir` calls; the permit is held
+        // only for this directory's own listing, not its descendants.
+        let _permit = match semaphore.clone().acquire_owned().await {
+            Ok(p) => p,
+            Err(_) => return, // Semaphore closed; nothing left to scan.
+        };
+
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                logger.warn(format!("Failed to read directory {}: {}", dir.display(), e));
+                return;
             }
-        }
-    }
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type().await else {
+                continue;
+            };
 
-    Ok(replays)
+            if file_type.is_file() {
+                if is_sc2_replay(&path) {
+                    let _ = tx.send(path).await;
+                }
+            } else if file_type.is_dir() {
+                let child_depth = depth + 1;
+                if max_depth.map_or(true, |max| child_depth <= max) {
+                    spawn_scan_task(
+                        path,
+                        child_depth,
+                        max_depth,
+                        Arc::clone(&semaphore),
+                        tx.clone(),
+                        Arc::clone(&logger),
+                    );
+                }
+            }
+        }
+    });
 }
 
 #[cfg(test)]
@@ -585,7 +1277,8 @@ mod tests {
         tokio::fs::write(&replay_path, b"test content").await.unwrap();
 
         let logger = Arc::new(DebugLogger::new());
-        let replays = poll_folders_for_replays(&[temp_dir.path().to_path_buf()], &logger).await;
+        let replays =
+            poll_folders_for_replays(&[temp_dir.path().to_path_buf()], logger, 4, None).await;
 
         assert_eq!(replays.len(), 1);
         assert_eq!(replays[0], replay_path);
@@ -598,7 +1291,8 @@ mod tests {
         tokio::fs::write(temp_dir.path().join("test.mp4"), b"not a replay").await.unwrap();
 
         let logger = Arc::new(DebugLogger::new());
-        let replays = poll_folders_for_replays(&[temp_dir.path().to_path_buf()], &logger).await;
+        let replays =
+            poll_folders_for_replays(&[temp_dir.path().to_path_buf()], logger, 4, None).await;
 
         assert_eq!(replays.len(), 0);
     }
@@ -637,7 +1331,27 @@ mod tests {
         tokio::fs::write(&replay1, b"replay1").await.unwrap();
         tokio::fs::write(&replay2, b"replay2").await.unwrap();
 
-        let replays = scan_directory_recursive(temp_dir.path()).await.unwrap();
+        let logger = Arc::new(DebugLogger::new());
+        let replays =
+            poll_folders_for_replays(&[temp_dir.path().to_path_buf()], logger, 4, None).await;
         assert_eq!(replays.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_scan_depth_limit_top_level_only() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let sub_dir = temp_dir.path().join("subdir");
+        tokio::fs::create_dir(&sub_dir).await.unwrap();
+
+        tokio::fs::write(temp_dir.path().join("top.SC2Replay"), b"r").await.unwrap();
+        tokio::fs::write(sub_dir.join("nested.SC2Replay"), b"r").await.unwrap();
+
+        let logger = Arc::new(DebugLogger::new());
+        // Depth 0 scans only the top-level folder, skipping the subdirectory.
+        let replays =
+            poll_folders_for_replays(&[temp_dir.path().to_path_buf()], logger, 4, Some(0)).await;
+        assert_eq!(replays.len(), 1);
+        assert!(replays[0].ends_with("top.SC2Replay"));
+    }
 }
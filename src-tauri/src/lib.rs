@@ -9,15 +9,25 @@
 //! - Other modules for specific functionality (sc2_detector, device_auth, etc.)
 
 // Core modules
+mod backup;
+mod db;
 mod sc2_detector;
 mod device_auth;
 mod replay_tracker;
 mod replay_uploader;
 mod upload_manager;
+mod upload_queue;
 mod replay_parser;
 mod debug_logger;
+mod session_log;
 mod services;
 mod config_utils;
+mod app_config;
+mod device_identity;
+mod token_manager;
+mod token_store;
+mod session;
+mod tray;
 
 // API contract types (must match Academy TypeScript contracts)
 pub mod api_contracts;
@@ -52,9 +62,14 @@ pub fn run() {
         .manage(AppStateManager::new())
         .invoke_handler(tauri::generate_handler![
             commands::detection::detect_replay_folders,
+            commands::backup::create_replay_backup,
+            commands::backup::list_replay_backups,
+            commands::backup::restore_replay_backup,
             commands::auth::request_device_code,
             commands::auth::poll_device_authorization,
+            commands::auth::await_device_authorization,
             commands::auth::verify_auth_token,
+            commands::auth::refresh_auth_token,
             commands::state_cmd::get_app_state,
             commands::state_cmd::set_app_state,
             commands::browser::open_browser,
@@ -67,14 +82,36 @@ pub fn run() {
             commands::tokens::clear_auth_tokens,
             commands::settings::get_autostart_enabled,
             commands::settings::set_autostart_enabled,
+            commands::settings::get_config,
+            commands::settings::update_config,
+            commands::settings::get_proxy_url,
+            commands::settings::set_proxy_url,
+            commands::settings::get_scan_interval,
+            commands::settings::set_scan_interval,
+            commands::settings::get_http_client_config,
+            commands::settings::set_http_client_config,
             commands::upload::initialize_upload_manager,
             commands::upload::get_upload_state,
+            commands::upload::get_last_scan_skips,
             commands::upload::scan_and_upload_replays,
-            commands::upload::start_file_watcher,
+            commands::upload::start_watch,
+            commands::upload::stop_watch,
+            commands::upload::start_scan_scheduler,
+            commands::queue::get_upload_queue,
+            commands::queue::set_upload_queue_paused,
+            commands::queue::cancel_current_upload,
+            commands::queue::retry_dead_lettered_job,
+            commands::jobs::start_scan_job,
+            commands::jobs::cancel_job,
+            commands::jobs::get_job_report,
             commands::version::get_version,
             commands::version::check_for_updates,
             commands::version::install_update,
             commands::debug::export_debug_log,
+            commands::debug::create_debug_bundle,
+            commands::debug::upload_debug_bundle,
+            commands::debug::set_log_level,
+            commands::debug::stream_logs,
             commands::debug::get_debug_stats,
             commands::debug::open_folder_for_path,
         ])
@@ -151,16 +188,35 @@ pub fn run() {
             // Create tray menu
             let open_item = MenuItemBuilder::with_id("open", "Open").build(app)?;
             let settings_item = MenuItemBuilder::with_id("settings", "Settings").build(app)?;
+            // The label flips between pause/resume as the flag toggles; start
+            // from the persisted queue state so a relaunch shows the truth.
+            let persisted_paused = crate::config_utils::config_file_path("upload_queue.json")
+                .ok()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .and_then(|contents| serde_json::from_str::<crate::upload_queue::UploadQueue>(&contents).ok())
+                .map(|queue| queue.paused)
+                .unwrap_or(false);
+            app.state::<AppStateManager>().uploads_paused.store(persisted_paused, std::sync::atomic::Ordering::Relaxed);
+            let initially_paused = app.state::<AppStateManager>().uploads_paused.load(std::sync::atomic::Ordering::Relaxed);
+            let pause_item = MenuItemBuilder::with_id(
+                "toggle_pause",
+                if initially_paused { "Resume uploads" } else { "Pause uploads" },
+            )
+            .build(app)?;
             let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
 
             let tray_menu = MenuBuilder::new(app)
                 .items(&[
                     &open_item,
                     &settings_item,
+                    &pause_item,
                     &quit_item,
                 ])
                 .build()?;
 
+            // Remember the pause item so its label can be updated on toggle.
+            *app.state::<AppStateManager>().tray_pause_item.lock().unwrap() = Some(pause_item.clone());
+
             // Create tray icon
             debug_logger.debug("Creating tray icon".to_string());
             let logger_for_tray_menu = debug_logger.clone();
@@ -214,6 +270,13 @@ pub fn run() {
                                 }
                             }
                         }
+                        "toggle_pause" => {
+                            let now_paused = tray::toggle_paused(app);
+                            logger_for_tray_menu.info(format!(
+                                "Toggled uploads from tray (paused = {})",
+                                now_paused
+                            ));
+                        }
                         "quit" => {
                             logger_for_tray_menu.info("Quitting app from tray menu".to_string());
                             app.exit(0);
@@ -263,10 +326,14 @@ pub fn run() {
                 })
                 .build(app)?;
 
-            // CRITICAL: Prevent the tray icon from being dropped when setup() ends.
-            // On Windows, dropping the TrayIcon destroys the system tray icon.
-            // By using std::mem::forget, the tray icon lives for the app's lifetime.
-            std::mem::forget(tray);
+            // Keep the tray alive and updatable by storing its handle in app
+            // state. On Windows, dropping the TrayIcon destroys the system tray
+            // icon, so the stored handle must live for the app's lifetime; it
+            // also lets upload progress drive the icon/tooltip live.
+            *app.state::<AppStateManager>().tray_icon.lock().unwrap() = Some(tray);
+
+            // Paint the initial idle status.
+            tray::refresh(app.handle());
 
             debug_logger.debug("Tray icon created and persisted successfully".to_string());
 
@@ -296,6 +363,10 @@ pub fn run() {
                 debug_logger.debug("Window event handler registered".to_string());
             }
 
+            // Kick off the background session-refresh timer. It no-ops cleanly
+            // when no tokens are stored yet and re-arms itself after each login.
+            crate::session::start(app.handle().clone());
+
             debug_logger.info("Tauri application setup complete".to_string());
             Ok(())
         })
@@ -1,68 +1,116 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-/// Custom deserializer that handles both old (u32) and new (String) manifest_version formats
-fn deserialize_manifest_version<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    use serde::de::{self, Visitor};
+/// Schema version recorded in an export archive's `version` file, bumped
+/// whenever [`TrackedReplay`] or [`ReplayTracker`]'s on-disk shape changes.
+const TRACKER_SCHEMA_VERSION: &str = "3";
 
-    struct ManifestVersionVisitor;
-
-    impl<'de> Visitor<'de> for ManifestVersionVisitor {
-        type Value = String;
+/// Compression codec for [`ReplayTracker::export_archive`] / [`ReplayTracker::import_archive`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Gzip,
+    Bzip2,
+}
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("a string or integer for manifest_version")
-        }
+/// Current shape of [`ReplayTracker`]'s `replays.json`. Bump this and add a
+/// `migrate_v{N}_to_v{N+1}` step in [`migrate_to_current_schema`] whenever a
+/// field is added, renamed, or reinterpreted, so a file from an older build
+/// upgrades deterministically instead of silently losing data.
+///
+/// - v0: no `schema_version`/`app_version` field; `manifest_version` was
+///   sometimes a raw integer from a long-retired format.
+/// - v1: `manifest_version` is always a string; no `app_version` yet.
+/// - v2: adds `app_version`.
+/// - v3 (current): adds `scan_cursors` for incremental folder scanning.
+const CURRENT_TRACKER_SCHEMA_VERSION: u32 = 3;
+
+/// Build version ([`env!("CARGO_PKG_VERSION")`]) stamped into a saved
+/// tracker, mirroring the version check already used for the debug bundle
+/// and upload `User-Agent`.
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Upgrade a freshly-parsed tracker JSON value to [`CURRENT_TRACKER_SCHEMA_VERSION`]
+/// in place, running each version's migration step in order.
+///
+/// A `schema_version` from a *newer* build than this one isn't migrated
+/// forward (we don't know what it means) — the caller is expected to check
+/// for that before calling this and fall back to a fresh tracker instead.
+fn migrate_to_current_schema(value: &mut serde_json::Value) {
+    let mut version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    if version == 0 {
+        migrate_v0_to_v1(value);
+        version = 1;
+    }
+    if version == 1 {
+        migrate_v1_to_v2(value);
+        version = 2;
+    }
+    if version == 2 {
+        migrate_v2_to_v3(value);
+        version = 3;
+    }
 
-        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(value.to_string())
-        }
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "schema_version".to_string(),
+            serde_json::json!(CURRENT_TRACKER_SCHEMA_VERSION.max(version)),
+        );
+    }
+}
 
-        fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            Ok(value)
+/// v0 -> v1: `manifest_version` used to be a raw integer on some builds.
+/// Replaces anything that isn't already a string with an empty one, which
+/// forces a re-sync with the server — the same behavior the old
+/// `deserialize_manifest_version` hack produced for a non-zero integer.
+fn migrate_v0_to_v1(value: &mut serde_json::Value) {
+    if let Some(obj) = value.as_object_mut() {
+        let is_string = matches!(obj.get("manifest_version"), Some(serde_json::Value::String(_)));
+        if !is_string {
+            obj.insert("manifest_version".to_string(), serde_json::json!(""));
         }
+    }
+}
 
-        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            // Old format was u32, migrate to empty string (will sync with server)
-            if value == 0 {
-                Ok(String::new())
-            } else {
-                // Non-zero old version -> force re-sync by returning empty string
-                Ok(String::new())
-            }
-        }
+/// v1 -> v2: adds `app_version`, defaulting to empty for a tracker written
+/// before this field existed.
+fn migrate_v1_to_v2(value: &mut serde_json::Value) {
+    if let Some(obj) = value.as_object_mut() {
+        obj.entry("app_version").or_insert_with(|| serde_json::json!(""));
+    }
+}
 
-        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
-        where
-            E: de::Error,
-        {
-            // Handle signed integers too
-            if value == 0 {
-                Ok(String::new())
-            } else {
-                Ok(String::new())
-            }
-        }
+/// v2 -> v3: adds `scan_cursors`, defaulting to an empty map so a tracker
+/// written before incremental scanning existed just falls back to a full
+/// scan of every folder the next time it's used.
+fn migrate_v2_to_v3(value: &mut serde_json::Value) {
+    if let Some(obj) = value.as_object_mut() {
+        obj.entry("scan_cursors").or_insert_with(|| serde_json::json!({}));
     }
+}
 
-    deserializer.deserialize_any(ManifestVersionVisitor)
+/// What [`ReplayTracker::reconcile`] found while walking tracked filepaths
+/// against the state of the disk.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReconcileReport {
+    /// Entries whose `filepath` no longer exists anywhere, and wasn't found
+    /// (by hash) elsewhere in the scanned folder either.
+    pub missing: Vec<TrackedReplay>,
+    /// Entries whose file moved to a new path within the scanned folder;
+    /// `filepath`/`filename` have already been updated to match.
+    pub relocated: Vec<TrackedReplay>,
+    /// Entries whose file still exists at the recorded `filepath`, but whose
+    /// content no longer hashes to the stored `hash` (corruption or
+    /// replacement).
+    pub hash_mismatched: Vec<TrackedReplay>,
 }
 
 /// Represents a single tracked replay file
@@ -80,6 +128,14 @@ pub struct TrackedReplay {
     pub filepath: String,
 }
 
+/// How far an incremental scan of a single replay folder has progressed.
+/// See [`ReplayTracker::scan_folder_incremental`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScanCursor {
+    /// Newest `modified_time` seen across all files in the last scan of this folder.
+    pub newest_modified: SystemTime,
+}
+
 /// Manages the local cache of uploaded replays
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReplayTracker {
@@ -88,9 +144,19 @@ pub struct ReplayTracker {
     /// Total count of uploaded replays
     pub total_uploaded: usize,
     /// Last known server manifest version (ISO timestamp for sync detection)
-    /// Backward compatible: old u32 values are migrated to empty string
-    #[serde(default, deserialize_with = "deserialize_manifest_version")]
+    #[serde(default)]
     pub manifest_version: String,
+    /// On-disk schema version this file was last migrated to or saved at.
+    /// See [`CURRENT_TRACKER_SCHEMA_VERSION`] for the migration chain.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Build version ([`APP_VERSION`]) that last wrote this file.
+    #[serde(default)]
+    pub app_version: String,
+    /// Per-folder incremental-scan progress, keyed by the scanned folder's path.
+    /// See [`ReplayTracker::scan_folder_incremental`].
+    #[serde(default)]
+    pub scan_cursors: HashMap<PathBuf, ScanCursor>,
 }
 
 impl ReplayTracker {
@@ -100,6 +166,9 @@ impl ReplayTracker {
             replays: HashMap::new(),
             total_uploaded: 0,
             manifest_version: String::new(),
+            schema_version: CURRENT_TRACKER_SCHEMA_VERSION,
+            app_version: APP_VERSION.to_string(),
+            scan_cursors: HashMap::new(),
         }
     }
 
@@ -124,16 +193,21 @@ impl ReplayTracker {
         self.manifest_version = version;
     }
 
-    /// Calculate SHA-256 hash of a file
+    /// Calculate SHA-256 hash of a file.
+    ///
+    /// Streams the file through the digest in buffered chunks rather than
+    /// reading it entirely into memory, so hashing a directory of multi-
+    /// megabyte replays for `check_hashes` stays cheap on memory.
     pub fn calculate_hash(file_path: &Path) -> Result<String, String> {
-        let contents = fs::read(file_path)
-            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let file = fs::File::open(file_path)
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+        let mut reader = std::io::BufReader::new(file);
 
         let mut hasher = Sha256::new();
-        hasher.update(&contents);
-        let result = hasher.finalize();
+        std::io::copy(&mut reader, &mut hasher)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
 
-        Ok(format!("{:x}", result))
+        Ok(format!("{:x}", hasher.finalize()))
     }
 
     /// Check if a replay has been uploaded (by hash)
@@ -166,6 +240,34 @@ impl ReplayTracker {
         self.replays.get(hash)
     }
 
+    /// Scan `folder` for new or changed replays, skipping anything not newer
+    /// than the folder's persisted [`ScanCursor`] and advancing the cursor to
+    /// the newest `modified_time` seen. The first scan of a folder (no cursor
+    /// yet) returns every replay found, same as a full scan.
+    ///
+    /// Descends into subdirectories (see [`scan_replay_folder_recursive`]),
+    /// since players often nest replays by season or ladder tier. Two files
+    /// that share the exact same `modified_time` as the cursor and appear
+    /// after the cursor was recorded won't be distinguished from ones already
+    /// seen; this only matters at filesystem mtime resolution, which callers
+    /// relying on this for display purposes (not dedup) can tolerate.
+    pub fn scan_folder_incremental(&mut self, folder: &Path) -> Result<Vec<ReplayFileInfo>, String> {
+        let all = scan_replay_folder_recursive(folder)?;
+
+        let previous_cursor = self.scan_cursors.get(folder).map(|c| c.newest_modified);
+        let new_or_changed: Vec<ReplayFileInfo> = match previous_cursor {
+            Some(cutoff) => all.iter().filter(|r| r.modified_time > cutoff).cloned().collect(),
+            None => all.clone(),
+        };
+
+        if let Some(newest_seen) = all.iter().map(|r| r.modified_time).max() {
+            let newest_modified = previous_cursor.map_or(newest_seen, |cutoff| newest_seen.max(cutoff));
+            self.scan_cursors.insert(folder.to_path_buf(), ScanCursor { newest_modified });
+        }
+
+        Ok(new_or_changed)
+    }
+
     /// Load tracker from config file
     pub fn load() -> Result<Self, String> {
         let config_dir = dirs::config_dir()
@@ -184,8 +286,37 @@ impl ReplayTracker {
         let contents = fs::read_to_string(tracker_file)
             .map_err(|e| format!("Failed to read tracker file: {}", e))?;
 
-        match serde_json::from_str::<ReplayTracker>(&contents) {
-            Ok(tracker) => Ok(tracker),
+        let mut value: serde_json::Value = match serde_json::from_str(&contents) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Warning: tracker file corrupted ({}), starting fresh", e);
+                return Ok(Self::new());
+            }
+        };
+
+        // A schema_version newer than this build knows about means the file
+        // was written by a future, incompatible version — don't attempt to
+        // migrate or deserialize it, just rebuild from scratch.
+        let declared_version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        if declared_version > CURRENT_TRACKER_SCHEMA_VERSION as u64 {
+            eprintln!(
+                "Warning: tracker file is schema v{} (newer than this build's v{}), starting fresh",
+                declared_version, CURRENT_TRACKER_SCHEMA_VERSION
+            );
+            return Ok(Self::new());
+        }
+
+        migrate_to_current_schema(&mut value);
+
+        match serde_json::from_value::<ReplayTracker>(value) {
+            Ok(mut tracker) => {
+                tracker.schema_version = CURRENT_TRACKER_SCHEMA_VERSION;
+                tracker.app_version = APP_VERSION.to_string();
+                Ok(tracker)
+            }
             Err(e) => {
                 eprintln!("Warning: tracker file corrupted ({}), starting fresh", e);
                 Ok(Self::new())
@@ -226,6 +357,375 @@ impl ReplayTracker {
             .map_err(|e| format!("Failed to create config directory: {}", e))?;
         self.save_to_path(&app_config_dir.join("replays.json"))
     }
+
+    /// Bundle every tracked `.SC2Replay` file plus the `replays.json` manifest
+    /// into a single compressed tar archive, for backup or transfer to another
+    /// machine.
+    ///
+    /// Builds the archive at a `tmp-<name>` path next to `out`, then
+    /// `fs::rename`s it into place once writing succeeds — the same atomic
+    /// temp-file-then-rename pattern [`save_to_path`] uses. A replay whose
+    /// `filepath` no longer exists is skipped with a warning rather than
+    /// failing the whole export.
+    ///
+    /// [`save_to_path`]: Self::save_to_path
+    pub fn export_archive(&self, out: &Path, format: ArchiveFormat) -> Result<(), String> {
+        let tmp_name = format!(
+            "tmp-{}",
+            out.file_name().and_then(|n| n.to_str()).unwrap_or("archive")
+        );
+        let tmp_path = out.with_file_name(tmp_name);
+
+        if let Err(e) = self.write_archive(&tmp_path, format) {
+            let _ = fs::remove_file(&tmp_path); // best-effort cleanup of a partial archive
+            return Err(e);
+        }
+
+        fs::rename(&tmp_path, out).map_err(|e| {
+            let _ = fs::remove_file(&tmp_path); // best-effort cleanup of orphaned tmp
+            format!("Failed to rename archive into place: {}", e)
+        })
+    }
+
+    /// Write the `version`/`replays.json`/`replays/*` tar entries through
+    /// `format`'s compressor into `tmp_path`.
+    fn write_archive(&self, tmp_path: &Path, format: ArchiveFormat) -> Result<(), String> {
+        let file = fs::File::create(tmp_path)
+            .map_err(|e| format!("Failed to create archive file: {}", e))?;
+
+        match format {
+            ArchiveFormat::Gzip => {
+                use flate2::write::GzEncoder;
+                use flate2::Compression;
+
+                let encoder = self.write_tar_entries(GzEncoder::new(file, Compression::default()))?;
+                encoder
+                    .finish()
+                    .map_err(|e| format!("Failed to flush gzip stream: {}", e))?;
+            }
+            ArchiveFormat::Bzip2 => {
+                use bzip2::write::BzEncoder;
+
+                let encoder =
+                    self.write_tar_entries(BzEncoder::new(file, bzip2::Compression::default()))?;
+                encoder
+                    .finish()
+                    .map_err(|e| format!("Failed to flush bzip2 stream: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write every archive entry into a tar stream and hand back the
+    /// underlying compressor so the caller can finish (flush) it.
+    fn write_tar_entries<W: Write>(&self, writer: W) -> Result<W, String> {
+        let mut builder = tar::Builder::new(writer);
+
+        append_tar_bytes(&mut builder, "version", TRACKER_SCHEMA_VERSION.as_bytes())?;
+
+        let manifest = serde_json::to_vec_pretty(self)
+            .map_err(|e| format!("Failed to serialize tracker: {}", e))?;
+        append_tar_bytes(&mut builder, "replays.json", &manifest)?;
+
+        for replay in self.replays.values() {
+            let name = format!("replays/{}", replay.filename);
+            if let Err(e) = builder.append_path_with_name(&replay.filepath, &name) {
+                eprintln!("Warning: skipping {} in export ({})", replay.filepath, e);
+            }
+        }
+
+        builder
+            .into_inner()
+            .map_err(|e| format!("Failed to finalize archive: {}", e))
+    }
+
+    /// Restore an archive written by [`export_archive`] into `dest_dir`,
+    /// merging its manifest into `self` by hash.
+    ///
+    /// Replay files land at `dest_dir/<filename>`; tracked entries whose hash
+    /// is already present in `self` are left alone (the existing entry —
+    /// and whatever `filepath` it points at — wins), so re-importing the same
+    /// archive, or one that overlaps with what's already tracked, is a no-op
+    /// for those hashes. Returns the number of newly merged entries.
+    ///
+    /// [`export_archive`]: Self::export_archive
+    pub fn import_archive(&mut self, archive_path: &Path, dest_dir: &Path) -> Result<usize, String> {
+        fs::create_dir_all(dest_dir)
+            .map_err(|e| format!("Failed to create destination directory: {}", e))?;
+
+        let format = detect_archive_format(archive_path)?;
+        let file = fs::File::open(archive_path)
+            .map_err(|e| format!("Failed to open archive: {}", e))?;
+
+        let (manifest, extracted) = match format {
+            ArchiveFormat::Gzip => {
+                use flate2::read::GzDecoder;
+                extract_tar_entries(tar::Archive::new(GzDecoder::new(file)), dest_dir)?
+            }
+            ArchiveFormat::Bzip2 => {
+                use bzip2::read::BzDecoder;
+                extract_tar_entries(tar::Archive::new(BzDecoder::new(file)), dest_dir)?
+            }
+        };
+        let manifest = manifest.ok_or("Archive is missing the replays.json manifest")?;
+
+        let mut merged = 0usize;
+        for (hash, mut replay) in manifest.replays {
+            if self.replays.contains_key(&hash) {
+                continue;
+            }
+            if let Some(path) = extracted.get(&replay.filename) {
+                replay.filepath = path.to_string_lossy().to_string();
+            }
+            self.replays.insert(hash, replay);
+            merged += 1;
+        }
+        self.total_uploaded = self.replays.len();
+
+        Ok(merged)
+    }
+
+    /// Re-validate every tracked entry against the current state of disk.
+    ///
+    /// For each tracked replay whose `filepath` still exists, its content is
+    /// re-hashed and compared against the stored hash, catching silent
+    /// corruption or a same-path file swap. For one whose `filepath` is gone,
+    /// `replay_folder` is hashed and searched for a file with a matching
+    /// hash — if found, the entry's `filepath`/`filename` are updated in
+    /// place rather than treating the move as a new upload; if not found, the
+    /// entry is reported missing and, when `prune_missing` is set, removed.
+    ///
+    /// `is_uploaded`/`exists_by_metadata` otherwise trust cached state
+    /// indefinitely, so a user who moves or edits their replay folder outside
+    /// the app would see stale results without this check.
+    ///
+    /// The relocation search is only as deep as [`scan_replay_folder`]: it
+    /// looks at `replay_folder`'s immediate contents, not subdirectories.
+    pub fn reconcile(&mut self, replay_folder: &Path, prune_missing: bool) -> ReconcileReport {
+        let mut report = ReconcileReport::default();
+        let mut folder_by_hash: Option<HashMap<String, PathBuf>> = None;
+        let mut to_prune = Vec::new();
+
+        let hashes: Vec<String> = self.replays.keys().cloned().collect();
+        for hash in hashes {
+            let replay = match self.replays.get(&hash) {
+                Some(r) => r.clone(),
+                None => continue,
+            };
+            let path = Path::new(&replay.filepath);
+
+            if path.exists() {
+                if let Ok(current_hash) = Self::calculate_hash(path) {
+                    if current_hash != hash {
+                        report.hash_mismatched.push(replay);
+                    }
+                }
+                continue;
+            }
+
+            let folder_index =
+                folder_by_hash.get_or_insert_with(|| Self::index_folder_by_hash(replay_folder));
+            if let Some(new_path) = folder_index.get(&hash) {
+                let mut relocated = replay.clone();
+                relocated.filepath = new_path.to_string_lossy().to_string();
+                relocated.filename = new_path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(&replay.filename)
+                    .to_string();
+                self.replays.insert(hash, relocated.clone());
+                report.relocated.push(relocated);
+            } else {
+                report.missing.push(replay);
+                if prune_missing {
+                    to_prune.push(hash);
+                }
+            }
+        }
+
+        for hash in to_prune {
+            self.replays.remove(&hash);
+        }
+        self.total_uploaded = self.replays.len();
+
+        report
+    }
+
+    /// Hash every `.SC2Replay` file in `folder`, for [`reconcile`](Self::reconcile)'s
+    /// relocated-file lookup. Unreadable files are skipped rather than
+    /// failing the scan.
+    fn index_folder_by_hash(folder: &Path) -> HashMap<String, PathBuf> {
+        let mut by_hash = HashMap::new();
+        if let Ok(entries) = scan_replay_folder(folder) {
+            for entry in entries {
+                if let Ok(hash) = Self::calculate_hash(&entry.path) {
+                    by_hash.entry(hash).or_insert(entry.path);
+                }
+            }
+        }
+        by_hash
+    }
+}
+
+/// Write one in-memory buffer as a tar entry with a GNU header.
+fn append_tar_bytes<W: Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, data)
+        .map_err(|e| format!("Failed to add {} to archive: {}", name, e))
+}
+
+/// Sniff an archive's compression from its leading magic bytes so
+/// [`ReplayTracker::import_archive`] doesn't need the caller to pass the
+/// [`ArchiveFormat`] back in.
+fn detect_archive_format(path: &Path) -> Result<ArchiveFormat, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let mut magic = [0u8; 3];
+    let read = file
+        .read(&mut magic)
+        .map_err(|e| format!("Failed to read archive header: {}", e))?;
+    if read >= 2 && magic[0] == 0x1f && magic[1] == 0x8b {
+        Ok(ArchiveFormat::Gzip)
+    } else if read >= 3 && &magic == b"BZh" {
+        Ok(ArchiveFormat::Bzip2)
+    } else {
+        Err("Unrecognized archive format (expected gzip or bzip2)".to_string())
+    }
+}
+
+/// Reject an archive with more entries than this — a legitimate replay cache
+/// backup has, at most, a few tens of thousands of files.
+const MAX_UNPACKED_COUNT: u64 = 20_000;
+/// Reject an archive whose entries' declared (uncompressed) sizes add up to
+/// more than this. Defeats a decompression bomb: a tiny compressed archive
+/// that claims to unpack into something enormous.
+const MAX_UNPACKED_SIZE: u64 = 8 * 1024 * 1024 * 1024;
+
+/// Collapse an archive entry's path into a traversal-safe relative path, or
+/// `None` if it isn't one.
+///
+/// Rather than join-then-`canonicalize`-and-compare (which needs the target
+/// to already exist), this rebuilds the path from only its [`Component::Normal`]
+/// pieces — an absolute entry, or one containing `..`, has no such
+/// all-`Normal` reconstruction and is rejected outright.
+///
+/// Shared with [`crate::backup::restore_snapshot`], which extracts from the
+/// same kind of tar archive and needs the same traversal check before it
+/// joins an entry's path onto its restore target.
+pub(crate) fn normalized_relative_path(entry_path: &Path) -> Option<PathBuf> {
+    use std::path::Component;
+
+    let mut out = PathBuf::new();
+    for component in entry_path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    if out.as_os_str().is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+/// Extract `replays/*.SC2Replay` entries into `dest_dir` and parse out the
+/// `replays.json` manifest, returning it alongside a map of extracted
+/// filename -> destination path.
+///
+/// Treats the archive as untrusted input: entry count and cumulative
+/// declared size are capped to defeat decompression bombs, and every replay
+/// entry's path is traversal-checked before it's joined onto `dest_dir`.
+/// Anything that isn't the manifest or a `.SC2Replay` under `replays/` —
+/// including a path that fails the traversal check — is skipped with a
+/// warning rather than extracted.
+fn extract_tar_entries<R: Read>(
+    mut archive: tar::Archive<R>,
+    dest_dir: &Path,
+) -> Result<(Option<ReplayTracker>, HashMap<String, PathBuf>), String> {
+    let mut manifest = None;
+    let mut extracted = HashMap::new();
+    let mut entry_count: u64 = 0;
+    let mut total_unpacked_size: u64 = 0;
+
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read archive entries: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read archive entry: {}", e))?;
+
+        entry_count += 1;
+        if entry_count > MAX_UNPACKED_COUNT {
+            return Err(format!(
+                "Archive has more than {} entries, refusing to unpack",
+                MAX_UNPACKED_COUNT
+            ));
+        }
+        let declared_size = entry
+            .header()
+            .size()
+            .map_err(|e| format!("Failed to read archive entry size: {}", e))?;
+        total_unpacked_size += declared_size;
+        if total_unpacked_size > MAX_UNPACKED_SIZE {
+            return Err(format!(
+                "Archive's uncompressed contents exceed the {}-byte limit, refusing to unpack",
+                MAX_UNPACKED_SIZE
+            ));
+        }
+
+        let entry_path = entry
+            .path()
+            .map_err(|e| format!("Invalid archive entry path: {}", e))?
+            .to_string_lossy()
+            .to_string();
+
+        if entry_path == "replays.json" {
+            let mut contents = String::new();
+            entry
+                .read_to_string(&mut contents)
+                .map_err(|e| format!("Failed to read manifest from archive: {}", e))?;
+            manifest = Some(
+                serde_json::from_str(&contents)
+                    .map_err(|e| format!("Failed to parse manifest from archive: {}", e))?,
+            );
+            continue;
+        }
+
+        let Some(stripped) = entry_path.strip_prefix("replays/") else {
+            eprintln!("Warning: skipping unexpected archive entry: {}", entry_path);
+            continue;
+        };
+        if !stripped.ends_with(".SC2Replay") {
+            eprintln!("Warning: skipping non-replay archive entry: {}", entry_path);
+            continue;
+        }
+        let Some(relative) = normalized_relative_path(Path::new(stripped)) else {
+            eprintln!("Warning: skipping archive entry with an unsafe path: {}", entry_path);
+            continue;
+        };
+        let Some(filename) = relative.file_name().and_then(|n| n.to_str()) else {
+            eprintln!("Warning: skipping archive entry with no file name: {}", entry_path);
+            continue;
+        };
+        let filename = filename.to_string();
+
+        let dest_path = dest_dir.join(&relative);
+        entry
+            .unpack(&dest_path)
+            .map_err(|e| format!("Failed to extract {}: {}", entry_path, e))?;
+        extracted.insert(filename, dest_path);
+    }
+
+    Ok((manifest, extracted))
 }
 
 impl Default for ReplayTracker {
@@ -245,6 +745,17 @@ pub struct ReplayFileInfo {
 
 /// Scan a directory for .SC2Replay files and return file information
 pub fn scan_replay_folder(folder_path: &Path) -> Result<Vec<ReplayFileInfo>, String> {
+    scan_replay_folder_since(folder_path, None)
+}
+
+/// Like [`scan_replay_folder`], but skips any file whose `modified_time` is
+/// not newer than `since`, when given. Used for steady-state scans so a
+/// folder with years of old replays only costs a `read_dir` plus one
+/// `metadata` call per file, not a full re-filter/re-hash of everything.
+pub fn scan_replay_folder_since(
+    folder_path: &Path,
+    since: Option<SystemTime>,
+) -> Result<Vec<ReplayFileInfo>, String> {
     if !folder_path.exists() {
         return Err(format!("Folder does not exist: {}", folder_path.display()));
     }
@@ -274,6 +785,12 @@ pub fn scan_replay_folder(folder_path: &Path) -> Result<Vec<ReplayFileInfo>, Str
         let modified_time = metadata.modified()
             .map_err(|e| format!("Failed to get modified time: {}", e))?;
 
+        if let Some(since) = since {
+            if modified_time <= since {
+                continue;
+            }
+        }
+
         replays.push(ReplayFileInfo {
             path,
             filename,
@@ -288,6 +805,100 @@ pub fn scan_replay_folder(folder_path: &Path) -> Result<Vec<ReplayFileInfo>, Str
     Ok(replays)
 }
 
+/// Build a [`ReplayFileInfo`] for a single known path, e.g. one the folder
+/// watcher just saw settle, without rescanning its whole parent directory.
+pub fn replay_file_info_for_path(path: &Path) -> Result<ReplayFileInfo, String> {
+    let metadata = fs::metadata(path)
+        .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+
+    let filename = path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Ok(ReplayFileInfo {
+        path: path.to_path_buf(),
+        filename,
+        filesize: metadata.len(),
+        modified_time: metadata.modified()
+            .map_err(|e| format!("Failed to get modified time: {}", e))?,
+    })
+}
+
+/// Like [`scan_replay_folder`], but also descends into subdirectories, since
+/// players often nest replays by season or ladder tier. Guards against
+/// symlink cycles by canonicalizing each directory before descending into it
+/// and refusing to revisit one already seen.
+pub fn scan_replay_folder_recursive(folder_path: &Path) -> Result<Vec<ReplayFileInfo>, String> {
+    if !folder_path.exists() {
+        return Err(format!("Folder does not exist: {}", folder_path.display()));
+    }
+
+    let mut replays = Vec::new();
+    let mut visited_dirs = HashSet::new();
+    scan_dir_recursive(folder_path, &mut visited_dirs, &mut replays)?;
+
+    // Sort by modified time (newest first)
+    replays.sort_by(|a, b| b.modified_time.cmp(&a.modified_time));
+
+    Ok(replays)
+}
+
+fn scan_dir_recursive(
+    dir: &Path,
+    visited_dirs: &mut HashSet<PathBuf>,
+    replays: &mut Vec<ReplayFileInfo>,
+) -> Result<(), String> {
+    let canonical_dir = fs::canonicalize(dir)
+        .map_err(|e| format!("Failed to resolve {}: {}", dir.display(), e))?;
+    if !visited_dirs.insert(canonical_dir) {
+        // Already descended into this real directory - a symlink cycle.
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let file_type = match entry.file_type() {
+            Ok(ft) => ft,
+            Err(_) => continue,
+        };
+
+        if file_type.is_dir() || (file_type.is_symlink() && path.is_dir()) {
+            scan_dir_recursive(&path, visited_dirs, replays)?;
+            continue;
+        }
+
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "SC2Replay") {
+            continue;
+        }
+
+        let filename = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let metadata = entry.metadata()
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+
+        let filesize = metadata.len();
+        let modified_time = metadata.modified()
+            .map_err(|e| format!("Failed to get modified time: {}", e))?;
+
+        replays.push(ReplayFileInfo {
+            path,
+            filename,
+            filesize,
+            modified_time,
+        });
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -469,6 +1080,75 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_scan_replay_folder_recursive_descends_into_subdirectories() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_replay_file(temp_dir.path(), "top.SC2Replay", b"top");
+
+        let season_dir = temp_dir.path().join("season1").join("diamond");
+        fs::create_dir_all(&season_dir).unwrap();
+        create_test_replay_file(&season_dir, "nested.SC2Replay", b"nested");
+
+        let flat = scan_replay_folder(temp_dir.path()).unwrap();
+        assert_eq!(flat.len(), 1, "Non-recursive scan should not see nested replays");
+
+        let recursive = scan_replay_folder_recursive(temp_dir.path()).unwrap();
+        assert_eq!(recursive.len(), 2, "Recursive scan should find replays in subdirectories");
+        assert!(recursive.iter().any(|r| r.filename == "top.SC2Replay"));
+        assert!(recursive.iter().any(|r| r.filename == "nested.SC2Replay"));
+    }
+
+    #[test]
+    fn test_scan_folder_incremental_first_scan_returns_everything() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_replay_file(temp_dir.path(), "replay1.SC2Replay", b"content1");
+        create_test_replay_file(temp_dir.path(), "replay2.SC2Replay", b"content2");
+
+        let mut tracker = ReplayTracker::new();
+        let found = tracker.scan_folder_incremental(temp_dir.path()).unwrap();
+
+        assert_eq!(found.len(), 2);
+        assert!(tracker.scan_cursors.contains_key(temp_dir.path()));
+    }
+
+    #[test]
+    fn test_scan_folder_incremental_skips_previously_seen_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_replay_file(temp_dir.path(), "old.SC2Replay", b"old");
+
+        let mut tracker = ReplayTracker::new();
+        let first_scan = tracker.scan_folder_incremental(temp_dir.path()).unwrap();
+        assert_eq!(first_scan.len(), 1);
+
+        // Nothing changed since the last scan, so the cursor should filter everything out.
+        let second_scan = tracker.scan_folder_incremental(temp_dir.path()).unwrap();
+        assert!(second_scan.is_empty(), "Unchanged files should be skipped on the next scan");
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        create_test_replay_file(temp_dir.path(), "new.SC2Replay", b"new");
+
+        let third_scan = tracker.scan_folder_incremental(temp_dir.path()).unwrap();
+        assert_eq!(third_scan.len(), 1, "Only the newly added file should be returned");
+        assert_eq!(third_scan[0].filename, "new.SC2Replay");
+    }
+
+    #[test]
+    fn test_scan_folder_incremental_tracks_multiple_folders_independently() {
+        let folder_a = TempDir::new().unwrap();
+        let folder_b = TempDir::new().unwrap();
+        create_test_replay_file(folder_a.path(), "a.SC2Replay", b"a");
+        create_test_replay_file(folder_b.path(), "b.SC2Replay", b"b");
+
+        let mut tracker = ReplayTracker::new();
+        tracker.scan_folder_incremental(folder_a.path()).unwrap();
+        tracker.scan_folder_incremental(folder_b.path()).unwrap();
+
+        assert_eq!(tracker.scan_cursors.len(), 2);
+
+        let rescan_a = tracker.scan_folder_incremental(folder_a.path()).unwrap();
+        assert!(rescan_a.is_empty(), "folder_a's cursor should be unaffected by scanning folder_b");
+    }
+
     #[test]
     fn test_tracked_replay_equality() {
         let replay1 = TrackedReplay {
@@ -616,6 +1296,63 @@ mod tests {
         let tracker = ReplayTracker::load_from_path(&tracker_file).unwrap();
         assert_eq!(tracker.get_manifest_version(), "", "Old format should default to empty version");
         assert_eq!(tracker.total_uploaded, 1, "Should still load replays");
+        assert_eq!(tracker.schema_version, CURRENT_TRACKER_SCHEMA_VERSION, "Should migrate up to the current schema");
+    }
+
+    #[test]
+    fn test_migrates_legacy_integer_manifest_version_to_empty_string() {
+        // The oldest format stored manifest_version as a raw integer.
+        let temp_dir = TempDir::new().unwrap();
+        let tracker_file = temp_dir.path().join("replays.json");
+        let json_content = r#"{
+            "replays": {},
+            "total_uploaded": 0,
+            "manifest_version": 7
+        }"#;
+        fs::write(&tracker_file, json_content).unwrap();
+
+        let tracker = ReplayTracker::load_from_path(&tracker_file).unwrap();
+        assert_eq!(tracker.get_manifest_version(), "", "Non-zero legacy integer version should force a re-sync");
+        assert_eq!(tracker.schema_version, CURRENT_TRACKER_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_load_stamps_current_schema_and_app_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker_file = temp_dir.path().join("replays.json");
+        fs::write(&tracker_file, r#"{"replays": {}, "total_uploaded": 0}"#).unwrap();
+
+        let tracker = ReplayTracker::load_from_path(&tracker_file).unwrap();
+        assert_eq!(tracker.schema_version, CURRENT_TRACKER_SCHEMA_VERSION);
+        assert_eq!(tracker.app_version, APP_VERSION);
+    }
+
+    #[test]
+    fn test_load_rejects_future_schema_version_with_fresh_tracker() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker_file = temp_dir.path().join("replays.json");
+        let json_content = format!(
+            r#"{{"replays": {{"hash1": {{"hash": "hash1", "filename": "test.SC2Replay", "filesize": 1, "uploaded_at": 1, "filepath": "/p"}}}}, "total_uploaded": 1, "schema_version": {}}}"#,
+            CURRENT_TRACKER_SCHEMA_VERSION + 1
+        );
+        fs::write(&tracker_file, json_content).unwrap();
+
+        let tracker = ReplayTracker::load_from_path(&tracker_file).unwrap();
+        assert_eq!(tracker.total_uploaded, 0, "A file from a newer, unrecognized schema should rebuild fresh rather than partially read");
+        assert_eq!(tracker.schema_version, CURRENT_TRACKER_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_save_then_load_round_trips_schema_and_app_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker_file = temp_dir.path().join("replays.json");
+
+        let tracker = ReplayTracker::new();
+        tracker.save_to_path(&tracker_file).unwrap();
+
+        let loaded = ReplayTracker::load_from_path(&tracker_file).unwrap();
+        assert_eq!(loaded.schema_version, CURRENT_TRACKER_SCHEMA_VERSION);
+        assert_eq!(loaded.app_version, APP_VERSION);
     }
 
     #[test]
@@ -666,4 +1403,304 @@ mod tests {
         // Tmp file must be cleaned up after a successful save
         assert!(!tmp_file.exists(), "Tmp file should not exist after successful save");
     }
+
+    fn tracker_with_one_replay(dir: &Path) -> (ReplayTracker, PathBuf) {
+        let replay_path = create_test_replay_file(dir, "game.SC2Replay", b"replay bytes");
+        let hash = ReplayTracker::calculate_hash(&replay_path).unwrap();
+
+        let mut tracker = ReplayTracker::new();
+        tracker.add_replay(TrackedReplay {
+            hash,
+            filename: "game.SC2Replay".to_string(),
+            filesize: 12,
+            uploaded_at: 1700000000,
+            filepath: replay_path.to_string_lossy().to_string(),
+        });
+        (tracker, replay_path)
+    }
+
+    #[test]
+    fn test_export_archive_gzip_round_trips_through_import() {
+        let source_dir = TempDir::new().unwrap();
+        let (tracker, _replay_path) = tracker_with_one_replay(source_dir.path());
+
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("backup.tar.gz");
+        tracker.export_archive(&archive_path, ArchiveFormat::Gzip).unwrap();
+        assert!(archive_path.exists());
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut imported = ReplayTracker::new();
+        let merged = imported.import_archive(&archive_path, dest_dir.path()).unwrap();
+
+        assert_eq!(merged, 1);
+        assert_eq!(imported.total_uploaded, 1);
+        let entry = imported.get_all()[0];
+        assert_eq!(entry.filename, "game.SC2Replay");
+        assert!(Path::new(&entry.filepath).exists(), "restored file should exist at the new filepath");
+        assert_eq!(fs::read(&entry.filepath).unwrap(), b"replay bytes");
+    }
+
+    #[test]
+    fn test_export_archive_bzip2_round_trips_through_import() {
+        let source_dir = TempDir::new().unwrap();
+        let (tracker, _replay_path) = tracker_with_one_replay(source_dir.path());
+
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("backup.tar.bz2");
+        tracker.export_archive(&archive_path, ArchiveFormat::Bzip2).unwrap();
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut imported = ReplayTracker::new();
+        let merged = imported.import_archive(&archive_path, dest_dir.path()).unwrap();
+
+        assert_eq!(merged, 1);
+        assert_eq!(fs::read(&imported.get_all()[0].filepath).unwrap(), b"replay bytes");
+    }
+
+    #[test]
+    fn test_export_archive_leaves_no_tmp_file_behind() {
+        let source_dir = TempDir::new().unwrap();
+        let (tracker, _replay_path) = tracker_with_one_replay(source_dir.path());
+
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("backup.tar.gz");
+        tracker.export_archive(&archive_path, ArchiveFormat::Gzip).unwrap();
+
+        let tmp_path = archive_dir.path().join("tmp-backup.tar.gz");
+        assert!(!tmp_path.exists(), "Tmp archive should not exist after successful export");
+    }
+
+    #[test]
+    fn test_import_archive_merges_without_clobbering_existing_hash() {
+        let source_dir = TempDir::new().unwrap();
+        let (tracker, _replay_path) = tracker_with_one_replay(source_dir.path());
+        let existing_hash = tracker.get_all()[0].hash.clone();
+
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("backup.tar.gz");
+        tracker.export_archive(&archive_path, ArchiveFormat::Gzip).unwrap();
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut existing = ReplayTracker::new();
+        existing.add_replay(TrackedReplay {
+            hash: existing_hash.clone(),
+            filename: "game.SC2Replay".to_string(),
+            filesize: 12,
+            uploaded_at: 1,
+            filepath: "/already/tracked/game.SC2Replay".to_string(),
+        });
+
+        let merged = existing.import_archive(&archive_path, dest_dir.path()).unwrap();
+
+        assert_eq!(merged, 0, "hash already tracked should not be re-merged");
+        assert_eq!(
+            existing.get_by_hash(&existing_hash).unwrap().filepath,
+            "/already/tracked/game.SC2Replay",
+            "existing entry's filepath should win over the imported one"
+        );
+    }
+
+    /// Build a gzip-compressed tar with the given raw `(entry_name, contents)`
+    /// pairs, bypassing `export_archive` so tests can craft entries a real
+    /// export would never produce (path traversal, oversized claims, ...).
+    fn build_raw_tar_gz(path: &Path, entries: &[(&str, &[u8])]) {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let file = fs::File::create(path).unwrap();
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            // Write the name bytes straight into the header: `append_data`
+            // refuses `..` and absolute paths, which these tests need to craft.
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_import_archive_rejects_path_traversal_entry() {
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("evil.tar.gz");
+        build_raw_tar_gz(
+            &archive_path,
+            &[
+                ("replays.json", b"{\"replays\":{},\"total_uploaded\":0}"),
+                ("replays/../../../../etc/evil.SC2Replay", b"pwn"),
+            ],
+        );
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut tracker = ReplayTracker::new();
+        let merged = tracker.import_archive(&archive_path, dest_dir.path()).unwrap();
+
+        assert_eq!(merged, 0, "traversal entry carries no manifest record, so nothing merges");
+        assert!(
+            !archive_dir.path().join("../../../../etc/evil.SC2Replay").exists(),
+            "traversal entry must not be written outside dest_dir"
+        );
+    }
+
+    #[test]
+    fn test_import_archive_rejects_absolute_path_entry() {
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("evil.tar.gz");
+        build_raw_tar_gz(
+            &archive_path,
+            &[
+                ("replays.json", b"{\"replays\":{},\"total_uploaded\":0}"),
+                ("/tmp/evil.SC2Replay", b"pwn"),
+            ],
+        );
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut tracker = ReplayTracker::new();
+        tracker.import_archive(&archive_path, dest_dir.path()).unwrap();
+
+        assert!(!Path::new("/tmp/evil.SC2Replay").exists(), "absolute-path entry must not escape dest_dir");
+    }
+
+    #[test]
+    fn test_import_archive_skips_non_replay_entry() {
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("archive.tar.gz");
+        build_raw_tar_gz(
+            &archive_path,
+            &[
+                ("replays.json", b"{\"replays\":{},\"total_uploaded\":0}"),
+                ("replays/notes.txt", b"not a replay"),
+            ],
+        );
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut tracker = ReplayTracker::new();
+        tracker.import_archive(&archive_path, dest_dir.path()).unwrap();
+
+        assert!(!dest_dir.path().join("notes.txt").exists(), "non-.SC2Replay entries should be skipped");
+    }
+
+    #[test]
+    fn test_import_archive_rejects_archive_over_entry_count_limit() {
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("huge.tar.gz");
+
+        let mut entries: Vec<(String, Vec<u8>)> = (0..(MAX_UNPACKED_COUNT + 1))
+            .map(|i| (format!("replays/r{}.SC2Replay", i), b"x".to_vec()))
+            .collect();
+        entries.push(("replays.json".to_string(), b"{\"replays\":{},\"total_uploaded\":0}".to_vec()));
+        let entries: Vec<(&str, &[u8])> = entries.iter().map(|(n, d)| (n.as_str(), d.as_slice())).collect();
+        build_raw_tar_gz(&archive_path, &entries);
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut tracker = ReplayTracker::new();
+        let result = tracker.import_archive(&archive_path, dest_dir.path());
+
+        assert!(result.is_err(), "archive with more than MAX_UNPACKED_COUNT entries must be rejected");
+    }
+
+    #[test]
+    fn test_import_archive_rejects_archive_over_size_limit() {
+        let archive_dir = TempDir::new().unwrap();
+        let archive_path = archive_dir.path().join("bomb.tar.gz");
+
+        let file = fs::File::create(&archive_path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::best());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        // Claim a size far beyond MAX_UNPACKED_SIZE without actually writing
+        // that many bytes — append_data trusts the caller-set header size,
+        // so this mimics a decompression-bomb archive without needing to
+        // hold gigabytes of real data in the test.
+        header.set_size(MAX_UNPACKED_SIZE + 1);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "replays/bomb.SC2Replay", &b"x"[..]).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let dest_dir = TempDir::new().unwrap();
+        let mut tracker = ReplayTracker::new();
+        let result = tracker.import_archive(&archive_path, dest_dir.path());
+
+        assert!(result.is_err(), "archive whose declared size exceeds MAX_UNPACKED_SIZE must be rejected");
+    }
+
+    #[test]
+    fn test_reconcile_leaves_untouched_entries_alone() {
+        let folder = TempDir::new().unwrap();
+        let (mut tracker, _path) = tracker_with_one_replay(folder.path());
+
+        let report = tracker.reconcile(folder.path(), false);
+
+        assert!(report.missing.is_empty());
+        assert!(report.relocated.is_empty());
+        assert!(report.hash_mismatched.is_empty());
+        assert_eq!(tracker.total_uploaded, 1);
+    }
+
+    #[test]
+    fn test_reconcile_reports_and_prunes_missing_entry() {
+        let folder = TempDir::new().unwrap();
+        let (mut tracker, path) = tracker_with_one_replay(folder.path());
+        fs::remove_file(&path).unwrap();
+
+        let report = tracker.reconcile(folder.path(), true);
+
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].filename, "game.SC2Replay");
+        assert_eq!(tracker.total_uploaded, 0, "missing entry should be pruned");
+    }
+
+    #[test]
+    fn test_reconcile_reports_missing_without_pruning_by_default() {
+        let folder = TempDir::new().unwrap();
+        let (mut tracker, path) = tracker_with_one_replay(folder.path());
+        fs::remove_file(&path).unwrap();
+
+        let report = tracker.reconcile(folder.path(), false);
+
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(tracker.total_uploaded, 1, "entry should stay tracked when prune_missing is false");
+    }
+
+    #[test]
+    fn test_reconcile_updates_filepath_for_relocated_file() {
+        let folder = TempDir::new().unwrap();
+        let (mut tracker, path) = tracker_with_one_replay(folder.path());
+        let hash = tracker.get_all()[0].hash.clone();
+
+        // scan_replay_folder only looks at the top level of a folder, so
+        // "moved" here means renamed within the same folder rather than
+        // into a subdirectory.
+        let moved_path = folder.path().join("renamed.SC2Replay");
+        fs::rename(&path, &moved_path).unwrap();
+
+        let report = tracker.reconcile(folder.path(), true);
+
+        assert!(report.missing.is_empty(), "file was found elsewhere, not truly missing");
+        assert_eq!(report.relocated.len(), 1);
+        assert_eq!(
+            tracker.get_by_hash(&hash).unwrap().filepath,
+            moved_path.to_string_lossy().to_string()
+        );
+        assert_eq!(tracker.total_uploaded, 1, "relocated entry should not be pruned or duplicated");
+    }
+
+    #[test]
+    fn test_reconcile_detects_hash_mismatch_on_silent_corruption() {
+        let folder = TempDir::new().unwrap();
+        let (mut tracker, path) = tracker_with_one_replay(folder.path());
+        fs::write(&path, b"corrupted replacement bytes").unwrap();
+
+        let report = tracker.reconcile(folder.path(), true);
+
+        assert_eq!(report.hash_mismatched.len(), 1);
+        assert!(report.missing.is_empty());
+        assert_eq!(tracker.total_uploaded, 1, "hash-mismatched entry is reported, not removed");
+    }
 }
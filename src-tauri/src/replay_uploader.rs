@@ -3,12 +3,173 @@ use crate::api_contracts::{
     CheckHashesRequest, CheckHashesResponse, HashInfo,
     UploadReplayResponse, ManifestVersionResponse,
     UserSettings, UserSettingsResponse, StoredReplay,
+    UploadInitRequest, UploadInitResponse, UploadFinalizeRequest,
 };
-use serde::{Deserialize, Serialize};
+use crate::config_utils::save_config_file;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use std::fs;
 
+/// Filename used to persist the conditional-GET cache in the config directory.
+const HTTP_CACHE_FILE: &str = "http_cache.json";
+
+/// Filename used to persist in-flight chunked-upload ids so an interrupted
+/// upload can resume after a restart instead of starting over.
+const CHUNK_STATE_FILE: &str = "chunked_uploads.json";
+
+/// Default chunk size for resumable uploads (1 MiB). The server may clamp this
+/// in its init response, in which case the client honours the returned value.
+const DEFAULT_CHUNK_SIZE: u32 = 1024 * 1024;
+
+/// A single cached conditional-GET response: the validator plus the raw body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    /// The `ETag` (or `Last-Modified`) value sent back as `If-None-Match`.
+    etag: Option<String>,
+    /// The cached JSON response body, returned verbatim on a `304`.
+    body: String,
+}
+
+/// Turn a reqwest transport error into a message the UI can classify.
+///
+/// Connection/proxy failures are prefixed `Proxy/connection error` so the
+/// frontend can point the user at their proxy setting, rather than at their
+/// login, when the network can't be reached at all. Auth failures surface
+/// separately as non-2xx HTTP statuses at the call sites.
+fn network_error(e: reqwest::Error) -> String {
+    if e.is_connect() || e.is_timeout() {
+        format!("Proxy/connection error: {}", e)
+    } else {
+        format!("Network error: {}", e)
+    }
+}
+
+/// Default retry-policy parameters for transient upload failures: five
+/// attempts with a 500ms base delay that doubles each time, capped at 30s.
+const RETRY_MAX_ATTEMPTS: u32 = 5;
+const RETRY_BASE_DELAY_MS: u64 = 500;
+const RETRY_FACTOR: u32 = 2;
+const RETRY_CAP_MS: u64 = 30_000;
+
+/// Backoff-with-jitter retry policy for transient upload failures.
+///
+/// Retries are attempted on connection/timeout transport errors and on
+/// `429` / `5xx` responses; `4xx` auth and validation errors are never
+/// retried. Set `max_attempts` to `1` (see [`RetryPolicy::disabled`]) to turn
+/// retries off entirely.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. `1` disables retries.
+    pub max_attempts: u32,
+    /// Base delay in milliseconds before the first retry; doubles each attempt.
+    pub base_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: RETRY_MAX_ATTEMPTS,
+            base_delay_ms: RETRY_BASE_DELAY_MS,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that performs a single attempt with no retries.
+    #[allow(dead_code)]
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay_ms: 0,
+        }
+    }
+
+    /// Delay before the given zero-based retry. A server-supplied `Retry-After`
+    /// wins (clamped to the cap); otherwise the exponential backoff with jitter.
+    fn delay_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(hint) = retry_after {
+            return hint.min(Duration::from_millis(RETRY_CAP_MS));
+        }
+        let factor = u64::from(RETRY_FACTOR.saturating_pow(attempt));
+        let base = self.base_delay_ms.saturating_mul(factor).min(RETRY_CAP_MS);
+        Duration::from_millis(base.saturating_add(jitter_ms(base)))
+    }
+}
+
+/// A small amount of randomised jitter (up to ~25% of `base`) to spread out
+/// retries from concurrent uploaders. Derived from the sub-second clock so we
+/// avoid pulling in an RNG dependency for this non-cryptographic use.
+fn jitter_ms(base: u64) -> u64 {
+    if base == 0 {
+        return 0;
+    }
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    nanos % (base / 4 + 1)
+}
+
+/// Parse a `Retry-After` header expressed as an integer number of seconds.
+/// The rarely-used HTTP-date form is ignored in favour of plain backoff.
+fn parse_retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// Tunable HTTP-client settings for the [`ReplayUploader`].
+///
+/// Defaults reproduce the original hardcoded behaviour — a 60s total request
+/// timeout, reqwest's default connect timeout and redirect policy, no proxy,
+/// and no response decompression — so existing callers are unaffected. Users
+/// behind corporate proxies or on slow links can override the relevant fields
+/// (e.g. a longer connect timeout or a SOCKS5 proxy URL).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayUploaderConfig {
+    /// Total per-request timeout in seconds. `None` uses the original 60s
+    /// ([`DEFAULT_REQUEST_TIMEOUT_SECS`]).
+    pub request_timeout_secs: Option<u64>,
+    /// TCP connect timeout in seconds. `None` leaves reqwest's default.
+    pub connect_timeout_secs: Option<u64>,
+    /// Maximum number of redirects to follow. `None` keeps reqwest's default
+    /// policy; `Some(0)` disables redirects entirely.
+    pub max_redirects: Option<usize>,
+    /// HTTP/HTTPS/SOCKS5 proxy URL; `None` honours the `*_PROXY` env vars.
+    pub proxy_url: Option<String>,
+    /// Request gzip response decompression.
+    pub gzip: bool,
+    /// Request brotli response decompression.
+    pub brotli: bool,
+}
+
+/// Original total request timeout, used when the config leaves it unset.
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// Current Unix time in seconds.
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Generate a fresh base64 request nonce for the signature envelope.
+fn gen_nonce() -> String {
+    use base64::Engine;
+    use rand_core::RngCore;
+    let mut bytes = [0u8; 16];
+    rand_core::OsRng.fill_bytes(&mut bytes);
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
 /// Response from get replays endpoint (used by tests)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetReplaysResponse {
@@ -22,41 +183,507 @@ pub struct ErrorResponse {
     pub error: String,
 }
 
+/// Outcome of a self-update attempt, reported back to the server so operators
+/// can track release-rollout adoption and failures across the fleet.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateReport {
+    /// Version we attempted to install.
+    pub attempted_version: String,
+    /// Version the app was running before the attempt.
+    pub previous_version: String,
+    /// `"success"`, `"download-error"`, or `"install-error"`.
+    pub outcome: String,
+    /// Target triple / OS the attempt ran on.
+    pub platform: String,
+    /// Error message plus any partially-downloaded state on failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Refresh the access token if it is within this many seconds of expiring.
+const TOKEN_EXPIRY_SKEW_SECS: u64 = 60;
+
+/// Response from the `/api/auth/refresh` endpoint.
+#[derive(Debug, Clone, Deserialize)]
+struct RefreshResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// Mutable token state shared across requests. Guarded by a mutex so a refresh
+/// on one request is visible to the next.
+#[derive(Debug, Clone)]
+struct TokenState {
+    access_token: String,
+    refresh_token: Option<String>,
+    /// Unix timestamp (seconds) at which the access token expires, if known.
+    expires_at: Option<u64>,
+}
+
 /// API client for replay upload operations
 pub struct ReplayUploader {
     base_url: String,
-    access_token: String,
+    auth: Mutex<TokenState>,
     client: reqwest::Client,
     logger: Option<Arc<DebugLogger>>,
+    /// Conditional-GET cache keyed by endpoint, loaded from disk on construction
+    /// and persisted whenever a fresh `200` response replaces an entry.
+    http_cache: Mutex<HashMap<String, CacheEntry>>,
+    /// Retry policy applied to the network requests that go through
+    /// [`ReplayUploader::send_with_retry`].
+    retry_policy: RetryPolicy,
+    /// Single-flight guard so a parallel batch that all sees `401` fires exactly
+    /// one token refresh rather than one per in-flight request.
+    refresh_lock: tokio::sync::Mutex<()>,
+    /// Optional per-device request signer. When present, uploads carry an
+    /// ed25519 signature over a canonical `METHOD\nPATH\nTIMESTAMP\nBODY_DIGEST`
+    /// string; see [`crate::device_identity`].
+    device_signer: Option<Arc<crate::device_identity::DeviceSigner>>,
+    /// Last `checked_at` timestamp observed from the server's manifest, used to
+    /// detect local clock skew before signing.
+    last_server_time: Mutex<Option<String>>,
 }
 
 impl ReplayUploader {
     /// Create a new replay uploader with access token (used by tests)
     #[allow(dead_code)]
     pub fn new(base_url: String, access_token: String) -> Self {
-        Self::with_logger(base_url, access_token, None)
+        Self::with_logger(base_url, access_token, None, None)
     }
 
-    /// Create a new replay uploader with access token and optional logger
-    pub fn with_logger(base_url: String, access_token: String, logger: Option<Arc<DebugLogger>>) -> Self {
-        // Create client with 60 second timeout for replay uploads
-        // (analysis can take time, so we give it more time)
-        // Include version in User-Agent header for tracking
-        let version = env!("CARGO_PKG_VERSION");
-        let user_agent = format!("LadderLegendsUploader/{}", version);
+    /// Create a replay uploader with full auth context, enabling transparent
+    /// token refresh. `expires_at` is a Unix timestamp in seconds.
+    ///
+    /// `proxy_url` routes all requests through the given HTTP/HTTPS/SOCKS proxy;
+    /// `None` falls back to reqwest's default handling of the `HTTP_PROXY` /
+    /// `HTTPS_PROXY` / `NO_PROXY` environment variables ("use system proxy").
+    pub fn with_auth(
+        base_url: String,
+        access_token: String,
+        refresh_token: Option<String>,
+        expires_at: Option<u64>,
+        proxy_url: Option<String>,
+        logger: Option<Arc<DebugLogger>>,
+    ) -> Self {
+        let mut uploader = Self::with_logger(base_url, access_token, proxy_url, logger);
+        {
+            let mut auth = uploader.auth.lock().unwrap_or_else(|e| e.into_inner());
+            auth.refresh_token = refresh_token;
+            auth.expires_at = expires_at;
+        }
+        uploader
+    }
 
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(60))
-            .user_agent(&user_agent)
-            .build()
-            .unwrap_or_else(|_| reqwest::Client::new());
+    /// Create a new replay uploader with access token and optional logger.
+    ///
+    /// When `proxy_url` is set the reqwest client routes every request through
+    /// it via [`reqwest::Proxy::all`]; when it is `None` reqwest keeps its
+    /// default behaviour of honouring `HTTP_PROXY` / `HTTPS_PROXY` / `NO_PROXY`.
+    pub fn with_logger(
+        base_url: String,
+        access_token: String,
+        proxy_url: Option<String>,
+        logger: Option<Arc<DebugLogger>>,
+    ) -> Self {
+        let config = ReplayUploaderConfig {
+            proxy_url,
+            ..Default::default()
+        };
+        Self::with_config(base_url, access_token, config, logger)
+    }
+
+    /// Create a replay uploader with a fully-specified HTTP-client
+    /// [`ReplayUploaderConfig`]. See [`ReplayUploader::with_logger`] for the
+    /// default-behaviour convenience wrapper.
+    pub fn with_config(
+        base_url: String,
+        access_token: String,
+        config: ReplayUploaderConfig,
+        logger: Option<Arc<DebugLogger>>,
+    ) -> Self {
+        let client = Self::build_client(&config, logger.as_ref());
+
+        // Best-effort load of the on-disk cache; an unreadable cache just
+        // means we fall back to unconditional requests. This is a tiny one-time
+        // read during construction, so a plain synchronous read is fine here —
+        // the hot paths below go through the async config helpers.
+        let http_cache = crate::config_utils::config_file_path(HTTP_CACHE_FILE)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<HashMap<String, CacheEntry>>(&contents).ok())
+            .unwrap_or_default();
 
         Self {
             base_url,
-            access_token,
+            auth: Mutex::new(TokenState {
+                access_token,
+                refresh_token: None,
+                expires_at: None,
+            }),
             client,
             logger,
+            http_cache: Mutex::new(http_cache),
+            retry_policy: RetryPolicy::default(),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            device_signer: None,
+            last_server_time: Mutex::new(None),
+        }
+    }
+
+    /// Attach a per-device signer so uploads are signed for integrity and
+    /// anti-tamper. Without one, uploads fall back to bearer-only auth.
+    pub fn with_device_signer(
+        mut self,
+        signer: Arc<crate::device_identity::DeviceSigner>,
+    ) -> Self {
+        self.device_signer = Some(signer);
+        self
+    }
+
+    /// Build the reqwest client from a [`ReplayUploaderConfig`], applying the
+    /// tunable timeouts, redirect policy, proxy, and decompression options. A
+    /// malformed proxy URL is logged and ignored so a bad setting never bricks
+    /// uploads entirely; a client that fails to build falls back to the default.
+    fn build_client(
+        config: &ReplayUploaderConfig,
+        logger: Option<&Arc<DebugLogger>>,
+    ) -> reqwest::Client {
+        // Include version in User-Agent header for tracking.
+        let version = env!("CARGO_PKG_VERSION");
+        let user_agent = format!("LadderLegendsUploader/{}", version);
+
+        // A long total timeout by default: replay analysis can take time.
+        let request_timeout = config
+            .request_timeout_secs
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS);
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(request_timeout))
+            .user_agent(&user_agent)
+            .gzip(config.gzip)
+            .brotli(config.brotli);
+
+        if let Some(secs) = config.connect_timeout_secs {
+            builder = builder.connect_timeout(std::time::Duration::from_secs(secs));
+        }
+        if let Some(max) = config.max_redirects {
+            builder = builder.redirect(reqwest::redirect::Policy::limited(max));
+        }
+
+        // An explicit proxy wins over the environment; a malformed URL is logged
+        // and ignored so a bad setting never bricks uploads entirely.
+        if let Some(url) = config.proxy_url.as_deref().filter(|u| !u.trim().is_empty()) {
+            match reqwest::Proxy::all(url) {
+                Ok(proxy) => {
+                    builder = builder.proxy(proxy);
+                    if let Some(logger) = logger {
+                        logger.info(format!("Routing uploads through proxy: {}", url));
+                    }
+                }
+                Err(e) => {
+                    if let Some(logger) = logger {
+                        logger.warn(format!("Ignoring invalid proxy URL '{}': {}", url, e));
+                    }
+                }
+            }
+        }
+
+        builder.build().unwrap_or_else(|_| reqwest::Client::new())
+    }
+
+    /// Override the retry policy (for tuning or disabling retries).
+    #[allow(dead_code)]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Send a request built by `build`, retrying transient failures per the
+    /// configured [`RetryPolicy`].
+    ///
+    /// `build` is invoked fresh for every attempt so that consumed bodies
+    /// (e.g. multipart uploads) can be reconstructed. Retries cover transport
+    /// connect/timeout errors and `429` / `5xx` responses — the latter honour a
+    /// `Retry-After` header — while other transport errors and `4xx` responses
+    /// are returned to the caller immediately. Each retry is logged through the
+    /// optional [`DebugLogger`].
+    async fn send_with_retry(
+        &self,
+        op: &str,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, String> {
+        let policy = self.retry_policy;
+        let mut attempt = 0u32;
+        // Allow exactly one transparent token refresh + replay per call so an
+        // expired token mid-session recovers without surfacing to the user.
+        let mut refreshed = false;
+        loop {
+            let has_retries_left = attempt + 1 < policy.max_attempts;
+            match build().send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    // Expired access token: refresh once (single-flight) and
+                    // replay the request with the new token. This replay does
+                    // not consume the retry budget.
+                    if status == reqwest::StatusCode::UNAUTHORIZED
+                        && !refreshed
+                        && self.has_refresh_token()
+                    {
+                        refreshed = true;
+                        self.refresh_access_token(op).await?;
+                        continue;
+                    }
+                    let retryable = status.as_u16() == 429 || status.is_server_error();
+                    if retryable && has_retries_left {
+                        let delay = policy.delay_for(attempt, parse_retry_after(&response));
+                        if let Some(ref logger) = self.logger {
+                            logger.warn(format!(
+                                "{} returned {} (attempt {}/{}), retrying in {}ms",
+                                op,
+                                status,
+                                attempt + 1,
+                                policy.max_attempts,
+                                delay.as_millis()
+                            ));
+                        }
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    let retryable = e.is_connect() || e.is_timeout();
+                    if retryable && has_retries_left {
+                        let delay = policy.delay_for(attempt, None);
+                        if let Some(ref logger) = self.logger {
+                            logger.warn(format!(
+                                "{} transport error (attempt {}/{}), retrying in {}ms: {}",
+                                op,
+                                attempt + 1,
+                                policy.max_attempts,
+                                delay.as_millis(),
+                                e
+                            ));
+                        }
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    return Err(network_error(e));
+                }
+            }
+        }
+    }
+
+    /// Whether a refresh token is available for transparent 401 recovery.
+    fn has_refresh_token(&self) -> bool {
+        self.auth
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .refresh_token
+            .is_some()
+    }
+
+    /// Refresh the access token in response to a `401`, coalescing concurrent
+    /// callers behind a single-flight lock: the first caller performs the
+    /// refresh while the rest wait, then observe the already-updated token and
+    /// skip a redundant round-trip. A failure here is surfaced with a distinct
+    /// prefix so the UI can prompt the user to re-login rather than treat it as
+    /// a transient network error.
+    async fn refresh_access_token(&self, op: &str) -> Result<(), String> {
+        let token_before = self.access_token();
+        let _guard = self.refresh_lock.lock().await;
+        // Another caller refreshed while we waited for the lock.
+        if self.access_token() != token_before {
+            return Ok(());
+        }
+        if let Some(ref logger) = self.logger {
+            logger.info(format!("{} hit 401; refreshing access token", op));
+        }
+        self.refresh_token()
+            .await
+            .map_err(|e| format!("Authentication expired, please sign in again: {}", e))
+    }
+
+    /// Current access token.
+    fn access_token(&self) -> String {
+        self.auth
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .access_token
+            .clone()
+    }
+
+    /// Refresh the access token proactively if it is within the skew window of
+    /// expiring. A no-op when there is no refresh token or no known expiry.
+    async fn refresh_if_expiring(&self) {
+        let needs_refresh = {
+            let auth = self.auth.lock().unwrap_or_else(|e| e.into_inner());
+            match (auth.refresh_token.as_ref(), auth.expires_at) {
+                (Some(_), Some(expires_at)) => now_secs() + TOKEN_EXPIRY_SKEW_SECS >= expires_at,
+                _ => false,
+            }
+        };
+        if needs_refresh {
+            if let Err(e) = self.refresh_token().await {
+                if let Some(ref logger) = self.logger {
+                    logger.warn(format!("Proactive token refresh failed: {}", e));
+                }
+            }
+        }
+    }
+
+    /// POST the refresh token to `/api/auth/refresh` and update the stored token
+    /// state on success. Persists the new tokens through the secure token store.
+    async fn refresh_token(&self) -> Result<(), String> {
+        let refresh_token = {
+            let auth = self.auth.lock().unwrap_or_else(|e| e.into_inner());
+            auth.refresh_token.clone()
+        }
+        .ok_or("No refresh token available")?;
+
+        let url = format!("{}/api/auth/refresh", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({ "refresh_token": refresh_token }))
+            .send()
+            .await
+            .map_err(|e| format!("Token refresh network error: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(format!("Token refresh failed: {}", status));
+        }
+
+        let refreshed: RefreshResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse refresh response: {}", e))?;
+
+        {
+            let mut auth = self.auth.lock().unwrap_or_else(|e| e.into_inner());
+            auth.access_token = refreshed.access_token.clone();
+            if let Some(new_refresh) = refreshed.refresh_token.clone() {
+                auth.refresh_token = Some(new_refresh);
+            }
+            auth.expires_at = refreshed.expires_in.map(|secs| now_secs() + secs);
+        }
+
+        if let Some(ref logger) = self.logger {
+            logger.info("Access token refreshed".to_string());
+        }
+
+        // Persist the refreshed tokens so they survive restart. Tokens are
+        // encrypted at rest, so this goes through the secure store rather than
+        // the plaintext config writer.
+        let snapshot = {
+            let auth = self.auth.lock().unwrap_or_else(|e| e.into_inner());
+            crate::types::AuthTokens {
+                access_token: crate::types::SecretString::new(auth.access_token.clone()),
+                refresh_token: auth
+                    .refresh_token
+                    .clone()
+                    .map(crate::types::SecretString::new),
+                expires_at: auth.expires_at,
+                user: None,
+            }
+        };
+        if let Ok(path) = crate::config_utils::config_file_path("auth.json") {
+            if let Err(e) = crate::token_store::SecureTokenStore::open(path)
+                .and_then(|store| store.save(&snapshot))
+            {
+                if let Some(ref logger) = self.logger {
+                    logger.warn(format!("Failed to persist refreshed tokens: {}", e));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Perform a conditional GET against `url`, keyed in the cache by `cache_key`.
+    ///
+    /// Sends `If-None-Match` with the stored `ETag`; on `304 Not Modified` the
+    /// cached body is deserialized and returned, avoiding a full re-download.
+    /// On a `200` the body is parsed, and if the server supplied a new `ETag`
+    /// the cache is overwritten and persisted.
+    async fn conditional_get<T: DeserializeOwned>(
+        &self,
+        cache_key: &str,
+        url: &str,
+    ) -> Result<T, String> {
+        self.refresh_if_expiring().await;
+
+        let cached_etag = {
+            let cache = self.http_cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache.get(cache_key).and_then(|e| e.etag.clone())
+        };
+
+        let response = self
+            .send_with_retry("conditional_get", || {
+                let mut request = self.client.get(url).bearer_auth(&self.access_token());
+                if let Some(ref etag) = cached_etag {
+                    request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                request
+            })
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            let cache = self.http_cache.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = cache
+                .get(cache_key)
+                .ok_or("Server returned 304 but no cached body is available")?;
+            if let Some(ref logger) = self.logger {
+                logger.debug(format!("Using cached response for {}", cache_key));
+            }
+            return serde_json::from_str(&entry.body)
+                .map_err(|e| format!("Failed to parse cached response: {}", e));
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Request to {} failed {}: {}", cache_key, status, error_text));
+        }
+
+        // Capture the validator before consuming the body.
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response body: {}", e))?;
+
+        let parsed: T = serde_json::from_str(&body)
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        // Only cache responses that carry a validator we can replay later.
+        if let Some(etag) = etag {
+            {
+                let mut cache = self.http_cache.lock().unwrap_or_else(|e| e.into_inner());
+                cache.insert(
+                    cache_key.to_string(),
+                    CacheEntry {
+                        etag: Some(etag),
+                        body,
+                    },
+                );
+            }
+            let snapshot = self.http_cache.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            let _ = save_config_file(HTTP_CACHE_FILE, &snapshot).await;
         }
+
+        Ok(parsed)
     }
 
     /// Get URL for my-replays endpoint
@@ -65,6 +692,7 @@ impl ReplayUploader {
     }
 
     /// Upload a replay file
+    #[allow(clippy::too_many_arguments)]
     pub async fn upload_replay(
         &self,
         file_path: &Path,
@@ -72,9 +700,17 @@ impl ReplayUploader {
         target_build_id: Option<&str>,
         game_type: Option<&str>,
         region: Option<&str>,
+        realm: Option<u8>,
     ) -> Result<StoredReplay, String> {
-        // Read file contents
-        let file_contents = fs::read(file_path)
+        // Reject obviously-invalid files locally before touching the network.
+        crate::replay_parser::validate_replay_file(file_path)?;
+
+        self.refresh_if_expiring().await;
+
+        // Read file contents without blocking the async runtime's worker thread;
+        // replay files can be several megabytes.
+        let file_contents = tokio::fs::read(file_path)
+            .await
             .map_err(|e| format!("Failed to read file: {}", e))?;
 
         let filename = file_path.file_name()
@@ -101,28 +737,206 @@ impl ReplayUploader {
             if let Some(r) = region {
                 query_pairs.append_pair("region", r);
             }
+            if let Some(realm) = realm {
+                query_pairs.append_pair("realm", &realm.to_string());
+            }
         }
 
-        // Create multipart form
-        let part = reqwest::multipart::Part::bytes(file_contents)
-            .file_name(filename);
+        // Sign the request over the file bytes (the meaningful body). The
+        // signature, timestamp, body digest, and nonce ride along as headers;
+        // absence of a signer leaves this empty and uploads stay bearer-only.
+        let signature = self.build_signature("POST", url.path(), &file_contents)?;
+
+        // Send request, rebuilding the multipart form on each retry since the
+        // body is consumed when the request is sent.
+        let response = self
+            .send_with_retry("upload_replay", || {
+                let part = reqwest::multipart::Part::bytes(file_contents.clone())
+                    .file_name(filename.clone());
+                let form = reqwest::multipart::Form::new().part("file", part);
+                let mut request = self
+                    .client
+                    .post(url.clone()) // reqwest::Url is accepted directly
+                    .bearer_auth(&self.access_token())
+                    .multipart(form);
+                if let Some(sig) = &signature {
+                    use crate::device_identity as di;
+                    request = request
+                        .header(di::HEADER_DEVICE_ID, &sig.device_id)
+                        .header(di::HEADER_TIMESTAMP, &sig.timestamp)
+                        .header(di::HEADER_BODY_DIGEST, &sig.body_digest)
+                        .header(di::HEADER_NONCE, &sig.nonce)
+                        .header(di::HEADER_SIGNATURE, &sig.signature);
+                }
+                request
+            })
+            .await?;
+
+        // A signed request expects the server to echo our nonce; a mismatch
+        // means the response was replayed or tampered with.
+        if let Some(sig) = &signature {
+            self.verify_nonce_echo(&sig.nonce, &response)?;
+        }
 
-        let form = reqwest::multipart::Form::new()
-            .part("file", part);
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Upload failed {}: {}", status, error_text));
+        }
 
-        // Send request
-        let response = self.client
-            .post(url)  // reqwest::Url is accepted directly
-            .bearer_auth(&self.access_token)
-            .multipart(form)
-            .send()
+        let data: UploadReplayResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        // Handle discriminated union response
+        match data.replay() {
+            Some(replay) => Ok(replay.clone()),
+            None => {
+                if let Some(error) = data.error() {
+                    Err(format!("Upload failed: {} ({})", error.message, error.code))
+                } else {
+                    Err("Upload failed with unknown error".to_string())
+                }
+            }
+        }
+    }
+
+    /// Upload a replay in resumable chunks.
+    ///
+    /// Unlike [`upload_replay`], which sends the whole file in one request, this
+    /// splits the file into fixed-size chunks and tracks per-chunk progress on
+    /// the server. An interrupted transfer resumes by re-running init (which
+    /// reports the chunks the server already holds) and sending only the
+    /// missing ones. The `hash` must be the SHA-256 of the file contents; the
+    /// server recomputes it at finalize and rejects a mismatch.
+    ///
+    /// [`upload_replay`]: Self::upload_replay
+    #[allow(dead_code)]
+    pub async fn upload_replay_chunked(
+        &self,
+        file_path: &Path,
+        hash: &str,
+    ) -> Result<StoredReplay, String> {
+        crate::replay_parser::validate_replay_file(file_path)?;
+        self.refresh_if_expiring().await;
+
+        let file_contents = tokio::fs::read(file_path)
             .await
-            .map_err(|e| format!("Network error: {}", e))?;
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+        let filesize = file_contents.len() as u64;
+
+        // Begin or resume the upload. The server is the source of truth for
+        // which chunks it already holds, so we always init before sending.
+        let init = self.init_chunked_upload(hash, filesize).await?;
+        let chunk_size = init.chunk_size.max(1) as usize;
+        let total_chunks = file_contents.len().div_ceil(chunk_size) as u32;
+
+        self.persist_chunk_upload_id(hash, &init.upload_id).await;
+
+        for index in init.missing_chunks(total_chunks) {
+            let start = index as usize * chunk_size;
+            let end = (start + chunk_size).min(file_contents.len());
+            self.upload_chunk(&init.upload_id, index, &file_contents[start..end])
+                .await?;
+        }
+
+        let replay = self.finalize_chunked_upload(&init.upload_id, hash).await?;
+        self.clear_chunk_upload_id(hash).await;
+        Ok(replay)
+    }
+
+    /// Begin or resume a chunked upload, returning the server's chunk state.
+    async fn init_chunked_upload(
+        &self,
+        hash: &str,
+        filesize: u64,
+    ) -> Result<UploadInitResponse, String> {
+        let url = format!("{}/api/my-replays/upload/init", self.base_url);
+        let request = UploadInitRequest {
+            hash: hash.to_string(),
+            filesize,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        };
+
+        let response = self
+            .send_with_retry("upload_init", || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(&self.access_token())
+                    .json(&request)
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Upload failed {}: {}", status, error_text));
+            return Err(format!("Upload init failed {}: {}", status, error_text));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse upload init response: {}", e))
+    }
+
+    /// Send a single chunk. The bytes travel as the raw request body; the
+    /// `upload_id` and `index` identify the chunk as query parameters.
+    async fn upload_chunk(
+        &self,
+        upload_id: &str,
+        index: u32,
+        bytes: &[u8],
+    ) -> Result<(), String> {
+        let mut url = reqwest::Url::parse(&format!("{}/api/my-replays/upload/chunk", self.base_url))
+            .map_err(|e| format!("Invalid base URL: {}", e))?;
+        url.query_pairs_mut()
+            .append_pair("upload_id", upload_id)
+            .append_pair("index", &index.to_string());
+
+        let response = self
+            .send_with_retry("upload_chunk", || {
+                self.client
+                    .post(url.clone())
+                    .bearer_auth(&self.access_token())
+                    .body(bytes.to_vec())
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Chunk {} upload failed {}: {}", index, status, error_text));
+        }
+
+        Ok(())
+    }
+
+    /// Finalize the upload; the server verifies the reassembled SHA-256.
+    async fn finalize_chunked_upload(
+        &self,
+        upload_id: &str,
+        hash: &str,
+    ) -> Result<StoredReplay, String> {
+        let url = format!("{}/api/my-replays/upload/finalize", self.base_url);
+        let request = UploadFinalizeRequest {
+            upload_id: upload_id.to_string(),
+            hash: hash.to_string(),
+        };
+
+        let response = self
+            .send_with_retry("upload_finalize", || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(&self.access_token())
+                    .json(&request)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Upload finalize failed {}: {}", status, error_text));
         }
 
         let data: UploadReplayResponse = response
@@ -130,7 +944,6 @@ impl ReplayUploader {
             .await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-        // Handle discriminated union response
         match data.replay() {
             Some(replay) => Ok(replay.clone()),
             None => {
@@ -143,6 +956,66 @@ impl ReplayUploader {
         }
     }
 
+    /// Record the `upload_id` for `hash` so an interrupted upload can resume.
+    async fn persist_chunk_upload_id(&self, hash: &str, upload_id: &str) {
+        let mut state: HashMap<String, String> =
+            crate::config_utils::load_config_file(CHUNK_STATE_FILE)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+        state.insert(hash.to_string(), upload_id.to_string());
+        let _ = save_config_file(CHUNK_STATE_FILE, &state).await;
+    }
+
+    /// Forget the persisted `upload_id` for `hash` once the upload completes.
+    async fn clear_chunk_upload_id(&self, hash: &str) {
+        let mut state: HashMap<String, String> =
+            match crate::config_utils::load_config_file(CHUNK_STATE_FILE)
+                .await
+                .ok()
+                .flatten()
+            {
+                Some(state) => state,
+                None => return,
+            };
+        if state.remove(hash).is_some() {
+            let _ = save_config_file(CHUNK_STATE_FILE, &state).await;
+        }
+    }
+
+    /// Report the outcome of a self-update attempt to the server.
+    ///
+    /// Best-effort telemetry sent before the app restarts; a failure here is
+    /// returned to the caller but should never block the update itself.
+    pub async fn report_update_result(&self, report: &UpdateReport) -> Result<(), String> {
+        let url = format!("{}/api/updates/report", self.base_url);
+
+        if let Some(ref logger) = self.logger {
+            logger.debug(format!(
+                "Reporting update outcome '{}' for {} to server",
+                report.outcome, report.attempted_version
+            ));
+        }
+
+        let response = self
+            .send_with_retry("report_update_result", || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(&self.access_token())
+                    .json(report)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("Update report rejected {}: {}", status, error_text));
+        }
+
+        Ok(())
+    }
+
     /// Check which hashes are new on the server
     pub async fn check_hashes(
         &self,
@@ -152,24 +1025,28 @@ impl ReplayUploader {
 
         let request = CheckHashesRequest { hashes };
 
+        self.refresh_if_expiring().await;
+        let token = self.access_token();
+
         // Log auth debug info if logger is available
         if let Some(ref logger) = self.logger {
-            let token_preview = if self.access_token.len() > 20 {
-                &self.access_token[..20]
+            let token_preview = if token.len() > 20 {
+                &token[..20]
             } else {
-                &self.access_token
+                &token
             };
             logger.debug(format!("Using access token (first 20 chars): {}...", token_preview));
             logger.debug(format!("Sending check-hashes request to: {}", url));
         }
 
-        let response = self.client
-            .post(&url)
-            .bearer_auth(&self.access_token)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
+        let response = self
+            .send_with_retry("check_hashes", || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(&token)
+                    .json(&request)
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -188,25 +1065,7 @@ impl ReplayUploader {
     /// Get user settings (player names, preferences)
     pub async fn get_user_settings(&self) -> Result<UserSettings, String> {
         let url = format!("{}/api/settings", self.base_url);
-
-        let response = self.client
-            .get(&url)
-            .bearer_auth(&self.access_token)
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Failed to fetch settings {}: {}", status, error_text));
-        }
-
-        let data: UserSettingsResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse settings response: {}", e))?;
-
+        let data: UserSettingsResponse = self.conditional_get("settings", &url).await?;
         Ok(data.settings)
     }
 
@@ -226,12 +1085,13 @@ impl ReplayUploader {
             logger.debug(format!("Fetching manifest version from: {}", url));
         }
 
-        let response = self.client
-            .get(&url)
-            .bearer_auth(&self.access_token)
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
+        self.refresh_if_expiring().await;
+
+        let response = self
+            .send_with_retry("get_manifest_version", || {
+                self.client.get(&url).bearer_auth(&self.access_token())
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -248,32 +1108,82 @@ impl ReplayUploader {
             logger.info(format!("Server manifest version: {}", data.manifest_version));
         }
 
+        // Remember the server's wall-clock so uploads can refuse to sign under
+        // excessive clock skew.
+        *self.last_server_time.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some(data.checked_at.clone());
+
         Ok(data)
     }
 
-    /// Get user's replays from server (used by integration tests)
-    #[allow(dead_code)]
-    pub async fn get_user_replays(&self) -> Result<Vec<StoredReplay>, String> {
-        let url = self.my_replays_url();
-
-        let response = self.client
-            .get(&url)
-            .bearer_auth(&self.access_token)
-            .send()
-            .await
-            .map_err(|e| format!("Network error: {}", e))?;
+    /// Build the device-signature headers for a request over `body`, or `None`
+    /// when no signer is configured.
+    ///
+    /// Refuses to sign (returning an `Err`) when the local clock differs from
+    /// the last-known server time by more than
+    /// [`device_identity::MAX_CLOCK_SKEW_SECS`], since such a timestamp would be
+    /// rejected by the server as a replay attempt. The returned nonce must be
+    /// echoed back by the server and checked with [`verify_nonce_echo`].
+    ///
+    /// [`device_identity::MAX_CLOCK_SKEW_SECS`]: crate::device_identity::MAX_CLOCK_SKEW_SECS
+    /// [`verify_nonce_echo`]: Self::verify_nonce_echo
+    fn build_signature(
+        &self,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> Result<Option<crate::device_identity::SignatureEnvelope>, String> {
+        use crate::device_identity;
+
+        let Some(signer) = self.device_signer.as_ref() else {
+            return Ok(None);
+        };
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Failed to fetch replays {}: {}", status, error_text));
+        let now = now_secs() as i64;
+        if let Some(server_time) = self
+            .last_server_time
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_deref()
+        {
+            let skew = device_identity::clock_skew_secs(now, server_time)?;
+            if skew.abs() > device_identity::MAX_CLOCK_SKEW_SECS {
+                return Err(format!(
+                    "Refusing to sign upload: local clock differs from server by {}s (max {}s)",
+                    skew, device_identity::MAX_CLOCK_SKEW_SECS
+                ));
+            }
         }
 
-        let data: GetReplaysResponse = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse replays response: {}", e))?;
+        let timestamp = chrono::Utc::now().to_rfc3339();
+        let nonce = gen_nonce();
+        let envelope = signer.sign(method, path, &timestamp, body, &nonce)?;
+        Ok(Some(envelope))
+    }
+
+    /// Confirm the server echoed the nonce we signed with; a mismatch means the
+    /// response was replayed or tampered and the upload must not be trusted.
+    fn verify_nonce_echo(
+        &self,
+        expected: &str,
+        response: &reqwest::Response,
+    ) -> Result<(), String> {
+        let echoed = response
+            .headers()
+            .get(crate::device_identity::HEADER_NONCE)
+            .and_then(|v| v.to_str().ok());
+        match echoed {
+            Some(value) if value == expected => Ok(()),
+            Some(_) => Err("Server nonce echo did not match the signed nonce".to_string()),
+            None => Err("Server did not echo the signed nonce".to_string()),
+        }
+    }
 
+    /// Get user's replays from server (used by integration tests)
+    #[allow(dead_code)]
+    pub async fn get_user_replays(&self) -> Result<Vec<StoredReplay>, String> {
+        let url = self.my_replays_url();
+        let data: GetReplaysResponse = self.conditional_get("my-replays", &url).await?;
         Ok(data.replays)
     }
 }
@@ -285,7 +1195,10 @@ mod tests {
 
     fn create_test_replay(dir: &Path, name: &str, contents: &[u8]) -> std::path::PathBuf {
         let path = dir.join(name);
-        fs::write(&path, contents).unwrap();
+        // Prefix with the MPQ magic so local pre-upload validation accepts it.
+        let mut bytes = b"MPQ\x1a".to_vec();
+        bytes.extend_from_slice(contents);
+        fs::write(&path, bytes).unwrap();
         path
     }
 
@@ -297,7 +1210,7 @@ mod tests {
         );
 
         assert_eq!(uploader.base_url, "https://example.com");
-        assert_eq!(uploader.access_token, "test-token");
+        assert_eq!(uploader.access_token(), "test-token");
     }
 
     #[test]
@@ -466,7 +1379,7 @@ mod tests {
                 .expect("TEST_ACCESS_TOKEN env var required for integration tests"),
         );
 
-        let result = uploader.upload_replay(&replay_path, None, None, None, None).await;
+        let result = uploader.upload_replay(&replay_path, None, None, None, None, None).await;
 
         // Don't assert success - just verify it returns a result
         match result {
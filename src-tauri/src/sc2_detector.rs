@@ -1,6 +1,9 @@
 use crate::debug_logger::DebugLogger;
-use std::path::PathBuf;
-use std::sync::Arc;
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
 use std::fs;
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -9,6 +12,85 @@ pub struct SC2ReplayFolder {
     pub account_id: String,
     pub region: String,      // Human-readable: "NA", "EU", "KR", "CN"
     pub region_code: String, // Raw folder name: "1-S2-1-802768"
+    /// Structured breakdown of `region_code`'s gateway/realm/profile numbers,
+    /// or `None` if it doesn't match the expected `<gateway>-S2-<realm>-<profile>`
+    /// shape. Lets callers key uploads by profile ID or tell two accounts on
+    /// the same gateway apart, which `region`/`region_code` alone can't do.
+    pub account_folder: Option<AccountFolderName>,
+}
+
+/// Region implied by an account folder's gateway, and, for gateways that
+/// split by realm, its realm too: on the Americas gateway realm `2` is Latin
+/// America (anything else is North America); on the Korea/Taiwan gateway
+/// realm `2` is Taiwan (anything else is Korea).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum Region {
+    NorthAmerica,
+    SouthAmerica,
+    Europe,
+    Korea,
+    Taiwan,
+    China,
+    PublicTest,
+    Unknown,
+}
+
+/// Structured decomposition of an account folder name like `"1-S2-1-802768"`
+/// (`<gateway>-S2-<realm>-<profile>`), produced by [`parse_account_folder_name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct AccountFolderName {
+    pub gateway: u8,
+    pub realm: u8,
+    pub profile: u64,
+    pub region: Region,
+}
+
+/// Regex for an account folder name: `<gateway>-S2-<realm>-<profile>`.
+fn account_folder_regex() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(?P<gateway>\d+)-S2-(?P<realm>\d+)-(?P<profile>\d+)$")
+            .expect("account folder regex is valid")
+    })
+}
+
+/// Gateway/realm to [`Region`] mapping, shared with
+/// [`crate::replay_parser`]'s `m_toon` decoding and
+/// [`crate::services::upload_executor`]'s account-folder decoding so an
+/// account folder, a replay's player list, and an upload's region metadata
+/// all agree on what region a given gateway/realm pair means.
+///
+/// Per Blizzard's Battle.net gateway assignments: 1 = Americas (realm 1 = US,
+/// realm 2 = LATAM), 2 = Europe, 3 = Korea/Taiwan (realm 2 = Taiwan), 5 =
+/// China, 98/99 = public test realms.
+pub(crate) fn region_for_gateway(gateway: u8, realm: u8) -> Region {
+    match gateway {
+        1 if realm == 2 => Region::SouthAmerica,
+        1 => Region::NorthAmerica,
+        2 => Region::Europe,
+        3 if realm == 2 => Region::Taiwan,
+        3 => Region::Korea,
+        5 => Region::China,
+        98 | 99 => Region::PublicTest,
+        _ => Region::Unknown,
+    }
+}
+
+/// Parse an account folder name into its gateway/realm/profile numbers and
+/// the [`Region`] they imply. Returns `None` (rather than a silently
+/// incorrect guess) if `folder_name` doesn't match the expected
+/// `<gateway>-S2-<realm>-<profile>` shape.
+pub fn parse_account_folder_name(folder_name: &str) -> Option<AccountFolderName> {
+    let caps = account_folder_regex().captures(folder_name)?;
+    let gateway: u8 = caps["gateway"].parse().ok()?;
+    let realm: u8 = caps["realm"].parse().ok()?;
+    let profile: u64 = caps["profile"].parse().ok()?;
+    Some(AccountFolderName {
+        gateway,
+        realm,
+        profile,
+        region: region_for_gateway(gateway, realm),
+    })
 }
 
 /// Parse region from folder name (e.g., "1-S2-1-802768" -> "NA")
@@ -31,6 +113,82 @@ fn parse_region_from_folder(folder_name: &str) -> String {
     }
 }
 
+/// Recover the `account_id`/`region` a watched multiplayer folder belongs to
+/// from its path alone, given it was produced by [`find_all_multiplayer_folders`]
+/// (`<account_id>/<region_code>/Replays/Multiplayer`). Lets callers that only
+/// kept the bare path — like [`crate::upload_manager::UploadManager`]'s
+/// watcher — recover the metadata without re-running detection.
+pub fn folder_account_and_region(folder: &Path) -> Option<(String, String)> {
+    let region_dir = folder.parent()?.parent()?;
+    let region_code = region_dir.file_name()?.to_str()?.to_string();
+    let account_id = region_dir.parent()?.file_name()?.to_str()?.to_string();
+    let region = parse_region_from_folder(&region_code);
+    Some((account_id, region))
+}
+
+/// One `.SC2Replay` file found by [`list_replays`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ReplayFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// List the files directly inside `folder.path` matching `pattern`
+/// (`*`/`?` wildcards, defaulting to `"*.SC2Replay"`), newest-first by
+/// modification time. Lets the UI show recent replays per account, or the
+/// uploader select only replays newer than its last upload, without every
+/// call site re-walking the directory itself.
+pub fn list_replays(folder: &SC2ReplayFolder, pattern: Option<&str>) -> Vec<ReplayFile> {
+    let pattern = pattern.unwrap_or("*.SC2Replay");
+
+    let Ok(entries) = fs::read_dir(&folder.path) else {
+        return Vec::new();
+    };
+
+    let mut replays: Vec<ReplayFile> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+        .filter(|entry| wildcard_match(pattern, &entry.file_name().to_string_lossy()))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(ReplayFile {
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().ok()?,
+            })
+        })
+        .collect();
+
+    replays.sort_by(|a, b| b.modified.cmp(&a.modified));
+    replays
+}
+
+/// Minimal `*`/`?` glob matcher (no character classes): `*` matches any run
+/// of characters (including none), `?` matches exactly one.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+
+    let mut dp = vec![vec![false; t.len() + 1]; p.len() + 1];
+    dp[0][0] = true;
+    for i in 1..=p.len() {
+        if p[i - 1] == '*' {
+            dp[i][0] = dp[i - 1][0];
+        }
+    }
+    for i in 1..=p.len() {
+        for j in 1..=t.len() {
+            dp[i][j] = match p[i - 1] {
+                '*' => dp[i - 1][j] || dp[i][j - 1],
+                '?' => dp[i - 1][j - 1],
+                c => c == t[j - 1] && dp[i - 1][j - 1],
+            };
+        }
+    }
+    dp[p.len()][t.len()]
+}
+
 /// Detect StarCraft 2 replay folder on the current platform
 /// Detects ALL SC2 replay folders for all accounts
 pub fn detect_all_sc2_folders(logger: Option<Arc<DebugLogger>>) -> Vec<SC2ReplayFolder> {
@@ -97,26 +255,208 @@ fn detect_all_macos(logger: Option<Arc<DebugLogger>>) -> Vec<SC2ReplayFolder> {
 fn detect_all_linux(logger: Option<Arc<DebugLogger>>) -> Vec<SC2ReplayFolder> {
     let mut all_folders = Vec::new();
 
-    if let Some(home) = dirs::home_dir() {
-        // Try Wine/Proton paths
-        let wine_path = home.join(".wine/drive_c/users");
-        if wine_path.exists() {
-            // Find username in wine
-            if let Ok(entries) = fs::read_dir(&wine_path) {
-                for entry in entries.flatten() {
-                    if entry.file_type().ok().map(|ft| ft.is_dir()).unwrap_or(false) {
-                        let sc2_path = entry.path().join("Documents/StarCraft II/Accounts");
-                        let mut folders = find_all_multiplayer_folders(sc2_path, logger.clone());
-                        all_folders.append(&mut folders);
+    for prefix in linux_wine_prefixes(logger.clone()) {
+        if let Some(ref log) = logger {
+            log.debug(format!("Linux: scanning Wine prefix {}", prefix.display()));
+        }
+        all_folders.extend(scan_wine_prefix(&prefix, logger.clone()));
+    }
+
+    dedupe_by_canonical_path(all_folders)
+}
+
+/// Every Wine prefix root we know how to find on Linux: plain Wine, native
+/// and Flatpak Steam Proton, Lutris, and Flatpak Bottles. Each entry is a
+/// prefix *root* (the directory containing `drive_c`), not yet narrowed down
+/// to an SC2 install — [`scan_wine_prefix`] does that per prefix.
+#[cfg(target_os = "linux")]
+fn linux_wine_prefixes(logger: Option<Arc<DebugLogger>>) -> Vec<PathBuf> {
+    let mut prefixes = Vec::new();
+
+    let Some(home) = dirs::home_dir() else {
+        return prefixes;
+    };
+
+    // Plain Wine.
+    prefixes.push(home.join(".wine"));
+
+    // Native Steam Proton: ~/.steam/steam/steamapps/compatdata/<appid>/pfx
+    // and the XDG data-dir equivalent some distros use instead.
+    prefixes.extend(compatdata_prefixes(&home.join(".steam/steam/steamapps/compatdata")));
+    if let Some(data_home) = xdg_data_home() {
+        prefixes.extend(compatdata_prefixes(&data_home.join("Steam/steamapps/compatdata")));
+    }
+
+    // Flatpak Steam: same compatdata layout, sandboxed under ~/.var/app.
+    if let Some(flatpak_steam) = flatpak_app_dir("com.valvesoftware.Steam") {
+        prefixes.extend(compatdata_prefixes(&flatpak_steam.join(".local/share/Steam/steamapps/compatdata")));
+        prefixes.extend(compatdata_prefixes(&flatpak_steam.join(".steam/steam/steamapps/compatdata")));
+    }
+
+    // Lutris: per-game prefixes, either recorded in its YAML config or
+    // installed under the conventional ~/Games directory.
+    prefixes.extend(lutris_prefixes(logger.clone()));
+
+    // Flatpak Bottles: every bottle is itself a Wine prefix directory.
+    if let Some(bottles) = flatpak_app_dir("com.usebottles.bottles") {
+        let bottles_root = bottles.join("data/bottles/bottles");
+        if let Ok(entries) = fs::read_dir(&bottles_root) {
+            for entry in entries.flatten() {
+                if entry.file_type().ok().map(|ft| ft.is_dir()).unwrap_or(false) {
+                    prefixes.push(entry.path());
+                }
+            }
+        }
+    }
+
+    prefixes
+}
+
+/// Every `<compatdata_root>/*/pfx` directory that exists, for Proton's
+/// per-appid prefix layout.
+#[cfg(target_os = "linux")]
+fn compatdata_prefixes(compatdata_root: &Path) -> Vec<PathBuf> {
+    let mut prefixes = Vec::new();
+    if let Ok(entries) = fs::read_dir(compatdata_root) {
+        for entry in entries.flatten() {
+            if !entry.file_type().ok().map(|ft| ft.is_dir()).unwrap_or(false) {
+                continue;
+            }
+            let pfx = entry.path().join("pfx");
+            if pfx.exists() {
+                prefixes.push(pfx);
+            }
+        }
+    }
+    prefixes
+}
+
+/// Lutris Wine prefixes: read the `prefix:` field out of each per-game YAML
+/// config, and fall back to scanning `~/Games` (Lutris's conventional
+/// install directory) for anything that looks like a prefix.
+#[cfg(target_os = "linux")]
+fn lutris_prefixes(logger: Option<Arc<DebugLogger>>) -> Vec<PathBuf> {
+    let mut prefixes = Vec::new();
+
+    if let Some(config_home) = xdg_config_home() {
+        let games_dir = config_home.join("lutris/games");
+        if let Ok(entries) = fs::read_dir(&games_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("yml") {
+                    continue;
+                }
+                if let Ok(contents) = fs::read_to_string(&path) {
+                    if let Some(prefix) = extract_yaml_value(&contents, "prefix") {
+                        prefixes.push(PathBuf::from(prefix));
                     }
                 }
             }
         }
     }
 
+    if let Some(home) = dirs::home_dir() {
+        let games_root = home.join("Games");
+        if let Ok(entries) = fs::read_dir(&games_root) {
+            for entry in entries.flatten() {
+                if !entry.file_type().ok().map(|ft| ft.is_dir()).unwrap_or(false) {
+                    continue;
+                }
+                let candidate = entry.path();
+                if candidate.join("drive_c").exists() {
+                    prefixes.push(candidate);
+                }
+            }
+        }
+    }
+
+    if let Some(ref log) = logger {
+        log.debug(format!("Lutris: found {} candidate prefix(es)", prefixes.len()));
+    }
+
+    prefixes
+}
+
+/// Minimal `key: value` line extractor for the flat subset of YAML Lutris
+/// writes to its per-game config files — not a full YAML parser, just enough
+/// to pull out one scalar field without adding a parsing dependency for it.
+#[cfg(target_os = "linux")]
+fn extract_yaml_value(contents: &str, key: &str) -> Option<String> {
+    for line in contents.lines() {
+        let Some(rest) = line.trim().strip_prefix(key) else { continue };
+        let Some(value) = rest.trim_start().strip_prefix(':') else { continue };
+        let value = value.trim().trim_matches('"').trim_matches('\'');
+        if !value.is_empty() {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// `$XDG_CONFIG_HOME`, defaulting to `~/.config` per the base-directory spec.
+#[cfg(target_os = "linux")]
+fn xdg_config_home() -> Option<PathBuf> {
+    std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".config")))
+}
+
+/// `$XDG_DATA_HOME`, defaulting to `~/.local/share` per the base-directory spec.
+#[cfg(target_os = "linux")]
+fn xdg_data_home() -> Option<PathBuf> {
+    std::env::var("XDG_DATA_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local/share")))
+}
+
+/// A Flatpak app's private, sandboxed home under `~/.var/app/<app-id>`, which
+/// mirrors a normal `$HOME` (its own `.config`, `.local/share`, `.steam`, etc).
+#[cfg(target_os = "linux")]
+fn flatpak_app_dir(app_id: &str) -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".var/app").join(app_id))
+}
+
+/// Scan a single Wine prefix root (the directory containing `drive_c`) for
+/// every user's SC2 install, the same way the original `~/.wine`-only
+/// detection did.
+#[cfg(target_os = "linux")]
+fn scan_wine_prefix(prefix: &Path, logger: Option<Arc<DebugLogger>>) -> Vec<SC2ReplayFolder> {
+    let users_path = prefix.join("drive_c/users");
+    if !users_path.exists() {
+        return Vec::new();
+    }
+
+    let mut all_folders = Vec::new();
+    if let Ok(entries) = fs::read_dir(&users_path) {
+        for entry in entries.flatten() {
+            if entry.file_type().ok().map(|ft| ft.is_dir()).unwrap_or(false) {
+                let sc2_path = entry.path().join("Documents/StarCraft II/Accounts");
+                let mut folders = find_all_multiplayer_folders(sc2_path, logger.clone());
+                all_folders.append(&mut folders);
+            }
+        }
+    }
     all_folders
 }
 
+/// Collapse folders that resolve to the same real path (e.g. a Lutris prefix
+/// and a Steam compatdata prefix both pointing at the same install via
+/// symlinks), keeping the first occurrence of each.
+#[cfg(target_os = "linux")]
+fn dedupe_by_canonical_path(folders: Vec<SC2ReplayFolder>) -> Vec<SC2ReplayFolder> {
+    let mut seen = HashSet::new();
+    let mut deduped = Vec::new();
+    for folder in folders {
+        let key = fs::canonicalize(&folder.path).unwrap_or_else(|_| folder.path.clone());
+        if seen.insert(key) {
+            deduped.push(folder);
+        }
+    }
+    deduped
+}
+
 /// Find ALL Multiplayer replays folders in the Accounts directory
 fn find_all_multiplayer_folders(accounts_path: PathBuf, logger: Option<Arc<DebugLogger>>) -> Vec<SC2ReplayFolder> {
     if let Some(ref log) = logger {
@@ -171,6 +511,7 @@ fn find_all_multiplayer_folders(accounts_path: PathBuf, logger: Option<Arc<Debug
                         account_id: account_id.clone(),
                         region,
                         region_code: region_name.clone(),
+                        account_folder: parse_account_folder_name(&region_name),
                     });
                 }
             }
@@ -237,6 +578,23 @@ mod tests {
         assert_eq!(folder.region_code, "1-S2-1-123456");
     }
 
+    #[test]
+    fn test_folder_account_and_region_roundtrip() {
+        let temp_dir = create_fake_sc2_structure();
+        let accounts_path = temp_dir.path().join("Accounts");
+        let result = find_all_multiplayer_folders(accounts_path, None);
+        let folder = &result[0];
+
+        let (account_id, region) = folder_account_and_region(&folder.path).unwrap();
+        assert_eq!(account_id, folder.account_id);
+        assert_eq!(region, folder.region);
+    }
+
+    #[test]
+    fn test_folder_account_and_region_too_shallow() {
+        assert!(folder_account_and_region(Path::new("Multiplayer")).is_none());
+    }
+
     #[test]
     fn test_parse_region_from_folder() {
         assert_eq!(parse_region_from_folder("1-S2-1-123456"), "NA");
@@ -246,6 +604,87 @@ mod tests {
         assert_eq!(parse_region_from_folder("unknown-format"), "Unknown");
     }
 
+    #[test]
+    fn test_parse_account_folder_name_americas() {
+        let parsed = parse_account_folder_name("1-S2-1-802768").unwrap();
+        assert_eq!(parsed.gateway, 1);
+        assert_eq!(parsed.realm, 1);
+        assert_eq!(parsed.profile, 802768);
+        assert_eq!(parsed.region, Region::NorthAmerica);
+    }
+
+    #[test]
+    fn test_parse_account_folder_name_south_america_realm() {
+        let parsed = parse_account_folder_name("1-S2-2-802768").unwrap();
+        assert_eq!(parsed.region, Region::SouthAmerica);
+    }
+
+    #[test]
+    fn test_parse_account_folder_name_all_gateways() {
+        assert_eq!(parse_account_folder_name("2-S2-1-1").unwrap().region, Region::Europe);
+        assert_eq!(parse_account_folder_name("3-S2-1-1").unwrap().region, Region::Korea);
+        assert_eq!(parse_account_folder_name("3-S2-2-1").unwrap().region, Region::Taiwan);
+        assert_eq!(parse_account_folder_name("5-S2-1-1").unwrap().region, Region::China);
+        assert_eq!(parse_account_folder_name("98-S2-1-1").unwrap().region, Region::PublicTest);
+        assert_eq!(parse_account_folder_name("99-S2-1-1").unwrap().region, Region::PublicTest);
+        assert_eq!(parse_account_folder_name("9-S2-1-1").unwrap().region, Region::Unknown);
+    }
+
+    #[test]
+    fn test_parse_account_folder_name_malformed() {
+        assert!(parse_account_folder_name("unknown-format").is_none());
+        assert!(parse_account_folder_name("1-S2-1").is_none());
+    }
+
+    #[test]
+    fn test_list_replays_filters_and_sorts_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("old.SC2Replay"), b"old").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(temp_dir.path().join("new.SC2Replay"), b"new").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), b"not a replay").unwrap();
+
+        let folder = SC2ReplayFolder {
+            path: temp_dir.path().to_path_buf(),
+            account_id: "12345678".to_string(),
+            region: "NA".to_string(),
+            region_code: "1-S2-1-123456".to_string(),
+            account_folder: parse_account_folder_name("1-S2-1-123456"),
+        };
+
+        let replays = list_replays(&folder, None);
+        assert_eq!(replays.len(), 2);
+        assert_eq!(replays[0].path.file_name().unwrap(), "new.SC2Replay");
+        assert_eq!(replays[1].path.file_name().unwrap(), "old.SC2Replay");
+    }
+
+    #[test]
+    fn test_list_replays_custom_pattern() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("game1.SC2Replay"), b"one").unwrap();
+        fs::write(temp_dir.path().join("game2.SC2Replay"), b"two").unwrap();
+
+        let folder = SC2ReplayFolder {
+            path: temp_dir.path().to_path_buf(),
+            account_id: "12345678".to_string(),
+            region: "NA".to_string(),
+            region_code: "1-S2-1-123456".to_string(),
+            account_folder: parse_account_folder_name("1-S2-1-123456"),
+        };
+
+        let replays = list_replays(&folder, Some("game1.*"));
+        assert_eq!(replays.len(), 1);
+        assert_eq!(replays[0].path.file_name().unwrap(), "game1.SC2Replay");
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.SC2Replay", "game1.SC2Replay"));
+        assert!(!wildcard_match("*.SC2Replay", "game1.txt"));
+        assert!(wildcard_match("game?.SC2Replay", "game1.SC2Replay"));
+        assert!(!wildcard_match("game?.SC2Replay", "game12.SC2Replay"));
+    }
+
     #[test]
     fn test_find_all_multiplayer_folders_missing_accounts() {
         let temp_dir = TempDir::new().unwrap();
@@ -374,6 +813,7 @@ mod tests {
             account_id: "12345678".to_string(),
             region: "NA".to_string(),
             region_code: "1-S2-1-123456".to_string(),
+            account_folder: parse_account_folder_name("1-S2-1-123456"),
         };
 
         let cloned = folder.clone();
@@ -390,6 +830,7 @@ mod tests {
             account_id: "12345678".to_string(),
             region: "EU".to_string(),
             region_code: "2-S2-1-654321".to_string(),
+            account_folder: parse_account_folder_name("2-S2-1-654321"),
         };
 
         let serialized = serde_json::to_string(&folder).unwrap();
@@ -0,0 +1,111 @@
+//! Persisted cache of replay content hashes.
+//!
+//! [`ReplayTracker::calculate_hash`](crate::replay_tracker::ReplayTracker::calculate_hash)
+//! streams every byte of a replay through SHA-256, which gets expensive once
+//! a watched folder holds thousands of files that get rescanned on every
+//! cycle. This cache remembers the digest for a `(path, filesize,
+//! modified_time)` triple, so a replay that hasn't changed since the last
+//! scan never needs to be re-read. Any edit changes the size and/or mtime,
+//! which invalidates the entry automatically — there's no separate
+//! invalidation step to get wrong.
+
+use crate::config_utils::{load_config_file, save_config_file};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Filename used to persist the cache in the config directory.
+const CACHE_FILE: &str = "hash_cache.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HashCacheEntry {
+    filesize: u64,
+    modified_time: u64,
+    hash: String,
+}
+
+/// Path -> cached hash, keyed loosely: a lookup only returns a hit when the
+/// stored `filesize`/`modified_time` still match what's asked for.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HashCache {
+    entries: HashMap<String, HashCacheEntry>,
+}
+
+impl HashCache {
+    /// Load the cache from disk, starting empty if it doesn't exist yet.
+    pub async fn load() -> Result<Self, String> {
+        Ok(load_config_file(CACHE_FILE).await?.unwrap_or_default())
+    }
+
+    /// Persist the cache atomically.
+    pub async fn save(&self) -> Result<(), String> {
+        save_config_file(CACHE_FILE, self).await.map(|_| ())
+    }
+
+    /// Return the cached hash for `path` if its size and mtime still match
+    /// the recorded entry, `None` otherwise (including "never seen before").
+    pub fn get(&self, path: &Path, filesize: u64, modified_time: u64) -> Option<String> {
+        let entry = self.entries.get(&Self::key(path))?;
+        if entry.filesize == filesize && entry.modified_time == modified_time {
+            Some(entry.hash.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Record (or replace) the hash for `path` at this size/mtime.
+    pub fn insert(&mut self, path: &Path, filesize: u64, modified_time: u64, hash: String) {
+        self.entries.insert(
+            Self::key(path),
+            HashCacheEntry { filesize, modified_time, hash },
+        );
+    }
+
+    fn key(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+}
+
+/// Convert a [`SystemTime`] to Unix seconds, saturating to `0` if the clock
+/// is before the epoch, so it round-trips through the cache's serialized
+/// `u64` the same way [`crate::replay_tracker::TrackedReplay::uploaded_at`]
+/// does.
+pub fn modified_time_to_unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_get_misses_on_size_mismatch() {
+        let mut cache = HashCache::default();
+        let path = PathBuf::from("/tmp/a.SC2Replay");
+        cache.insert(&path, 100, 1000, "abc".to_string());
+        assert_eq!(cache.get(&path, 100, 1000), Some("abc".to_string()));
+        assert_eq!(cache.get(&path, 200, 1000), None);
+    }
+
+    #[test]
+    fn test_get_misses_on_mtime_mismatch() {
+        let mut cache = HashCache::default();
+        let path = PathBuf::from("/tmp/a.SC2Replay");
+        cache.insert(&path, 100, 1000, "abc".to_string());
+        assert_eq!(cache.get(&path, 100, 2000), None);
+    }
+
+    #[test]
+    fn test_insert_overwrites_previous_entry() {
+        let mut cache = HashCache::default();
+        let path = PathBuf::from("/tmp/a.SC2Replay");
+        cache.insert(&path, 100, 1000, "abc".to_string());
+        cache.insert(&path, 200, 2000, "def".to_string());
+        assert_eq!(cache.get(&path, 100, 1000), None);
+        assert_eq!(cache.get(&path, 200, 2000), Some("def".to_string()));
+    }
+}
@@ -0,0 +1,351 @@
+//! Tracked, resumable background job for a scan-and-upload cycle.
+//!
+//! Wraps [`ReplayScanner::scan_and_prepare_cancelable`] and
+//! [`JobStoreDrainer`] with a single [`JobReport`] that a caller can poll for
+//! progress, cancel mid-run via the existing
+//! [`AppStateManager::upload_cancelled`](crate::state::AppStateManager) flag,
+//! and resume after a restart: the report is persisted to `jobs.json` after
+//! every step, and any job left `Running` when the app last exited is loaded
+//! back as `Paused` rather than silently forgotten.
+//!
+//! The scan step itself (folder walk, player filtering, local dedup, server
+//! hash check) is a single call into `ReplayScanner` and cannot be observed
+//! mid-flight, so [`JobPhase::Scanning`] covers all of it; only the
+//! subsequent upload step reports real per-item progress.
+
+use crate::debug_logger::DebugLogger;
+use crate::replay_tracker::ReplayTracker;
+use crate::replay_uploader::ReplayUploader;
+use crate::services::replay_scanner::ReplayScanner;
+use crate::services::upload_job_store::{JobStoreDrainer, UploadJobStore};
+use crate::upload_manager::now_unix_secs;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Manager};
+
+/// Filename used to persist the current job's report in the config directory.
+const JOBS_FILE: &str = "jobs.json";
+
+/// Event carrying a [`JobReport`] update to the frontend.
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+
+/// Which step of the scan-and-upload cycle a job is currently in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPhase {
+    /// Scanning folders, filtering, hashing, and checking with the server.
+    Scanning,
+    /// Uploading prepared replays one at a time.
+    Uploading,
+}
+
+/// Lifecycle state of a tracked job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    /// Stopped by a cancellation request before finishing; resumable.
+    Paused,
+    Completed,
+    Failed { msg: String },
+}
+
+/// Snapshot of a tracked job's progress, suitable for polling from the
+/// frontend or persisting across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobReport {
+    pub id: String,
+    pub state: JobState,
+    pub phase: JobPhase,
+    pub items_done: usize,
+    pub items_total: usize,
+    pub started_at: u64,
+}
+
+/// What gets written to `jobs.json`: just the last known report. The durable
+/// per-replay work itself lives in [`UploadJobStore`], not here.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PersistedJob {
+    report: Option<JobReport>,
+}
+
+/// Tracks the currently (or most recently) running scan-and-upload job.
+pub struct JobManager {
+    logger: Arc<DebugLogger>,
+    report: Mutex<Option<JobReport>>,
+}
+
+impl JobManager {
+    /// Load the last persisted report, if any, demoting a dangling `Running`
+    /// left by a crash to `Paused` so it reads as resumable rather than
+    /// silently stuck "in progress" forever.
+    pub fn new(logger: Arc<DebugLogger>) -> Self {
+        let mut persisted = Self::load_sync(&logger);
+        if let Some(report) = persisted.report.as_mut() {
+            if report.state == JobState::Running {
+                report.state = JobState::Paused;
+            }
+        }
+        Self {
+            logger,
+            report: Mutex::new(persisted.report),
+        }
+    }
+
+    /// Synchronous `std::fs` read, since [`JobManager::new`] runs from
+    /// `AppStateManager::new()` before the async runtime is handling
+    /// commands. Mirrors the startup read in
+    /// [`UploadManager::new`](crate::upload_manager::UploadManager::new).
+    fn load_sync(logger: &DebugLogger) -> PersistedJob {
+        let path = match crate::config_utils::config_file_path(JOBS_FILE) {
+            Ok(path) => path,
+            Err(e) => {
+                logger.warn(format!("Could not resolve jobs file path: {}", e));
+                return PersistedJob::default();
+            }
+        };
+        Self::load_sync_from(&path, logger)
+    }
+
+    /// The actual read, taking an explicit path so tests can point it at a
+    /// scoped temp directory instead of the real (shared, possibly
+    /// already-populated) config directory.
+    fn load_sync_from(path: &std::path::Path, logger: &DebugLogger) -> PersistedJob {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                logger.warn(format!("Failed to parse {}: {}", JOBS_FILE, e));
+                PersistedJob::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PersistedJob::default(),
+            Err(e) => {
+                logger.warn(format!("Failed to read {}: {}", JOBS_FILE, e));
+                PersistedJob::default()
+            }
+        }
+    }
+
+    /// The most recently known report, if a job has ever run this install.
+    pub fn current_report(&self) -> Option<JobReport> {
+        self.report.lock().unwrap().clone()
+    }
+
+    /// Persist the current report atomically, logging (not failing) on error
+    /// since a missed persist only costs progress visibility, not correctness
+    /// — [`UploadJobStore`] is still the durable source of truth for work.
+    async fn persist(&self) {
+        let snapshot = PersistedJob {
+            report: self.report.lock().unwrap().clone(),
+        };
+        if let Err(e) = crate::config_utils::save_config_file(JOBS_FILE, &snapshot).await {
+            self.logger.warn(format!("Failed to persist {}: {}", JOBS_FILE, e));
+        }
+    }
+
+    fn set_report(&self, report: JobReport) {
+        *self.report.lock().unwrap() = Some(report);
+    }
+
+    fn emit(&self, app: &tauri::AppHandle) {
+        if let Some(report) = self.current_report() {
+            if let Err(e) = app.emit(JOB_PROGRESS_EVENT, &report) {
+                self.logger.warn(format!("Failed to emit {}: {}", JOB_PROGRESS_EVENT, e));
+            }
+        }
+    }
+
+    /// Run a full scan-and-upload cycle as a tracked, cancelable job.
+    ///
+    /// Cancellation reuses [`AppStateManager::upload_cancelled`]
+    /// (the same flag [`cancel_current_upload`] sets), so a caller can stop a
+    /// tracked job the same way it already stops a plain batch upload. The
+    /// flag is reset to `false` at the start of the run and checked between
+    /// the scan step and before every upload.
+    ///
+    /// [`AppStateManager::upload_cancelled`]: crate::state::AppStateManager::upload_cancelled
+    /// [`cancel_current_upload`]: crate::commands::queue::cancel_current_upload
+    pub async fn run_scan_job(
+        self: Arc<Self>,
+        app: tauri::AppHandle,
+        replay_folders: Vec<PathBuf>,
+        tracker: ReplayTracker,
+        uploader: Arc<ReplayUploader>,
+        player_names: Vec<String>,
+        limit: usize,
+        full_rescan: bool,
+    ) {
+        let app_state = app.state::<crate::state::AppStateManager>();
+        let cancelled = &app_state.upload_cancelled;
+        cancelled.store(false, Ordering::Relaxed);
+
+        let id = format!("job-{}", now_unix_secs());
+        self.set_report(JobReport {
+            id: id.clone(),
+            state: JobState::Running,
+            phase: JobPhase::Scanning,
+            items_done: 0,
+            items_total: 0,
+            started_at: now_unix_secs(),
+        });
+        self.persist().await;
+        self.emit(&app);
+
+        let scanner = ReplayScanner::new(replay_folders, Arc::clone(&self.logger));
+        let scan_result = match scanner
+            .scan_and_prepare_cancelable(&tracker, &uploader, player_names, limit, full_rescan, cancelled)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                self.logger.error(format!("Scan job {} failed: {}", id, e));
+                self.set_report(JobReport {
+                    id,
+                    state: JobState::Failed { msg: e },
+                    phase: JobPhase::Scanning,
+                    items_done: 0,
+                    items_total: 0,
+                    started_at: now_unix_secs(),
+                });
+                self.persist().await;
+                self.emit(&app);
+                return;
+            }
+        };
+
+        let drainer = JobStoreDrainer::new(Arc::clone(&uploader), Arc::clone(&self.logger));
+        if let Err(e) = drainer.enqueue_prepared(&scan_result.prepared_replays).await {
+            self.logger.warn(format!("Failed to enqueue scan job {} results: {}", id, e));
+        }
+
+        let items_total = UploadJobStore::load()
+            .await
+            .map(|store| store.jobs.len())
+            .unwrap_or(scan_result.prepared_replays.len());
+
+        if cancelled.load(Ordering::Relaxed) {
+            self.set_report(JobReport {
+                id,
+                state: JobState::Paused,
+                phase: JobPhase::Uploading,
+                items_done: 0,
+                items_total,
+                started_at: now_unix_secs(),
+            });
+            self.persist().await;
+            self.emit(&app);
+            return;
+        }
+
+        self.set_report(JobReport {
+            id: id.clone(),
+            state: JobState::Running,
+            phase: JobPhase::Uploading,
+            items_done: 0,
+            items_total,
+            started_at: now_unix_secs(),
+        });
+        self.persist().await;
+        self.emit(&app);
+
+        let mut items_done = 0usize;
+        let drain_result = drainer
+            .drain_with_progress(cancelled, |_job, _succeeded| {
+                items_done += 1;
+                if let Some(mut report) = self.current_report() {
+                    report.items_done = items_done;
+                    self.set_report(report);
+                }
+                self.emit(&app);
+            })
+            .await;
+
+        let final_state = match drain_result {
+            Ok(_) if cancelled.load(Ordering::Relaxed) => JobState::Paused,
+            Ok(_) => JobState::Completed,
+            Err(e) => {
+                self.logger.error(format!("Scan job {} failed during upload: {}", id, e));
+                JobState::Failed { msg: e }
+            }
+        };
+        self.set_report(JobReport {
+            id,
+            state: final_state,
+            phase: JobPhase::Uploading,
+            items_done,
+            items_total,
+            started_at: now_unix_secs(),
+        });
+        self.persist().await;
+        self.emit(&app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger() -> Arc<DebugLogger> {
+        Arc::new(DebugLogger::new())
+    }
+
+    #[test]
+    fn test_new_has_no_report_when_nothing_persisted() {
+        // Points `load_sync_from` at a scoped temp directory rather than the
+        // real (shared, possibly already-populated) config directory, so the
+        // "nothing persisted yet" case is actually exercised instead of
+        // depending on ambient state.
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("jobs.json");
+        let persisted = JobManager::load_sync_from(&path, &DebugLogger::new());
+        assert!(persisted.report.is_none());
+    }
+
+    #[test]
+    fn test_current_report_initially_none_when_constructed_directly() {
+        let manager = JobManager {
+            logger: logger(),
+            report: Mutex::new(None),
+        };
+        assert!(manager.current_report().is_none());
+    }
+
+    #[test]
+    fn test_set_report_is_visible_via_current_report() {
+        let manager = JobManager {
+            logger: logger(),
+            report: Mutex::new(None),
+        };
+        manager.set_report(JobReport {
+            id: "job-1".to_string(),
+            state: JobState::Running,
+            phase: JobPhase::Scanning,
+            items_done: 0,
+            items_total: 0,
+            started_at: 0,
+        });
+        let report = manager.current_report().expect("report should be set");
+        assert_eq!(report.id, "job-1");
+        assert_eq!(report.state, JobState::Running);
+    }
+
+    #[test]
+    fn test_running_report_demoted_to_paused_on_load() {
+        let mut persisted = PersistedJob {
+            report: Some(JobReport {
+                id: "job-stale".to_string(),
+                state: JobState::Running,
+                phase: JobPhase::Uploading,
+                items_done: 1,
+                items_total: 3,
+                started_at: 0,
+            }),
+        };
+        if let Some(report) = persisted.report.as_mut() {
+            if report.state == JobState::Running {
+                report.state = JobState::Paused;
+            }
+        }
+        assert_eq!(persisted.report.unwrap().state, JobState::Paused);
+    }
+}
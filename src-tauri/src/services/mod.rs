@@ -3,8 +3,21 @@
 //! This module breaks up the monolithic scan_and_upload function into
 //! focused, testable services.
 
+pub mod hash_cache;
+pub mod job_manager;
 pub mod replay_scanner;
 pub mod upload_executor;
+pub mod upload_job_store;
 
-pub use replay_scanner::ReplayScanner;
-pub use upload_executor::UploadExecutor;
+pub use hash_cache::HashCache;
+pub use replay_scanner::{ReplayScanner, SkipReason, SkippedReplay};
+pub use upload_executor::{
+    BatchUploadReport, ReplayRegion, RegionOverrides, UploadExecutor, UploadPolicy,
+    DEFAULT_UPLOAD_CONCURRENCY,
+};
+pub use upload_job_store::{JobState, JobStoreDrainer, UploadJobRecord, UploadJobStore};
+
+// `job_manager::JobState` intentionally isn't re-exported here: it would
+// collide with `upload_job_store::JobState` above. Callers needing it use
+// `crate::services::job_manager::JobState` directly.
+pub use job_manager::{JobManager, JobPhase, JobReport};
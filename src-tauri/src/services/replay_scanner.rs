@@ -3,14 +3,19 @@
 //! Handles scanning replay folders, filtering, hashing, and preparing
 //! replays for upload. Extracted from the monolithic scan_and_upload function.
 
-use crate::replay_tracker::{ReplayTracker, ReplayFileInfo, scan_replay_folder};
+use crate::replay_tracker::{ReplayTracker, ReplayFileInfo, replay_file_info_for_path, scan_replay_folder_since};
 use crate::replay_uploader::{ReplayUploader, HashInfo};
 use crate::replay_parser;
 use crate::debug_logger::DebugLogger;
+use crate::services::hash_cache::{modified_time_to_unix_secs, HashCache};
 use crate::upload_manager::detect_user_player_names;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
 /// A replay that has been scanned, filtered, and is ready for upload
 #[derive(Debug, Clone)]
@@ -21,6 +26,34 @@ pub struct PreparedReplay {
     pub player_name: String,
 }
 
+/// Why a candidate replay (or a whole folder) was left out of the upload
+/// batch, surfaced to the frontend instead of only going to the debug log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", content = "detail", rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Couldn't parse the replay file itself (corrupt, unsupported version).
+    ParseError(String),
+    /// Not a competitive game type (e.g. a custom/arcade map).
+    NotCompetitive(String),
+    /// None of the configured player names were an active (non-observer)
+    /// player in this game.
+    PlayerNotActive,
+    /// Already present in the local tracker.
+    LocalDuplicate,
+    /// Server already has this replay's hash.
+    ServerDuplicate,
+    /// A configured replay folder couldn't be read at all.
+    FolderUnreadable(String),
+}
+
+/// A replay (or folder) that was seen during the scan but didn't make it into
+/// `prepared_replays`, with the reason why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedReplay {
+    pub filename: String,
+    pub reason: SkipReason,
+}
+
 /// Result of scanning replay folders
 #[derive(Debug)]
 pub struct ScanResult {
@@ -32,6 +65,8 @@ pub struct ScanResult {
     pub local_duplicate_count: usize,
     /// Replays already on server
     pub server_duplicate_count: usize,
+    /// Every replay (and unreadable folder) seen but not uploaded, with why.
+    pub skipped: Vec<SkippedReplay>,
 }
 
 /// Service for scanning and preparing replays for upload
@@ -57,15 +92,45 @@ impl ReplayScanner {
     /// 4. Local tracker deduplication
     /// 5. Hash computation
     /// 6. Server deduplication
+    ///
+    /// `full_rescan` forces every configured folder to be walked in full,
+    /// ignoring each folder's recorded `last_scan_time` — use this for the
+    /// first scan, or after the local tracker has been reset, so replays
+    /// written before the last recorded scan aren't skipped.
     pub async fn scan_and_prepare(
         &self,
         tracker: &ReplayTracker,
         uploader: &ReplayUploader,
         player_names: Vec<String>,
         limit: usize,
+        full_rescan: bool,
     ) -> Result<ScanResult, String> {
-        // Step 1: Scan all folders for replays
-        let all_replays = self.scan_all_folders()?;
+        self.scan_and_prepare_cancelable(tracker, uploader, player_names, limit, full_rescan, &AtomicBool::new(false)).await
+    }
+
+    /// Same as [`Self::scan_and_prepare`], but `cancelled` is checked between
+    /// every replay in the filter step (not during the parallel hashing of the
+    /// replays that pass it) so a job tracking this scan can be
+    /// stopped without waiting for the whole (potentially large) folder to be
+    /// processed. A cancellation mid-filter returns whatever was filtered so
+    /// far rather than an error, since partial progress is still useful.
+    pub async fn scan_and_prepare_cancelable(
+        &self,
+        tracker: &ReplayTracker,
+        uploader: &ReplayUploader,
+        player_names: Vec<String>,
+        limit: usize,
+        full_rescan: bool,
+        cancelled: &AtomicBool,
+    ) -> Result<ScanResult, String> {
+        // Step 1: Scan all folders for replays, in parallel, short-circuiting
+        // files untouched since each folder's last recorded scan.
+        let mut app_config = crate::app_config::load_app_config(&self.logger).await;
+        let (all_replays, skipped, new_scan_times) = self.scan_all_folders(full_rescan, &app_config.folder_scan_times);
+        app_config.folder_scan_times.extend(new_scan_times);
+        if let Err(e) = crate::app_config::save_app_config(&app_config).await {
+            self.logger.warn(format!("Failed to persist folder scan times: {}", e));
+        }
         let total_found = all_replays.len();
 
         if all_replays.is_empty() {
@@ -75,6 +140,7 @@ impl ReplayScanner {
                 total_found: 0,
                 local_duplicate_count: 0,
                 server_duplicate_count: 0,
+                skipped,
             });
         }
 
@@ -87,19 +153,105 @@ impl ReplayScanner {
             total_found
         ));
 
-        // Step 2: Detect player names if not provided
+        self.filter_hash_and_check(recent_replays, total_found, skipped, tracker, uploader, player_names, limit, cancelled).await
+    }
+
+    /// Like [`Self::scan_and_prepare`], but scoped to a specific set of paths
+    /// instead of rescanning every configured folder — for the folder watcher,
+    /// which already knows exactly which file just settled and shouldn't pay
+    /// for a full walk of every replay folder just to upload one new game.
+    pub async fn scan_and_prepare_for_paths(
+        &self,
+        tracker: &ReplayTracker,
+        uploader: &ReplayUploader,
+        player_names: Vec<String>,
+        paths: Vec<PathBuf>,
+    ) -> Result<ScanResult, String> {
+        let mut skipped = Vec::new();
+        let replays: Vec<ReplayFileInfo> = paths
+            .iter()
+            .filter_map(|path| match replay_file_info_for_path(path) {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    self.logger.warn(format!("Could not read {} ({}), skipping", path.display(), e));
+                    skipped.push(SkippedReplay {
+                        filename: path.display().to_string(),
+                        reason: SkipReason::ParseError(e),
+                    });
+                    None
+                }
+            })
+            .collect();
+        let total_found = replays.len();
+
+        if replays.is_empty() {
+            return Ok(ScanResult {
+                prepared_replays: Vec::new(),
+                total_found: 0,
+                local_duplicate_count: 0,
+                server_duplicate_count: 0,
+                skipped,
+            });
+        }
+
+        let limit = replays.len();
+        self.filter_hash_and_check(
+            replays,
+            total_found,
+            skipped,
+            tracker,
+            uploader,
+            player_names,
+            limit,
+            &AtomicBool::new(false),
+        ).await
+    }
+
+    /// Shared tail of [`Self::scan_and_prepare_cancelable`] and
+    /// [`Self::scan_and_prepare_for_paths`]: detect players if needed, filter
+    /// and hash the given replays against the cache, check the survivors with
+    /// the server, and build the final prepared list (capped at `limit`).
+    /// `skipped` carries any entries already recorded by the caller (e.g.
+    /// unreadable folders) and is appended to as filtering/hashing/server
+    /// checks rule more replays out.
+    async fn filter_hash_and_check(
+        &self,
+        replays: Vec<ReplayFileInfo>,
+        total_found: usize,
+        mut skipped: Vec<SkippedReplay>,
+        tracker: &ReplayTracker,
+        uploader: &ReplayUploader,
+        player_names: Vec<String>,
+        limit: usize,
+        cancelled: &AtomicBool,
+    ) -> Result<ScanResult, String> {
+        // Detect player names if not provided
         let player_names = if player_names.is_empty() {
-            self.detect_players_from_replays(&recent_replays)
+            self.detect_players_from_replays(&replays)
         } else {
             player_names
         };
 
-        // Step 3: Filter and compute hashes
+        // Filter and compute hashes, reusing cached digests for replays whose
+        // size/mtime haven't changed since the last scan.
+        let hash_cache = HashCache::load().await.unwrap_or_else(|e| {
+            self.logger.warn(format!("Failed to load hash cache, starting empty: {}", e));
+            HashCache::default()
+        });
+        let hash_cache = Mutex::new(hash_cache);
         let filter_result = self.filter_and_hash_replays(
-            recent_replays,
+            replays,
             tracker,
             &player_names,
+            cancelled,
+            &hash_cache,
         )?;
+        if let Ok(cache) = hash_cache.into_inner() {
+            if let Err(e) = cache.save().await {
+                self.logger.warn(format!("Failed to persist hash cache: {}", e));
+            }
+        }
+        skipped.extend(filter_result.skipped);
 
         if filter_result.hash_infos.is_empty() {
             self.logger.info("All replays already uploaded (per local tracker)".to_string());
@@ -108,11 +260,16 @@ impl ReplayScanner {
                 total_found,
                 local_duplicate_count: filter_result.local_duplicate_count,
                 server_duplicate_count: 0,
+                skipped,
             });
         }
 
-        // Step 4: Check with server for new hashes
+        // Check with server for new hashes
         self.logger.info(format!("Checking {} hashes with server...", filter_result.hash_infos.len()));
+        let submitted: HashMap<String, String> = filter_result.hash_infos
+            .iter()
+            .map(|info| (info.hash.clone(), info.filename.clone()))
+            .collect();
         let check_result = uploader.check_hashes(filter_result.hash_infos).await?;
 
         self.logger.info(format!(
@@ -121,7 +278,17 @@ impl ReplayScanner {
             check_result.existing_count
         ));
 
-        // Step 5: Build prepared replays list (limited)
+        let new_hash_set: HashSet<&String> = check_result.new_hashes.iter().collect();
+        for (hash, filename) in &submitted {
+            if !new_hash_set.contains(hash) {
+                skipped.push(SkippedReplay {
+                    filename: filename.clone(),
+                    reason: SkipReason::ServerDuplicate,
+                });
+            }
+        }
+
+        // Build prepared replays list (limited)
         let prepared_replays: Vec<PreparedReplay> = check_result
             .new_hashes
             .into_iter()
@@ -143,34 +310,79 @@ impl ReplayScanner {
             total_found,
             local_duplicate_count: filter_result.local_duplicate_count,
             server_duplicate_count: check_result.existing_count,
+            skipped,
         })
     }
 
-    /// Scan all configured replay folders
-    fn scan_all_folders(&self) -> Result<Vec<ReplayFileInfo>, String> {
-        let mut all_replays = Vec::new();
-
-        for folder in &self.replay_folders {
-            match scan_replay_folder(folder) {
-                Ok(replays) => {
-                    self.logger.debug(format!(
-                        "Found {} replays in {}",
-                        replays.len(),
-                        folder.display()
-                    ));
-                    all_replays.extend(replays);
-                }
-                Err(e) => {
-                    self.logger.warn(format!(
-                        "Error scanning {}: {}",
-                        folder.display(),
-                        e
-                    ));
+    /// Scan all configured replay folders concurrently (one `rayon` task per
+    /// folder), returning the replays found, a [`SkippedReplay`] for every
+    /// folder that couldn't be read, and this pass's `last_scan_time` for
+    /// each folder successfully scanned (to persist in [`crate::app_config`]).
+    ///
+    /// Unless `full_rescan` is set, each folder's previously recorded
+    /// timestamp in `scan_times` bounds the walk: files not modified since are
+    /// skipped without being re-filtered or re-hashed, so a steady-state scan
+    /// over folders with years of history only costs a `read_dir` plus one
+    /// `metadata` call per untouched file.
+    fn scan_all_folders(
+        &self,
+        full_rescan: bool,
+        scan_times: &HashMap<String, u64>,
+    ) -> (Vec<ReplayFileInfo>, Vec<SkippedReplay>, HashMap<String, u64>) {
+        let scan_started_at = modified_time_to_unix_secs(SystemTime::now());
+
+        let results: Vec<(Vec<ReplayFileInfo>, Option<SkippedReplay>, Option<(String, u64)>)> = self
+            .replay_folders
+            .par_iter()
+            .map(|folder| {
+                let key = folder.to_string_lossy().to_string();
+                let since = if full_rescan {
+                    None
+                } else {
+                    scan_times.get(&key).map(|secs| {
+                        std::time::UNIX_EPOCH + std::time::Duration::from_secs(*secs)
+                    })
+                };
+
+                match scan_replay_folder_since(folder, since) {
+                    Ok(replays) => {
+                        self.logger.debug(format!(
+                            "Found {} replays in {}",
+                            replays.len(),
+                            folder.display()
+                        ));
+                        (replays, None, Some((key, scan_started_at)))
+                    }
+                    Err(e) => {
+                        self.logger.warn(format!(
+                            "Error scanning {}: {}",
+                            folder.display(),
+                            e
+                        ));
+                        let skipped = SkippedReplay {
+                            filename: folder.display().to_string(),
+                            reason: SkipReason::FolderUnreadable(e),
+                        };
+                        (Vec::new(), Some(skipped), None)
+                    }
                 }
+            })
+            .collect();
+
+        let mut all_replays = Vec::new();
+        let mut skipped = Vec::new();
+        let mut new_scan_times = HashMap::new();
+        for (replays, skip, scan_time) in results {
+            all_replays.extend(replays);
+            if let Some(skip) = skip {
+                skipped.push(skip);
+            }
+            if let Some((key, time)) = scan_time {
+                new_scan_times.insert(key, time);
             }
         }
 
-        Ok(all_replays)
+        (all_replays, skipped, new_scan_times)
     }
 
     /// Get most recent replays sorted by modified time
@@ -190,9 +402,9 @@ impl ReplayScanner {
         let mut replay_player_data = Vec::new();
         for replay_info in replays {
             if let Ok(players) = replay_parser::get_players(&replay_info.path) {
-                let player_list: Vec<(String, bool)> = players
+                let player_list: Vec<(String, String, bool)> = players
                     .iter()
-                    .map(|p| (p.name.clone(), p.is_observer))
+                    .map(|p| (p.handle.clone(), p.name.clone(), p.is_observer))
                     .collect();
                 replay_player_data.push((
                     replay_info.path.to_string_lossy().to_string(),
@@ -201,7 +413,10 @@ impl ReplayScanner {
             }
         }
 
-        let detected_names = detect_user_player_names(&replay_player_data);
+        let detected_names: Vec<String> = detect_user_player_names(&replay_player_data)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
         if !detected_names.is_empty() {
             self.logger.info(format!(
                 "Detected {} player name(s): {}",
@@ -214,20 +429,38 @@ impl ReplayScanner {
         detected_names
     }
 
-    /// Filter replays and compute hashes
+    /// Filter replays and compute hashes.
+    ///
+    /// Game-type and player filtering (cheap, and able to skip a replay
+    /// without ever hashing it) run first, serially, so cancellation and the
+    /// early `continue`s keep working exactly as before. Only the survivors
+    /// reach the expensive step — content hashing — which runs in parallel
+    /// over `rayon`'s thread pool, consulting and updating `hash_cache` so a
+    /// replay whose size and mtime haven't changed since the last scan never
+    /// gets re-read from disk. `cancelled` is only checked during the
+    /// serial filter pass; once hashing starts, the batch runs to completion.
     fn filter_and_hash_replays(
         &self,
         replays: Vec<ReplayFileInfo>,
         tracker: &ReplayTracker,
         player_names: &[String],
+        cancelled: &AtomicBool,
+        hash_cache: &Mutex<HashCache>,
     ) -> Result<FilterResult, String> {
         let mut hash_infos = Vec::new();
         let mut replay_map: HashMap<String, (ReplayFileInfo, String, String)> = HashMap::new();
         let mut non_competitive_count = 0;
         let mut observer_game_count = 0;
         let mut local_duplicate_count = 0;
+        let mut candidates = Vec::new();
+        let mut skipped = Vec::new();
 
         for replay_info in replays {
+            if cancelled.load(Ordering::Relaxed) {
+                self.logger.info("Filter/hash step cancelled, returning partial results".to_string());
+                break;
+            }
+
             // Filter 1: Game type (only competitive games)
             let game_type = match replay_parser::get_game_type(&replay_info.path) {
                 Ok(gtype) => gtype,
@@ -236,6 +469,10 @@ impl ReplayScanner {
                         "Could not parse {} ({}), skipping",
                         replay_info.filename, e
                     ));
+                    skipped.push(SkippedReplay {
+                        filename: replay_info.filename.clone(),
+                        reason: SkipReason::ParseError(e),
+                    });
                     continue;
                 }
             };
@@ -247,6 +484,10 @@ impl ReplayScanner {
                     replay_info.filename,
                     game_type.as_str()
                 ));
+                skipped.push(SkippedReplay {
+                    filename: replay_info.filename.clone(),
+                    reason: SkipReason::NotCompetitive(game_type.as_str().to_string()),
+                });
                 continue;
             }
 
@@ -258,6 +499,10 @@ impl ReplayScanner {
                         "Could not extract players from {} ({}), skipping",
                         replay_info.filename, e
                     ));
+                    skipped.push(SkippedReplay {
+                        filename: replay_info.filename.clone(),
+                        reason: SkipReason::ParseError(e),
+                    });
                     continue;
                 }
             };
@@ -271,6 +516,10 @@ impl ReplayScanner {
                         "Skipping {} (player not active in game)",
                         replay_info.filename
                     ));
+                    skipped.push(SkippedReplay {
+                        filename: replay_info.filename.clone(),
+                        reason: SkipReason::PlayerNotActive,
+                    });
                     continue;
                 }
             };
@@ -282,32 +531,85 @@ impl ReplayScanner {
                     "Skipping {} (in local tracker by metadata)",
                     replay_info.filename
                 ));
+                skipped.push(SkippedReplay {
+                    filename: replay_info.filename.clone(),
+                    reason: SkipReason::LocalDuplicate,
+                });
                 continue;
             }
 
-            // Compute hash
-            let hash = ReplayTracker::calculate_hash(&replay_info.path)?;
+            candidates.push((replay_info, game_type.as_str().to_string(), player_name_in_replay));
+        }
 
-            // Filter 4: Local tracker by hash
-            if tracker.is_uploaded(&hash) {
-                local_duplicate_count += 1;
-                self.logger.debug(format!(
-                    "Skipping {} (in local tracker by hash)",
-                    replay_info.filename
-                ));
-                continue;
-            }
+        // Filter 4 (by hash) and the hash computation itself run together,
+        // in parallel, now that the cheap filters have narrowed the set down.
+        let hash_duplicate_count = AtomicUsize::new(0);
+        let parallel_skipped = Mutex::new(Vec::new());
+        let hashed: Vec<Option<(String, ReplayFileInfo, String, String)>> = candidates
+            .into_par_iter()
+            .map(|(replay_info, game_type, player_name)| {
+                let modified_time = modified_time_to_unix_secs(replay_info.modified_time);
+                let cached = hash_cache
+                    .lock()
+                    .ok()
+                    .and_then(|cache| cache.get(&replay_info.path, replay_info.filesize, modified_time));
+
+                let hash = match cached {
+                    Some(hash) => hash,
+                    None => match Self::hash_replay(&replay_info.path) {
+                        Ok(hash) => {
+                            if let Ok(mut cache) = hash_cache.lock() {
+                                cache.insert(&replay_info.path, replay_info.filesize, modified_time, hash.clone());
+                            }
+                            hash
+                        }
+                        Err(e) => {
+                            self.logger.warn(format!(
+                                "Could not hash {} ({}), skipping",
+                                replay_info.filename, e
+                            ));
+                            if let Ok(mut skipped) = parallel_skipped.lock() {
+                                skipped.push(SkippedReplay {
+                                    filename: replay_info.filename.clone(),
+                                    reason: SkipReason::ParseError(e),
+                                });
+                            }
+                            return None;
+                        }
+                    },
+                };
+
+                if tracker.is_uploaded(&hash) {
+                    hash_duplicate_count.fetch_add(1, Ordering::Relaxed);
+                    self.logger.debug(format!(
+                        "Skipping {} (in local tracker by hash)",
+                        replay_info.filename
+                    ));
+                    if let Ok(mut skipped) = parallel_skipped.lock() {
+                        skipped.push(SkippedReplay {
+                            filename: replay_info.filename.clone(),
+                            reason: SkipReason::LocalDuplicate,
+                        });
+                    }
+                    return None;
+                }
 
+                Some((hash, replay_info, game_type, player_name))
+            })
+            .collect();
+
+        local_duplicate_count += hash_duplicate_count.load(Ordering::Relaxed);
+        if let Ok(parallel_skipped) = parallel_skipped.into_inner() {
+            skipped.extend(parallel_skipped);
+        }
+
+        for (hash, replay_info, game_type, player_name) in hashed.into_iter().flatten() {
             hash_infos.push(HashInfo {
                 hash: hash.clone(),
                 filename: replay_info.filename.clone(),
                 filesize: replay_info.filesize,
             });
-
-            replay_map.insert(
-                hash,
-                (replay_info, game_type.as_str().to_string(), player_name_in_replay),
-            );
+            replay_map.insert(hash, (replay_info, game_type, player_name));
         }
 
         // Log filter stats
@@ -324,6 +626,28 @@ impl ReplayScanner {
             hash_infos,
             replay_map,
             local_duplicate_count,
+            skipped,
+        })
+    }
+
+    /// Stream a replay file through SHA-256 to produce its content hash.
+    ///
+    /// Delegates to [`ReplayTracker::calculate_hash`], which reads the file in
+    /// buffered chunks so huge replays are never loaded into memory whole.
+    /// Sending these hashes to `check_hashes` first turns a full re-upload into
+    /// an incremental sync: only files the server hasn't seen are read again
+    /// and multipart-uploaded.
+    pub fn hash_replay(path: &std::path::Path) -> Result<String, String> {
+        ReplayTracker::calculate_hash(path)
+    }
+
+    /// Build a [`HashInfo`] entry for a scanned replay without reading the whole
+    /// file into memory.
+    pub fn hash_info_for(info: &ReplayFileInfo) -> Result<HashInfo, String> {
+        Ok(HashInfo {
+            hash: Self::hash_replay(&info.path)?,
+            filename: info.filename.clone(),
+            filesize: info.filesize,
         })
     }
 
@@ -347,6 +671,7 @@ struct FilterResult {
     hash_infos: Vec<HashInfo>,
     replay_map: HashMap<String, (ReplayFileInfo, String, String)>,
     local_duplicate_count: usize,
+    skipped: Vec<SkippedReplay>,
 }
 
 #[cfg(test)]
@@ -367,8 +692,10 @@ mod tests {
         let logger = Arc::new(DebugLogger::new());
         let scanner = ReplayScanner::new(vec![temp_dir.path().to_path_buf()], logger);
 
-        let result = scanner.scan_all_folders().unwrap();
-        assert!(result.is_empty());
+        let (replays, skipped, scan_times) = scanner.scan_all_folders(false, &HashMap::new());
+        assert!(replays.is_empty());
+        assert!(skipped.is_empty());
+        assert_eq!(scan_times.len(), 1);
     }
 
     #[test]
@@ -380,8 +707,32 @@ mod tests {
         let logger = Arc::new(DebugLogger::new());
         let scanner = ReplayScanner::new(vec![temp_dir.path().to_path_buf()], logger);
 
-        let result = scanner.scan_all_folders().unwrap();
-        assert_eq!(result.len(), 2);
+        let (replays, skipped, scan_times) = scanner.scan_all_folders(false, &HashMap::new());
+        assert_eq!(replays.len(), 2);
+        assert!(skipped.is_empty());
+        assert_eq!(scan_times.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_all_folders_since_skips_unchanged_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_replay(temp_dir.path(), "old.SC2Replay", b"replay1");
+
+        let logger = Arc::new(DebugLogger::new());
+        let scanner = ReplayScanner::new(vec![temp_dir.path().to_path_buf()], logger);
+
+        // A scan_time from the future means every existing file looks
+        // untouched since, so it should be skipped.
+        let mut scan_times = HashMap::new();
+        let key = temp_dir.path().to_string_lossy().to_string();
+        let far_future = modified_time_to_unix_secs(SystemTime::now()) + 3600;
+        scan_times.insert(key, far_future);
+
+        let (replays, _skipped, _new_scan_times) = scanner.scan_all_folders(false, &scan_times);
+        assert!(replays.is_empty(), "unchanged file should be skipped when scanning since a future timestamp");
+
+        let (replays, _skipped, _new_scan_times) = scanner.scan_all_folders(true, &scan_times);
+        assert_eq!(replays.len(), 1, "full_rescan should ignore the recorded scan time");
     }
 
     #[test]
@@ -429,4 +780,28 @@ mod tests {
         let recent = scanner.get_recent_replays(replays, 3);
         assert_eq!(recent.len(), 3);
     }
+
+    #[test]
+    fn test_filter_and_hash_replays_stops_when_already_cancelled() {
+        let temp_dir = TempDir::new().unwrap();
+        let replay_path = create_test_replay(temp_dir.path(), "test1.SC2Replay", b"replay1");
+        let logger = Arc::new(DebugLogger::new());
+        let scanner = ReplayScanner::new(vec![temp_dir.path().to_path_buf()], logger);
+        let tracker = ReplayTracker::default();
+
+        let replays = vec![ReplayFileInfo {
+            path: replay_path,
+            filename: "test1.SC2Replay".to_string(),
+            filesize: 7,
+            modified_time: std::time::SystemTime::UNIX_EPOCH,
+        }];
+
+        let cancelled = AtomicBool::new(true);
+        let hash_cache = Mutex::new(HashCache::default());
+        let result = scanner
+            .filter_and_hash_replays(replays, &tracker, &[], &cancelled, &hash_cache)
+            .unwrap();
+
+        assert!(result.hash_infos.is_empty(), "a pre-cancelled scan should skip all replays");
+    }
 }
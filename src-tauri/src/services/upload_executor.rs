@@ -3,21 +3,190 @@
 //! Handles the actual upload of prepared replays with progress tracking,
 //! grouping by game type/player, and event emission.
 
+use crate::api_contracts::StoredReplay;
 use crate::replay_tracker::{ReplayTracker, ReplayFileInfo, TrackedReplay};
 use crate::replay_uploader::ReplayUploader;
 use crate::debug_logger::DebugLogger;
-use crate::upload_manager::{group_replays_by_type_and_player, UploadStatus, UploadManagerState};
+use crate::upload_manager::{group_replays_by_type_and_player, GroupingKey, ReplayGroupingEntry, UploadStatus, UploadManagerState};
+use crate::upload_queue::{emit_item_status, emit_queue_changed, ItemStatus, JobMetadata, UploadJob, UploadQueue};
 use crate::services::replay_scanner::PreparedReplay;
-use std::collections::HashMap;
+use crate::state::AppStateManager;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::Emitter;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Default number of uploads allowed in flight at once for the parallel batch
+/// mode. Serial latency-bound syncs of hundreds of files benefit most from a
+/// handful of concurrent requests; going much higher mostly stresses the
+/// server without improving wall-clock time.
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
+/// Maximum number of attempts (including the first) for a single replay
+/// upload within [`UploadExecutor::execute`], before it's handed to the
+/// durable retry queue for later.
+const MAX_UPLOAD_ATTEMPTS: usize = 4;
+
+/// Backoff between attempts in milliseconds; the delay caps at the last
+/// entry if `MAX_UPLOAD_ATTEMPTS` ever grows past this list's length.
+const RETRY_BACKOFF_MS: &[u64] = &[500, 1000, 2000];
+
+/// How many upload failures in a row `execute` tolerates before abandoning
+/// the rest of the batch, to avoid hammering a server that's down.
+const MAX_CONSECUTIVE_FAILURES: usize = 5;
+
+/// How often the stall-detection poll timer checks an in-flight upload.
+const STALL_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a single upload attempt may run before it's considered stalled
+/// and an `upload-stalled` event fires, mirroring pict-rs' `WithPollTimer`.
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Controls what [`UploadExecutor::execute`] does when a replay still fails
+/// after exhausting its retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UploadPolicy {
+    /// Stop the whole batch at the first replay that still fails.
+    FailFast,
+    /// Record the failure and keep going with the rest of the batch.
+    #[default]
+    ContinueOnError,
+}
+
+/// True for errors that indicate the replay is already on the server - these
+/// are treated as an immediate success rather than a failure to retry.
+fn is_duplicate_upload_error(error: &str) -> bool {
+    error.contains("409") || error.contains("REPLAY_DUPLICATE") || error.contains("already been uploaded")
+}
+
+/// True for non-409 4xx-shaped errors, which won't succeed no matter how many
+/// times they're retried (bad request, auth, not found, ...), so retrying
+/// them would just waste time.
+fn is_permanent_upload_error(error: &str) -> bool {
+    const PERMANENT_MARKERS: &[&str] = &["400", "401", "403", "404", "422"];
+    PERMANENT_MARKERS.iter().any(|marker| error.contains(marker))
+}
+
+/// Stable string code for a [`crate::replay_parser::UploadValidationError`],
+/// used in the `upload-error` event so the frontend can branch on the
+/// failure kind instead of matching the display message.
+fn upload_validation_error_code(error: &crate::replay_parser::UploadValidationError) -> &'static str {
+    use crate::replay_parser::UploadValidationError;
+    match error {
+        UploadValidationError::Empty => "Empty",
+        UploadValidationError::TooLarge { .. } => "TooLarge",
+        UploadValidationError::InvalidMagic => "InvalidMagic",
+        UploadValidationError::HashMismatch { .. } => "HashMismatch",
+    }
+}
+
+/// Outcome of a bounded-parallel batch upload. Results are aggregated per file
+/// so one failing replay never aborts the rest of the batch.
+#[derive(Debug, Default)]
+pub struct BatchUploadReport {
+    /// Replays that uploaded successfully, in completion order.
+    pub succeeded: Vec<StoredReplay>,
+    /// `(replay path, error)` pairs for replays that failed.
+    pub failed: Vec<(String, String)>,
+}
+
+impl BatchUploadReport {
+    /// Total number of files processed (succeeded + failed).
+    pub fn total(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+}
+
+/// Number of recent uploads kept for the rolling-throughput ETA estimate.
+const THROUGHPUT_WINDOW: usize = 10;
+
+/// Rolling estimate of upload throughput, used to turn the remaining byte count
+/// into a human-facing "time left". Only successful transfers are recorded so a
+/// run of failures doesn't poison the estimate.
+#[derive(Debug, Default)]
+struct ThroughputTracker {
+    /// Recent `(bytes, seconds)` samples, newest at the back.
+    samples: VecDeque<(u64, f64)>,
+}
+
+impl ThroughputTracker {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one completed upload of `bytes` that took `secs`.
+    fn record(&mut self, bytes: u64, secs: f64) {
+        if bytes == 0 || !secs.is_finite() || secs <= 0.0 {
+            return;
+        }
+        self.samples.push_back((bytes, secs));
+        while self.samples.len() > THROUGHPUT_WINDOW {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Bytes per second averaged over the window, or `None` until we have data.
+    fn bytes_per_sec(&self) -> Option<f64> {
+        let (bytes, secs) = self
+            .samples
+            .iter()
+            .fold((0u64, 0.0), |(b, s), (bytes, secs)| (b + bytes, s + secs));
+        if secs > 0.0 {
+            Some(bytes as f64 / secs)
+        } else {
+            None
+        }
+    }
+
+    /// Estimated seconds to transfer `remaining_bytes` at the current rate.
+    fn eta_seconds(&self, remaining_bytes: u64) -> Option<u64> {
+        self.bytes_per_sec()
+            .filter(|bps| *bps > 0.0)
+            .map(|bps| (remaining_bytes as f64 / bps).round() as u64)
+    }
+}
+
+/// Percentage of `done` out of `total`, clamped to `0..=100`.
+fn percent_of(done: u64, total: u64) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        (done as f64 / total as f64 * 100.0).clamp(0.0, 100.0)
+    }
+}
 
 /// Result of executing uploads
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct UploadResult {
     /// Number of replays successfully uploaded
     pub uploaded_count: usize,
+    /// `(hash, file path)` for every replay uploaded successfully this call,
+    /// for callers that need to persist per-replay outcomes (e.g.
+    /// [`crate::db::ReplayDb::record_upload`]) rather than just the count.
+    pub succeeded: Vec<(String, String)>,
+    /// Number of replays that failed (after retries) and were enqueued for a
+    /// later retry on the durable queue.
+    pub enqueued_count: usize,
+    /// `(hash, filename, error)` for every replay that failed after
+    /// exhausting retries.
+    pub failed: Vec<(String, String, String)>,
+    /// Replays never attempted because the batch stopped early, either due
+    /// to [`UploadPolicy::FailFast`] or [`MAX_CONSECUTIVE_FAILURES`].
+    pub skipped: usize,
+}
+
+/// Outcome of attempting to upload one replay, after retries, within a batch.
+enum UploadAttemptOutcome {
+    /// Uploaded (or already present on the server).
+    Uploaded,
+    /// Every attempt failed; the error has been enqueued on the durable
+    /// retry queue rather than the replay being dropped.
+    Failed(String),
 }
 
 /// Service for executing replay uploads
@@ -45,28 +214,39 @@ impl UploadExecutor {
 
     /// Execute uploads for prepared replays
     ///
-    /// Groups replays by (game_type, player_name) and uploads in batches,
-    /// emitting progress events along the way.
+    /// Groups replays by (game_type, player_name) and uploads each group with
+    /// up to `concurrency` replays in flight at once (see
+    /// [`DEFAULT_UPLOAD_CONCURRENCY`]), emitting progress events along the
+    /// way. Each replay is retried with backoff (see [`MAX_UPLOAD_ATTEMPTS`])
+    /// before being handed to the durable retry queue; once
+    /// [`MAX_CONSECUTIVE_FAILURES`] failures are seen, or `policy` is
+    /// [`UploadPolicy::FailFast`] and any replay fails, uploads still in
+    /// flight are allowed to finish but no new ones are started. A final
+    /// `upload-batch-summary` event reports the uploaded/failed/skipped
+    /// counts either way.
     pub async fn execute(
         &self,
         prepared_replays: Vec<PreparedReplay>,
+        policy: UploadPolicy,
+        concurrency: usize,
         app: &tauri::AppHandle,
     ) -> Result<UploadResult, String> {
+        let concurrency = concurrency.max(1);
         if prepared_replays.is_empty() {
-            return Ok(UploadResult {
-                uploaded_count: 0,
-            });
+            return Ok(UploadResult::default());
         }
 
         // Build hash list and replay maps for grouping
         let hashes: Vec<String> = prepared_replays.iter().map(|r| r.hash.clone()).collect();
 
-        // Map for group_replays_by_type_and_player (needs tuple format)
-        let tuple_map: HashMap<String, (ReplayFileInfo, String, String)> = prepared_replays
+        // Map for group_replays_by_type_and_player (needs tuple format). Matchup
+        // and MMR bucket aren't tracked on `PreparedReplay` yet, so grouping
+        // stays at the plain (game_type, player_name) granularity.
+        let tuple_map: HashMap<String, ReplayGroupingEntry> = prepared_replays
             .iter()
             .map(|r| (
                 r.hash.clone(),
-                (r.file_info.clone(), r.game_type.clone(), r.player_name.clone()),
+                (r.file_info.clone(), r.game_type.clone(), r.player_name.clone(), None, None),
             ))
             .collect();
 
@@ -77,7 +257,7 @@ impl UploadExecutor {
             .collect();
 
         // Group by (game_type, player_name)
-        let groups = group_replays_by_type_and_player(&hashes, &tuple_map);
+        let groups = group_replays_by_type_and_player(&hashes, &tuple_map, GroupingKey::TypeAndPlayer);
         let total_count = prepared_replays.len();
 
         self.logger.info(format!(
@@ -89,11 +269,30 @@ impl UploadExecutor {
         // Update pending count
         self.update_pending_count(total_count);
 
+        let region_overrides = self.load_region_overrides().await;
+
+        // Byte totals drive the live progress bar and the ETA estimate.
+        // `bytes_processed` is read from the concurrent upload futures below
+        // (for the per-file "upload-progress" event) and written from the
+        // sequential result loop, so it's atomic; `tracker` is only written
+        // from the sequential loop (the futures just read the current rate
+        // from it), so a plain mutex-guarded value is enough.
+        let batch_bytes_total: u64 = prepared_replays.iter().map(|r| r.file_info.filesize).sum();
+        let bytes_processed = std::sync::atomic::AtomicU64::new(0);
+        let tracker = Mutex::new(ThroughputTracker::new());
+        self.update_batch_bytes(batch_bytes_total, 0);
+
         let mut uploaded_count = 0;
-        let mut global_index = 0;
+        let mut succeeded: Vec<(String, String)> = Vec::new();
+        let mut enqueued_count = 0;
+        let global_index = AtomicUsize::new(0);
+        let mut failed: Vec<(String, String, String)> = Vec::new();
+        let consecutive_failures = AtomicUsize::new(0);
+        let group_aborted = AtomicBool::new(false);
+        let mut aborted = false;
 
         // Upload each group
-        for group in groups {
+        'groups: for group in groups {
             self.logger.info(format!(
                 "Uploading {} {} replays for {}...",
                 group.hashes.len(),
@@ -110,37 +309,166 @@ impl UploadExecutor {
                 self.logger.warn(format!("Failed to emit upload-batch-start: {}", e));
             }
 
-            for hash in &group.hashes {
-                let prepared = match replay_map.get(hash) {
-                    Some(p) => p,
-                    None => {
-                        self.logger.warn(format!("Hash {} not found in replay map, skipping", hash));
-                        continue;
+            // Consume-and-clear any pending cancellation once per group so it
+            // never leaks into the next `execute` call; a cancellation mid-group
+            // is honored the same way as `group_aborted` below - replays already
+            // in flight are left to finish, but no new ones are started.
+            let cancelled = app.state::<AppStateManager>().upload_cancelled.swap(false, Ordering::Relaxed);
+            group_aborted.store(cancelled, Ordering::Relaxed);
+            if cancelled {
+                self.logger.info("Upload batch cancelled by user".to_string());
+                aborted = true;
+            }
+
+            // Spawn up to `concurrency` uploads at once via `buffer_unordered`;
+            // each future checks `group_aborted` right before it would start a
+            // network request, so a mid-group abort only stops *new* uploads.
+            // Bind everything the futures close over by reference up front so
+            // `async move` captures copies of the references rather than
+            // trying (and failing) to move the shared, non-`Copy` state.
+            let group = &group;
+            let replay_map = &replay_map;
+            let region_overrides = &region_overrides;
+            let bytes_processed = &bytes_processed;
+            let tracker = &tracker;
+            let global_index = &global_index;
+            let group_aborted = &group_aborted;
+
+            let mut uploads = stream::iter(group.hashes.iter().cloned())
+                .map(|hash| async move {
+                    if app.state::<AppStateManager>().upload_cancelled.swap(false, Ordering::Relaxed) {
+                        self.logger.info("Upload batch cancelled by user".to_string());
+                        group_aborted.store(true, Ordering::Relaxed);
+                    }
+                    if group_aborted.load(Ordering::Relaxed) {
+                        return None;
+                    }
+
+                    let prepared = match replay_map.get(&hash) {
+                        Some(p) => *p,
+                        None => {
+                            self.logger.warn(format!("Hash {} not found in replay map, skipping", hash));
+                            return None;
+                        }
+                    };
+
+                    let index = global_index.fetch_add(1, Ordering::Relaxed) + 1;
+                    let file_bytes = prepared.file_info.filesize;
+
+                    // Per-file progress at the start of the transfer: bytes_sent
+                    // is zero, and the ETA reflects everything still outstanding.
+                    let processed_so_far = bytes_processed.load(Ordering::Relaxed);
+                    let remaining = batch_bytes_total.saturating_sub(processed_so_far);
+                    let (bytes_per_sec, eta_seconds) = tracker
+                        .lock()
+                        .map(|t| (t.bytes_per_sec(), t.eta_seconds(remaining)))
+                        .unwrap_or((None, None));
+                    if let Err(e) = app.emit("upload-progress", serde_json::json!({
+                        "path": prepared.file_info.path.to_string_lossy(),
+                        "filename": prepared.file_info.filename,
+                        "game_type": group.game_type,
+                        "player_name": group.player_name,
+                        "index": index,
+                        "total": total_count,
+                        "bytes_sent": 0,
+                        "bytes_total": file_bytes,
+                        "percent": percent_of(processed_so_far, batch_bytes_total),
+                        "bytes_per_sec": bytes_per_sec,
+                        "eta_seconds": eta_seconds,
+                    })) {
+                        self.logger.warn(format!("Failed to emit upload-progress: {}", e));
                     }
+
+                    let started = Instant::now();
+                    let outcome = self.upload_single_replay(
+                        prepared,
+                        &hash,
+                        index,
+                        total_count,
+                        &group.game_type,
+                        &group.player_name,
+                        region_overrides,
+                        app,
+                    ).await;
+
+                    Some((hash, prepared, file_bytes, started, outcome))
+                })
+                .buffer_unordered(concurrency);
+
+            while let Some(item) = uploads.next().await {
+                let (hash, prepared, file_bytes, started, outcome) = match item {
+                    Some(v) => v,
+                    None => continue,
                 };
 
-                global_index += 1;
-
-                match self.upload_single_replay(
-                    prepared,
-                    hash,
-                    global_index,
-                    total_count,
-                    &group.game_type,
-                    &group.player_name,
-                    app,
-                ).await {
-                    Ok(()) => {
+                match &outcome {
+                    // Uploaded successfully this pass.
+                    Ok(UploadAttemptOutcome::Uploaded) => {
                         uploaded_count += 1;
+                        succeeded.push((hash.clone(), prepared.file_info.path.to_string_lossy().to_string()));
+                        consecutive_failures.store(0, Ordering::Relaxed);
+                        if let Ok(mut t) = tracker.lock() {
+                            t.record(file_bytes, started.elapsed().as_secs_f64());
+                        }
                     }
-                    Err(e) => {
-                        // Return error on first failure (current behavior)
-                        // Could be changed to continue on failure in the future
-                        return Err(e);
+                    // Every retry was exhausted: enqueued for a later retry rather
+                    // than dropped, but still counted towards this batch's report.
+                    Ok(UploadAttemptOutcome::Failed(error)) => {
+                        enqueued_count += 1;
+                        let failures = consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+                        failed.push((hash.clone(), prepared.file_info.filename.clone(), error.clone()));
+
+                        if failures >= MAX_CONSECUTIVE_FAILURES {
+                            self.logger.error(format!(
+                                "Aborting batch after {} consecutive upload failures",
+                                failures
+                            ));
+                            group_aborted.store(true, Ordering::Relaxed);
+                            aborted = true;
+                        } else if policy == UploadPolicy::FailFast {
+                            group_aborted.store(true, Ordering::Relaxed);
+                            aborted = true;
+                        }
                     }
+                    // A local error (e.g. poisoned mutex) is genuinely fatal.
+                    Err(_) => {}
+                }
+
+                // Advance the bar regardless of the per-file result so a failed
+                // upload doesn't freeze the batch, then emit the rollup + ETA.
+                let processed = bytes_processed.fetch_add(file_bytes, Ordering::Relaxed) + file_bytes;
+                self.update_batch_bytes(batch_bytes_total, processed);
+                let remaining = batch_bytes_total.saturating_sub(processed);
+                let (bytes_per_sec, eta_seconds) = tracker
+                    .lock()
+                    .map(|t| (t.bytes_per_sec(), t.eta_seconds(remaining)))
+                    .unwrap_or((None, None));
+                if let Err(e) = app.emit("batch-progress", serde_json::json!({
+                    "completed": global_index.load(Ordering::Relaxed),
+                    "total": total_count,
+                    "bytes_done": processed,
+                    "bytes_total": batch_bytes_total,
+                    "percent": percent_of(processed, batch_bytes_total),
+                    "bytes_per_sec": bytes_per_sec,
+                    "eta_seconds": eta_seconds,
+                })) {
+                    self.logger.warn(format!("Failed to emit batch-progress: {}", e));
+                }
+
+                if let Err(e) = outcome {
+                    return Err(e);
                 }
             }
 
+            // A cancellation picked up by one of the futures above.
+            if group_aborted.load(Ordering::Relaxed) {
+                aborted = true;
+            }
+
+            if aborted {
+                break 'groups;
+            }
+
             // Emit batch complete
             if let Err(e) = app.emit("upload-batch-complete", serde_json::json!({
                 "game_type": group.game_type,
@@ -154,16 +482,148 @@ impl UploadExecutor {
         // Clear current upload status
         self.clear_current_upload();
 
+        let skipped = total_count.saturating_sub(global_index.load(Ordering::Relaxed));
+
         self.logger.info(format!(
-            "Upload execution complete: {} uploaded",
-            uploaded_count
+            "Upload execution complete: {} uploaded, {} failed, {} skipped",
+            uploaded_count,
+            failed.len(),
+            skipped
         ));
 
+        if let Err(e) = app.emit("upload-batch-summary", serde_json::json!({
+            "uploaded_count": uploaded_count,
+            "failed_count": failed.len(),
+            "skipped": skipped,
+        })) {
+            self.logger.warn(format!("Failed to emit upload-batch-summary: {}", e));
+        }
+
         Ok(UploadResult {
             uploaded_count,
+            succeeded,
+            enqueued_count,
+            failed,
+            skipped,
         })
     }
 
+    /// Upload a batch of prepared replays in parallel, capping the number of
+    /// simultaneous in-flight requests with a semaphore-guarded worker pool.
+    ///
+    /// Each upload future acquires a permit from a [`tokio::sync::Semaphore`]
+    /// (sized by `concurrency`, see [`DEFAULT_UPLOAD_CONCURRENCY`]) before
+    /// hitting the network, so at most `concurrency` requests run at once. The
+    /// futures are driven on a [`JoinSet`] and their `Result<StoredReplay,_>`
+    /// outcomes are collected into a [`BatchUploadReport`]; a single failure
+    /// (or panic) is recorded without aborting the others. Successful uploads
+    /// are folded back into the tracker serially after they complete, keeping
+    /// the parallelism on the network side where the latency actually is.
+    pub async fn execute_batch_parallel(
+        &self,
+        prepared_replays: Vec<PreparedReplay>,
+        concurrency: usize,
+        app: &tauri::AppHandle,
+    ) -> BatchUploadReport {
+        let concurrency = concurrency.max(1);
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let region_overrides = Arc::new(self.load_region_overrides().await);
+        let mut join_set: JoinSet<Result<(PreparedReplay, StoredReplay), (PreparedReplay, String)>> =
+            JoinSet::new();
+
+        self.logger.info(format!(
+            "Uploading {} replay(s) with concurrency {}",
+            prepared_replays.len(),
+            concurrency
+        ));
+
+        for prepared in prepared_replays {
+            let semaphore = Arc::clone(&semaphore);
+            let uploader = Arc::clone(&self.uploader);
+            let logger = Arc::clone(&self.logger);
+            let app = app.clone();
+            let region_overrides = Arc::clone(&region_overrides);
+
+            join_set.spawn(async move {
+                // Hold the permit for the whole upload so in-flight requests
+                // stay bounded; it is released when this future completes.
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("upload semaphore is never closed");
+
+                let path = prepared.file_info.path.clone();
+                let region = extract_replay_region(&path, &region_overrides).map(|(r, _)| r);
+                emit_item_status(Some(&app), &logger, &prepared.hash, &path, ItemStatus::Uploading, None);
+
+                match uploader
+                    .upload_replay(
+                        &path,
+                        Some(&prepared.player_name),
+                        None,
+                        Some(&prepared.game_type),
+                        region.map(|r| r.region_code),
+                        region.map(|r| r.realm),
+                    )
+                    .await
+                {
+                    Ok(stored) => {
+                        emit_item_status(Some(&app), &logger, &prepared.hash, &path, ItemStatus::Done, None);
+                        Ok((prepared, stored))
+                    }
+                    Err(e) => {
+                        emit_item_status(Some(&app), &logger, &prepared.hash, &path, ItemStatus::Failed, Some(&e));
+                        Err((prepared, e))
+                    }
+                }
+            });
+        }
+
+        let mut report = BatchUploadReport::default();
+        while let Some(joined) = join_set.join_next().await {
+            match joined {
+                Ok(Ok((prepared, stored))) => {
+                    // Record success serially so the tracker's single mutex is
+                    // never the bottleneck during the parallel network phase.
+                    if let Err(e) = self.handle_upload_success(&prepared, &prepared.hash) {
+                        self.logger.warn(format!(
+                            "Uploaded {} but failed to update tracker: {}",
+                            prepared.file_info.filename, e
+                        ));
+                    }
+                    report.succeeded.push(stored);
+                }
+                Ok(Err((prepared, e))) => {
+                    report
+                        .failed
+                        .push((prepared.file_info.path.to_string_lossy().to_string(), e));
+                }
+                Err(join_err) => {
+                    self.logger.warn(format!("Upload task failed to join: {}", join_err));
+                }
+            }
+        }
+
+        self.clear_current_upload();
+        self.logger.info(format!(
+            "Parallel batch upload complete: {} succeeded, {} failed",
+            report.succeeded.len(),
+            report.failed.len()
+        ));
+        report
+    }
+
+    /// Load the user's region-override table from the app config, converting
+    /// the on-disk `String` keys to `PathBuf`s for [`extract_replay_region`].
+    async fn load_region_overrides(&self) -> RegionOverrides {
+        crate::app_config::load_app_config(&self.logger)
+            .await
+            .region_overrides
+            .into_iter()
+            .map(|(path, code)| (PathBuf::from(path), code))
+            .collect()
+    }
+
     /// Update pending count in state
     fn update_pending_count(&self, count: usize) {
         if let Ok(mut state) = self.state.lock() {
@@ -173,6 +633,17 @@ impl UploadExecutor {
         }
     }
 
+    /// Update the batch's total/uploaded byte counts, so a global progress bar
+    /// can reflect data transferred rather than just replay count.
+    fn update_batch_bytes(&self, total: u64, uploaded: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            state.bytes_total = total;
+            state.bytes_uploaded = uploaded;
+        } else {
+            self.logger.error("Failed to lock state for byte progress update".to_string());
+        }
+    }
+
     /// Clear current upload status
     fn clear_current_upload(&self) {
         if let Ok(mut state) = self.state.lock() {
@@ -182,7 +653,14 @@ impl UploadExecutor {
         }
     }
 
-    /// Upload a single replay with progress tracking
+    /// Upload a single replay with progress tracking, retrying within this
+    /// call before giving up (see [`Self::upload_replay_with_retry`]).
+    ///
+    /// Returns `Ok(UploadAttemptOutcome::Uploaded)` when the replay uploaded
+    /// (or was already on the server), and `Ok(UploadAttemptOutcome::Failed)`
+    /// once every retry is exhausted and the replay has been enqueued on the
+    /// durable queue for a later retry instead of being dropped. `Err` is
+    /// reserved for genuinely fatal local errors.
     #[allow(clippy::too_many_arguments)]
     async fn upload_single_replay(
         &self,
@@ -192,8 +670,9 @@ impl UploadExecutor {
         total: usize,
         game_type: &str,
         player_name: &str,
+        region_overrides: &RegionOverrides,
         app: &tauri::AppHandle,
-    ) -> Result<(), String> {
+    ) -> Result<UploadAttemptOutcome, String> {
         self.logger.info(format!(
             "[{}/{}] Uploading {} ({} for {})...",
             index, total, prepared.file_info.filename, game_type, player_name
@@ -204,52 +683,210 @@ impl UploadExecutor {
             filename: prepared.file_info.filename.clone(),
         });
 
-        // Emit progress event
-        if let Err(e) = app.emit("upload-progress", serde_json::json!({
-            "current": index,
-            "total": total,
-            "filename": prepared.file_info.filename,
-            "game_type": game_type,
-            "player_name": player_name
-        })) {
-            self.logger.warn(format!("Failed to emit upload-progress: {}", e));
-        }
+        // Per-item queue transition for the live queue view.
+        emit_item_status(
+            Some(app),
+            &self.logger,
+            hash,
+            &prepared.file_info.path,
+            ItemStatus::Uploading,
+            None,
+        );
+
+        // The richer per-file `upload-progress` / `batch-progress` stream is
+        // emitted by the caller in `execute`, which owns the byte totals and
+        // the rolling-throughput ETA tracker.
 
-        // Extract region from path
-        let region = extract_region_from_path(&prepared.file_info.path);
+        // Extract region/realm from the path (or a configured override).
+        let region = extract_replay_region(&prepared.file_info.path, region_overrides).map(|(r, _)| r);
+        let region_code = region.map(|r| r.region_code);
+        let realm = region.map(|r| r.realm);
 
-        // Perform upload
-        match self.uploader.upload_replay(
+        // Cheap local gate before spending a network request: catches files
+        // that changed or went missing between scan and upload, as well as
+        // anything that was never a real replay to begin with.
+        if let Err(validation_err) = crate::replay_parser::validate_replay_for_upload(
             &prepared.file_info.path,
-            Some(player_name),
-            None, // target_build_id
-            Some(game_type),
-            region.as_deref(),
+            Some(&prepared.hash),
+            crate::replay_parser::DEFAULT_MAX_REPLAY_BYTES,
+        ) {
+            let code = upload_validation_error_code(&validation_err);
+            let error = validation_err.to_string();
+            self.handle_upload_failure(&prepared.file_info.filename, &error, Some(code), app);
+            self.enqueue_for_retry(
+                prepared, hash, game_type, player_name,
+                region_code.map(String::from), realm, &error, app,
+            ).await;
+            emit_item_status(Some(app), &self.logger, hash, &prepared.file_info.path, ItemStatus::Queued, Some(&error));
+            return Ok(UploadAttemptOutcome::Failed(error));
+        }
+
+        match self.upload_replay_with_retry(
+            &prepared.file_info.path,
+            player_name,
+            game_type,
+            region_code,
+            realm,
+            &prepared.file_info.filename,
+            app,
         ).await {
-            Ok(_) => {
+            Ok(()) => {
+                // Covers both a genuine upload and a 409/duplicate short-circuit -
+                // either way the local tracker should mark it as handled.
                 self.handle_upload_success(prepared, hash)?;
                 self.logger.info(format!("Successfully uploaded {}", prepared.file_info.filename));
-                Ok(())
+                emit_item_status(Some(app), &self.logger, hash, &prepared.file_info.path, ItemStatus::Done, None);
+                Ok(UploadAttemptOutcome::Uploaded)
             }
             Err(e) => {
-                // Check if this is a 409 Conflict (duplicate) - treat as success
-                // This can happen in race conditions where the same replay is uploaded twice
-                if e.contains("409") || e.contains("REPLAY_DUPLICATE") || e.contains("already been uploaded") {
-                    self.logger.info(format!(
-                        "Replay {} already exists on server (treating as success)",
-                        prepared.file_info.filename
-                    ));
-                    // Still mark as success in local tracker to prevent re-upload attempts
-                    self.handle_upload_success(prepared, hash)?;
-                    Ok(())
-                } else {
-                    self.handle_upload_failure(&prepared.file_info.filename, &e, app);
-                    Err(format!("Failed to upload {}: {}", prepared.file_info.filename, e))
+                // Every retry (or the permanent-error check) failed: surface it,
+                // then enqueue for retry so the replay survives a restart
+                // instead of being dropped.
+                self.handle_upload_failure(&prepared.file_info.filename, &e, None, app);
+                self.enqueue_for_retry(
+                    prepared, hash, game_type, player_name,
+                    region_code.map(String::from), realm, &e, app,
+                ).await;
+                emit_item_status(Some(app), &self.logger, hash, &prepared.file_info.path, ItemStatus::Queued, Some(&e));
+                Ok(UploadAttemptOutcome::Failed(e))
+            }
+        }
+    }
+
+    /// Upload one replay, retrying transient failures up to
+    /// [`MAX_UPLOAD_ATTEMPTS`] times with backoff from [`RETRY_BACKOFF_MS`].
+    /// Mirrors the Bazel BEP uploader's "retry until N consecutive errors"
+    /// approach: a 409/duplicate response short-circuits to success without
+    /// retrying, and a non-409 4xx error short-circuits to failure without
+    /// retrying either, since neither would behave differently on a retry.
+    /// Returns the last transient error once every attempt is exhausted.
+    #[allow(clippy::too_many_arguments)]
+    async fn upload_replay_with_retry(
+        &self,
+        path: &std::path::Path,
+        player_name: &str,
+        game_type: &str,
+        region: Option<&str>,
+        realm: Option<u8>,
+        filename: &str,
+        app: &tauri::AppHandle,
+    ) -> Result<(), String> {
+        let mut last_error = String::new();
+
+        for attempt in 0..MAX_UPLOAD_ATTEMPTS {
+            let upload = self.uploader.upload_replay(path, Some(player_name), None, Some(game_type), region, realm);
+            match self.with_stall_detection(filename, app, upload).await {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    if is_duplicate_upload_error(&e) {
+                        self.logger.info(format!(
+                            "Replay {} already exists on server (treating as success)",
+                            filename
+                        ));
+                        return Ok(());
+                    }
+                    if is_permanent_upload_error(&e) {
+                        return Err(e);
+                    }
+
+                    last_error = e;
+                    let is_last_attempt = attempt + 1 == MAX_UPLOAD_ATTEMPTS;
+                    if !is_last_attempt {
+                        let delay_ms = RETRY_BACKOFF_MS
+                            .get(attempt)
+                            .copied()
+                            .unwrap_or_else(|| *RETRY_BACKOFF_MS.last().unwrap());
+                        self.logger.warn(format!(
+                            "Upload of {} failed ({}), retrying in {}ms",
+                            filename, last_error, delay_ms
+                        ));
+                        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                    }
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Poll `fut` to completion, emitting `upload-stalled` once if it hasn't
+    /// finished after [`STALL_TIMEOUT`]. Modeled on pict-rs' `WithPollTimer`:
+    /// the uploader has no mid-transfer progress hook, so "stalled" here means
+    /// "still running past the timeout" rather than "zero bytes moved", but
+    /// that's enough to warn the user a transfer may be stuck.
+    async fn with_stall_detection<F, T>(&self, filename: &str, app: &tauri::AppHandle, fut: F) -> T
+    where
+        F: std::future::Future<Output = T>,
+    {
+        tokio::pin!(fut);
+        let started = Instant::now();
+        let mut warned = false;
+
+        loop {
+            tokio::select! {
+                result = &mut fut => return result,
+                _ = tokio::time::sleep(STALL_POLL_INTERVAL) => {
+                    if !warned && started.elapsed() >= STALL_TIMEOUT {
+                        warned = true;
+                        let elapsed_secs = started.elapsed().as_secs();
+                        self.logger.warn(format!(
+                            "Upload of {} has been running for {}s with no result, may be stalled",
+                            filename, elapsed_secs
+                        ));
+                        if let Err(e) = app.emit("upload-stalled", serde_json::json!({
+                            "filename": filename,
+                            "elapsed_secs": elapsed_secs,
+                        })) {
+                            self.logger.warn(format!("Failed to emit upload-stalled: {}", e));
+                        }
+                    }
                 }
             }
         }
     }
 
+    /// Persist a failed replay onto the durable queue so it is retried on the
+    /// next drain (and after a restart). De-duplication by hash is handled by
+    /// [`UploadQueue::enqueue`], so the same replay is never queued twice.
+    #[allow(clippy::too_many_arguments)]
+    async fn enqueue_for_retry(
+        &self,
+        prepared: &PreparedReplay,
+        hash: &str,
+        game_type: &str,
+        player_name: &str,
+        region: Option<String>,
+        realm: Option<u8>,
+        error: &str,
+        app: &tauri::AppHandle,
+    ) {
+        let mut queue = match UploadQueue::load().await {
+            Ok(q) => q,
+            Err(e) => {
+                self.logger.error(format!("Failed to load upload queue to enqueue {}: {}", hash, e));
+                return;
+            }
+        };
+
+        let metadata = JobMetadata {
+            player_name: Some(player_name.to_string()),
+            game_type: Some(game_type.to_string()),
+            region,
+            realm,
+        };
+        let mut job = UploadJob::new(hash.to_string(), prepared.file_info.path.clone(), metadata);
+        job.last_error = Some(error.to_string());
+
+        if queue.enqueue(job) {
+            if let Err(e) = queue.save().await {
+                self.logger.error(format!("Failed to persist queued upload {}: {}", hash, e));
+            } else {
+                self.logger.info(format!("Enqueued {} for retry after failure", prepared.file_info.filename));
+                emit_queue_changed(Some(app), &self.logger, &queue);
+            }
+        }
+    }
+
     /// Handle successful upload - update tracker and state
     fn handle_upload_success(&self, prepared: &PreparedReplay, hash: &str) -> Result<(), String> {
         let tracked_replay = TrackedReplay {
@@ -287,8 +924,13 @@ impl UploadExecutor {
         Ok(())
     }
 
-    /// Handle failed upload - update state and emit error event
-    fn handle_upload_failure(&self, filename: &str, error: &str, app: &tauri::AppHandle) {
+    /// Handle failed upload - update state and emit error event.
+    ///
+    /// `code` is `Some` for a failure classified by [`validate_replay_for_upload`]
+    /// before the network call (e.g. `"InvalidMagic"`), and `None` for a
+    /// failure surfaced by the upload itself, which only has a server-provided
+    /// message to go on.
+    fn handle_upload_failure(&self, filename: &str, error: &str, code: Option<&'static str>, app: &tauri::AppHandle) {
         if let Ok(mut state) = self.state.lock() {
             state.current_upload = Some(UploadStatus::Failed {
                 filename: filename.to_string(),
@@ -302,7 +944,8 @@ impl UploadExecutor {
         // Emit error event so frontend can display it
         if let Err(e) = app.emit("upload-error", serde_json::json!({
             "filename": filename,
-            "error": error
+            "error": error,
+            "code": code
         })) {
             self.logger.warn(format!("Failed to emit upload-error: {}", e));
         }
@@ -318,21 +961,94 @@ impl UploadExecutor {
     }
 }
 
-/// Extract region from replay path by looking at folder structure
-/// Looks for patterns like "1-S2-1-802768" in the path
-/// Returns: "NA", "EU", "KR", "CN", or None
-fn extract_region_from_path(path: &std::path::Path) -> Option<String> {
+/// A Battle.net gateway/realm decoded from a Blizzard account folder name,
+/// e.g. `1-S2-1-802768` -> gateway 1, realm 1, region code `"NA"`.
+///
+/// The gateway/realm -> region mapping itself lives in
+/// [`crate::sc2_detector::region_for_gateway`] — this type just carries that
+/// same [`crate::sc2_detector::Region`] as the `&'static str` code the server
+/// API expects, so this module and `sc2_detector` can never disagree about
+/// what region a given account folder means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayRegion {
+    pub gateway: u8,
+    pub realm: u8,
+    pub region_code: &'static str,
+}
+
+/// Map a [`crate::sc2_detector::Region`] to the region code string the
+/// upload API expects.
+fn region_code_for(region: crate::sc2_detector::Region) -> &'static str {
+    use crate::sc2_detector::Region;
+    match region {
+        Region::NorthAmerica => "NA",
+        Region::SouthAmerica => "LATAM",
+        Region::Europe => "EU",
+        Region::Korea => "KR",
+        Region::Taiwan => "TW",
+        Region::China => "CN",
+        Region::PublicTest => "PTR",
+        Region::Unknown => "UNKNOWN",
+    }
+}
+
+/// Per-install overrides mapping a replay folder (any ancestor directory of
+/// the replay file) to a region code, for setups where the standard
+/// `<gateway>-S2-<realm>-<toon>` account folder isn't present in the path
+/// (e.g. replays synced to a shared drive under a custom folder layout).
+pub type RegionOverrides = HashMap<PathBuf, String>;
+
+/// Resolve a user-supplied override code to one of the known static region
+/// codes, so [`ReplayRegion::region_code`] never has to allocate.
+fn static_region_code(code: &str) -> &'static str {
+    match code.to_ascii_uppercase().as_str() {
+        "NA" => "NA",
+        "LATAM" => "LATAM",
+        "EU" => "EU",
+        "KR" => "KR",
+        "TW" => "TW",
+        "CN" => "CN",
+        "PTR" => "PTR",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Decode a Blizzard account folder name (`<gateway>-S2-<realm>-<toon>`) into
+/// its region and toon handle, e.g. `1-S2-1-802768` -> (NA, "802768").
+///
+/// Delegates the actual `<gateway>-S2-<realm>-<profile>` parsing to
+/// [`crate::sc2_detector::parse_account_folder_name`] so this module and
+/// `sc2_detector` always agree on a given account folder's region.
+fn parse_account_folder(name: &str) -> Option<(ReplayRegion, String)> {
+    let parsed = crate::sc2_detector::parse_account_folder_name(name)?;
+    let region = ReplayRegion {
+        gateway: parsed.gateway,
+        realm: parsed.realm,
+        region_code: region_code_for(parsed.region),
+    };
+    Some((region, parsed.profile.to_string()))
+}
+
+/// Decode a replay's Battle.net region, realm, and toon handle from its path.
+///
+/// Checks `overrides` against every ancestor directory first, so a custom
+/// folder layout always wins; otherwise scans path components for the
+/// standard `<gateway>-S2-<realm>-<toon>` account folder pattern.
+pub fn extract_replay_region(path: &Path, overrides: &RegionOverrides) -> Option<(ReplayRegion, String)> {
+    for ancestor in path.ancestors() {
+        if let Some(code) = overrides.get(ancestor) {
+            return Some((
+                ReplayRegion { gateway: 0, realm: 0, region_code: static_region_code(code) },
+                String::new(),
+            ));
+        }
+    }
+
     for component in path.components() {
         if let std::path::Component::Normal(folder_name) = component {
             if let Some(name) = folder_name.to_str() {
-                if name.starts_with("1-S2-") || name.starts_with("1-") {
-                    return Some("NA".to_string());
-                } else if name.starts_with("2-S2-") || name.starts_with("2-") {
-                    return Some("EU".to_string());
-                } else if name.starts_with("3-S2-") || name.starts_with("3-") {
-                    return Some("KR".to_string());
-                } else if name.starts_with("5-S2-") || name.starts_with("5-") {
-                    return Some("CN".to_string());
+                if let Some(parsed) = parse_account_folder(name) {
+                    return Some(parsed);
                 }
             }
         }
@@ -340,6 +1056,13 @@ fn extract_region_from_path(path: &std::path::Path) -> Option<String> {
     None
 }
 
+/// Extract just the region code from a replay path, for call sites that only
+/// need the string to hand to [`crate::replay_uploader::ReplayUploader::upload_replay`].
+/// See [`extract_replay_region`] for the full gateway/realm breakdown.
+fn extract_region_from_path(path: &std::path::Path) -> Option<String> {
+    extract_replay_region(path, &RegionOverrides::new()).map(|(region, _)| region.region_code.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -349,9 +1072,31 @@ mod tests {
     fn test_extract_region_na() {
         let path = PathBuf::from("/Users/test/StarCraft II/Accounts/123/1-S2-1-802768/Replays/test.SC2Replay");
         assert_eq!(extract_region_from_path(&path), Some("NA".to_string()));
+    }
 
-        let path2 = PathBuf::from("/Users/test/1-S2-2-123456/replay.SC2Replay");
-        assert_eq!(extract_region_from_path(&path2), Some("NA".to_string()));
+    #[test]
+    fn test_extract_region_latam() {
+        let path = PathBuf::from("/Users/test/1-S2-2-123456/replay.SC2Replay");
+        assert_eq!(extract_region_from_path(&path), Some("LATAM".to_string()));
+    }
+
+    #[test]
+    fn test_extract_region_tw() {
+        // Gateway 3 is Korea/Taiwan; realm 2 on that gateway is Taiwan.
+        let path = PathBuf::from("/Users/test/3-S2-2-802768/Replays/test.SC2Replay");
+        assert_eq!(extract_region_from_path(&path), Some("TW".to_string()));
+    }
+
+    #[test]
+    fn test_extract_region_ptr() {
+        let path = PathBuf::from("/Users/test/98-S2-1-802768/Replays/test.SC2Replay");
+        assert_eq!(extract_region_from_path(&path), Some("PTR".to_string()));
+    }
+
+    #[test]
+    fn test_extract_region_ptr_gateway_99() {
+        let path = PathBuf::from("/Users/test/99-S2-1-802768/Replays/test.SC2Replay");
+        assert_eq!(extract_region_from_path(&path), Some("PTR".to_string()));
     }
 
     #[test]
@@ -378,11 +1123,126 @@ mod tests {
         assert_eq!(extract_region_from_path(&path), None);
     }
 
+    #[test]
+    fn test_parse_account_folder_unknown_gateway_is_unknown_not_none() {
+        // An unrecognized gateway is still a structurally valid account
+        // folder, so it decodes to `Region::Unknown` rather than `None` —
+        // `None` is reserved for names that don't match the
+        // `<gateway>-S2-<realm>-<profile>` shape at all.
+        let (region, _) = parse_account_folder("7-S2-1-802768").unwrap();
+        assert_eq!(region.region_code, "UNKNOWN");
+    }
+
+    #[test]
+    fn test_parse_account_folder_extracts_toon() {
+        let (region, toon) = parse_account_folder("1-S2-1-802768").unwrap();
+        assert_eq!(region.region_code, "NA");
+        assert_eq!(toon, "802768");
+    }
+
+    #[test]
+    fn test_parse_account_folder_rejects_malformed_names() {
+        assert!(parse_account_folder("not-an-account-folder").is_none());
+        assert!(parse_account_folder("1-S2-802768").is_none());
+        assert!(parse_account_folder("1-WC3-1-802768").is_none());
+    }
+
+    #[test]
+    fn test_extract_replay_region_override_wins_over_account_folder() {
+        let path = PathBuf::from("/custom/shared-drive/1-S2-1-802768/test.SC2Replay");
+        let mut overrides = RegionOverrides::new();
+        overrides.insert(PathBuf::from("/custom/shared-drive"), "eu".to_string());
+
+        let (region, toon) = extract_replay_region(&path, &overrides).unwrap();
+        assert_eq!(region.region_code, "EU");
+        assert_eq!(toon, "");
+    }
+
+    #[test]
+    fn test_extract_replay_region_falls_back_to_account_folder() {
+        let path = PathBuf::from("/Users/test/2-S2-1-802768/test.SC2Replay");
+        let (region, toon) = extract_replay_region(&path, &RegionOverrides::new()).unwrap();
+        assert_eq!(region.region_code, "EU");
+        assert_eq!(toon, "802768");
+    }
+
     #[test]
     fn test_upload_result() {
         let result = UploadResult {
             uploaded_count: 5,
+            succeeded: vec![("hash1".to_string(), "/a.SC2Replay".to_string())],
+            enqueued_count: 2,
+            failed: vec![("hash1".to_string(), "a.SC2Replay".to_string(), "timeout".to_string())],
+            skipped: 1,
         };
         assert_eq!(result.uploaded_count, 5);
+        assert_eq!(result.enqueued_count, 2);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.skipped, 1);
+    }
+
+    #[test]
+    fn test_upload_result_default() {
+        let result = UploadResult::default();
+        assert_eq!(result.uploaded_count, 0);
+        assert_eq!(result.enqueued_count, 0);
+        assert!(result.failed.is_empty());
+        assert_eq!(result.skipped, 0);
+    }
+
+    #[test]
+    fn test_upload_policy_default_is_continue_on_error() {
+        assert_eq!(UploadPolicy::default(), UploadPolicy::ContinueOnError);
+    }
+
+    #[test]
+    fn test_is_duplicate_upload_error() {
+        assert!(is_duplicate_upload_error("409 Conflict"));
+        assert!(is_duplicate_upload_error("REPLAY_DUPLICATE"));
+        assert!(is_duplicate_upload_error("replay has already been uploaded"));
+        assert!(!is_duplicate_upload_error("500 Internal Server Error"));
+    }
+
+    #[test]
+    fn test_is_permanent_upload_error() {
+        assert!(is_permanent_upload_error("400 Bad Request"));
+        assert!(is_permanent_upload_error("401 Unauthorized"));
+        assert!(is_permanent_upload_error("404 Not Found"));
+        assert!(!is_permanent_upload_error("500 Internal Server Error"));
+        assert!(!is_permanent_upload_error("connection timed out"));
+    }
+
+    #[test]
+    fn test_batch_report_total() {
+        let mut report = BatchUploadReport::default();
+        assert_eq!(report.total(), 0);
+        report.failed.push(("/tmp/a.SC2Replay".to_string(), "boom".to_string()));
+        assert_eq!(report.total(), 1);
+    }
+
+    #[test]
+    fn test_percent_of_clamps() {
+        assert_eq!(percent_of(0, 0), 100.0);
+        assert_eq!(percent_of(0, 10), 0.0);
+        assert_eq!(percent_of(5, 10), 50.0);
+        assert_eq!(percent_of(20, 10), 100.0);
+    }
+
+    #[test]
+    fn test_throughput_eta_uses_rolling_average() {
+        let mut tracker = ThroughputTracker::new();
+        assert_eq!(tracker.eta_seconds(1000), None, "no samples yet");
+
+        // 1000 bytes in 1s => 1000 B/s; 2000 bytes remaining => ~2s.
+        tracker.record(1000, 1.0);
+        assert_eq!(tracker.eta_seconds(2000), Some(2));
+    }
+
+    #[test]
+    fn test_throughput_ignores_degenerate_samples() {
+        let mut tracker = ThroughputTracker::new();
+        tracker.record(0, 1.0);
+        tracker.record(1000, 0.0);
+        assert_eq!(tracker.bytes_per_sec(), None);
     }
 }
@@ -0,0 +1,278 @@
+//! Durable upload job store
+//!
+//! A persisted, hash-keyed job list that survives app restarts. Replays
+//! discovered by [`ReplayScanner`](crate::services::replay_scanner) are
+//! enqueued here and drained through [`ReplayUploader::upload_replay`], so a
+//! large first-time sync that is interrupted by a crash or a network outage
+//! resumes from where it left off instead of re-scanning and re-uploading
+//! everything.
+//!
+//! Each job carries an explicit [`JobState`] (pending / in-progress /
+//! succeeded / failed) plus its attempt count and last error. A job is removed
+//! from the store only once the server confirms the upload, so nothing is lost
+//! if the process dies mid-upload — the job is simply reloaded as `pending` (or
+//! reset from a dangling `in-progress`) on the next startup.
+//!
+//! This complements the [`UploadQueue`](crate::upload_queue) retry backstop:
+//! the queue holds only uploads that already failed and reschedules them with
+//! exponential backoff, whereas this store is the durable record of
+//! *everything still to upload* across sessions. The store is written through the atomic
+//! `config_utils` helpers so a crash mid-write never corrupts it.
+
+use crate::config_utils::{load_config_file, save_config_file};
+use crate::debug_logger::DebugLogger;
+use crate::replay_uploader::ReplayUploader;
+use crate::services::replay_scanner::PreparedReplay;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Filename used to persist the job store in the config directory.
+const STORE_FILE: &str = "upload_jobs.json";
+
+/// Lifecycle state of a single upload job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum JobState {
+    /// Enqueued and waiting to be uploaded.
+    Pending,
+    /// Currently being uploaded. A job left in this state by a crash is reset
+    /// to `Pending` on the next load.
+    InProgress,
+    /// Confirmed uploaded by the server. The drainer removes a confirmed job
+    /// instead of assigning this state, so persisted stores never contain it.
+    Succeeded,
+    /// The most recent attempt failed; `error` carries the reason.
+    Failed { error: String },
+}
+
+/// A single durable upload job, keyed by its replay content hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadJobRecord {
+    /// SHA-256 content hash; the primary key used for de-duplication.
+    pub hash: String,
+    /// Absolute path to the replay file on disk.
+    pub path: PathBuf,
+    /// Player name to attach to the upload.
+    pub player_name: String,
+    /// Competitive game type string (e.g. `1v1`).
+    pub game_type: String,
+    /// Current lifecycle state.
+    pub state: JobState,
+    /// Number of upload attempts made so far.
+    pub attempts: u32,
+}
+
+impl UploadJobRecord {
+    /// Build a fresh `pending` job from a scanned, prepared replay.
+    pub fn from_prepared(prepared: &PreparedReplay) -> Self {
+        Self {
+            hash: prepared.hash.clone(),
+            path: prepared.file_info.path.clone(),
+            player_name: prepared.player_name.clone(),
+            game_type: prepared.game_type.clone(),
+            state: JobState::Pending,
+            attempts: 0,
+        }
+    }
+}
+
+/// Persisted set of outstanding upload jobs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UploadJobStore {
+    pub jobs: Vec<UploadJobRecord>,
+}
+
+impl UploadJobStore {
+    /// Load the store from disk, resetting any job left `in-progress` by a
+    /// previous crash back to `pending` so it is retried cleanly.
+    pub async fn load() -> Result<Self, String> {
+        let mut store: Self = load_config_file(STORE_FILE).await?.unwrap_or_default();
+        for job in &mut store.jobs {
+            if job.state == JobState::InProgress {
+                job.state = JobState::Pending;
+            }
+        }
+        Ok(store)
+    }
+
+    /// Persist the store atomically.
+    pub async fn save(&self) -> Result<(), String> {
+        save_config_file(STORE_FILE, self).await.map(|_| ())
+    }
+
+    /// Enqueue a job, skipping it if one with the same hash already exists.
+    /// Returns `true` if the job was added.
+    pub fn enqueue(&mut self, job: UploadJobRecord) -> bool {
+        if self.jobs.iter().any(|j| j.hash == job.hash) {
+            return false;
+        }
+        self.jobs.push(job);
+        true
+    }
+
+    /// Remove the job with the given hash (called on confirmed success).
+    fn remove(&mut self, hash: &str) {
+        self.jobs.retain(|j| j.hash != hash);
+    }
+
+    /// Mark a job's state by hash, leaving the store otherwise untouched.
+    fn set_state(&mut self, hash: &str, state: JobState) {
+        if let Some(job) = self.jobs.iter_mut().find(|j| j.hash == hash) {
+            job.state = state;
+        }
+    }
+}
+
+/// Drains the job store through an uploader, persisting after every job so an
+/// interruption never loses more than the in-flight upload.
+pub struct JobStoreDrainer {
+    uploader: Arc<ReplayUploader>,
+    logger: Arc<DebugLogger>,
+}
+
+impl JobStoreDrainer {
+    pub fn new(uploader: Arc<ReplayUploader>, logger: Arc<DebugLogger>) -> Self {
+        Self { uploader, logger }
+    }
+
+    /// Enqueue newly-prepared replays into the persisted store, de-duplicating
+    /// by hash against jobs already recorded. Returns the number newly added.
+    pub async fn enqueue_prepared(&self, prepared: &[PreparedReplay]) -> Result<usize, String> {
+        let mut store = UploadJobStore::load().await?;
+        let added = prepared
+            .iter()
+            .filter(|p| store.enqueue(UploadJobRecord::from_prepared(p)))
+            .count();
+        if added > 0 {
+            store.save().await?;
+            self.logger
+                .info(format!("Enqueued {} replay(s) to the durable upload store", added));
+        }
+        Ok(added)
+    }
+
+    /// Upload every pending job, removing each on confirmed success and marking
+    /// failures so they are retried on the next drain (or after a restart).
+    /// Returns the number of jobs that uploaded successfully in this pass.
+    pub async fn drain(&self) -> Result<usize, String> {
+        self.drain_with_progress(&AtomicBool::new(false), |_, _| {}).await
+    }
+
+    /// Like [`Self::drain`], but checks `cancelled` before starting each job
+    /// (so a caller can stop the pass early without waiting for the rest of
+    /// the snapshot to finish) and invokes `on_item(&job, succeeded)` after
+    /// every attempt, for a caller that wants to track live progress (e.g. the
+    /// tracked job subsystem in `crate::services::job_manager`).
+    pub async fn drain_with_progress(
+        &self,
+        cancelled: &AtomicBool,
+        mut on_item: impl FnMut(&UploadJobRecord, bool),
+    ) -> Result<usize, String> {
+        let pending: Vec<UploadJobRecord> = {
+            let store = UploadJobStore::load().await?;
+            store
+                .jobs
+                .into_iter()
+                .filter(|j| j.state == JobState::Pending || matches!(j.state, JobState::Failed { .. }))
+                .collect()
+        };
+
+        let mut succeeded = 0;
+        for job in pending {
+            if cancelled.load(Ordering::Relaxed) {
+                self.logger.info("Durable upload drain cancelled".to_string());
+                break;
+            }
+
+            // Flip to in-progress and persist so a crash mid-upload is visible.
+            self.update_state(&job.hash, JobState::InProgress).await?;
+
+            let result = self
+                .uploader
+                .upload_replay(
+                    &job.path,
+                    Some(&job.player_name),
+                    None,
+                    Some(&job.game_type),
+                    None,
+                    None,
+                )
+                .await;
+
+            let mut store = UploadJobStore::load().await?;
+            let success = match result {
+                Ok(_) => {
+                    store.remove(&job.hash);
+                    succeeded += 1;
+                    self.logger.info(format!("Durable upload job {} succeeded", job.hash));
+                    true
+                }
+                Err(e) => {
+                    if let Some(rec) = store.jobs.iter_mut().find(|j| j.hash == job.hash) {
+                        rec.attempts += 1;
+                        rec.state = JobState::Failed { error: e.clone() };
+                    }
+                    self.logger
+                        .warn(format!("Durable upload job {} failed: {}", job.hash, e));
+                    false
+                }
+            };
+            store.save().await?;
+            on_item(&job, success);
+        }
+
+        Ok(succeeded)
+    }
+
+    /// Load, mutate one job's state, and persist.
+    async fn update_state(&self, hash: &str, state: JobState) -> Result<(), String> {
+        let mut store = UploadJobStore::load().await?;
+        store.set_state(hash, state);
+        store.save().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(hash: &str) -> UploadJobRecord {
+        UploadJobRecord {
+            hash: hash.to_string(),
+            path: PathBuf::from("/tmp/x.SC2Replay"),
+            player_name: "Player".to_string(),
+            game_type: "1v1".to_string(),
+            state: JobState::Pending,
+            attempts: 0,
+        }
+    }
+
+    #[test]
+    fn test_enqueue_dedupes_by_hash() {
+        let mut store = UploadJobStore::default();
+        assert!(store.enqueue(record("abc")));
+        assert!(!store.enqueue(record("abc")), "duplicate hash should be rejected");
+        assert_eq!(store.jobs.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_on_success() {
+        let mut store = UploadJobStore::default();
+        store.enqueue(record("abc"));
+        store.remove("abc");
+        assert!(store.jobs.is_empty());
+    }
+
+    #[test]
+    fn test_set_state_records_failure() {
+        let mut store = UploadJobStore::default();
+        store.enqueue(record("abc"));
+        store.set_state("abc", JobState::Failed { error: "boom".to_string() });
+        assert_eq!(
+            store.jobs[0].state,
+            JobState::Failed { error: "boom".to_string() }
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Background session-refresh and expiry subsystem.
+//!
+//! [`AuthTokens`] already carries a `refresh_token` and an `expires_at`, but the
+//! auth commands never acted on them: once the access token lapsed the user was
+//! silently signed out mid-upload. This module closes that gap. It inspects
+//! `expires_at`, exchanges the refresh token a configurable window *before*
+//! expiry via [`ApiClient::refresh_token`], persists the rotated tokens through
+//! the existing [`SecureTokenStore`] save path, and emits a fresh
+//! [`AppState::Authenticated`]. A Tokio timer task re-arms itself after each
+//! refresh so long-lived sessions keep uploading without interruption; a failed
+//! refresh transitions to [`AppState::NeedsAuth`] rather than surfacing an error.
+//!
+//! [`ApiClient::refresh_token`]: crate::device_auth::ApiClient::refresh_token
+//! [`SecureTokenStore`]: crate::token_store::SecureTokenStore
+
+use std::sync::atomic::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{Emitter, Manager};
+
+use crate::state::AppState;
+use crate::state::AppStateManager;
+use crate::token_store::SecureTokenStore;
+use crate::types::{AuthTokens, SecretString};
+
+/// Default lead time before `expires_at` at which we refresh proactively.
+const DEFAULT_REFRESH_WINDOW_SECS: u64 = 120;
+
+/// Assumed access-token lifetime used to re-arm the timer after a refresh; the
+/// refresh endpoint returns a new access token but not a new TTL, so we fall
+/// back to a conservative value and refresh again within its window.
+const ASSUMED_ACCESS_TTL_SECS: u64 = 60 * 60;
+
+/// Smallest delay the timer will ever sleep, so a just-expired token doesn't
+/// spin the refresh loop.
+const MIN_REARM_SECS: u64 = 5;
+
+/// Current Unix time in seconds.
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Open the encrypted token store backed by `auth.json`.
+fn open_store() -> Result<SecureTokenStore, String> {
+    let path = crate::config_utils::config_file_path("auth.json")?;
+    SecureTokenStore::open(path)
+}
+
+/// Read the configurable refresh window (seconds before expiry), falling back
+/// to [`DEFAULT_REFRESH_WINDOW_SECS`].
+async fn refresh_window_secs() -> u64 {
+    let config: Option<serde_json::Value> =
+        crate::config_utils::load_config_file("config.json").await.ok().flatten();
+    config
+        .as_ref()
+        .and_then(|c| c.get("token_refresh_window_secs"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_REFRESH_WINDOW_SECS)
+}
+
+/// Exchange the stored refresh token for a fresh access token, persist the
+/// rotated [`AuthTokens`], and move the app into [`AppState::Authenticated`].
+///
+/// Returns the new `expires_at` on success. On a missing/invalid refresh token
+/// the app is transitioned to [`AppState::NeedsAuth`] and an error is returned
+/// so the caller can stop the timer.
+pub async fn refresh_once(app: &tauri::AppHandle) -> Result<u64, String> {
+    let state_manager = app.state::<AppStateManager>();
+    let logger = &state_manager.debug_logger;
+
+    let store = open_store()?;
+    let current = match store.load()? {
+        Some(tokens) => tokens,
+        None => {
+            logger.info("No stored tokens to refresh; needs auth".to_string());
+            set_needs_auth(app);
+            return Err("No stored tokens".to_string());
+        }
+    };
+
+    let Some(refresh_token) = current.refresh_token.as_ref() else {
+        logger.warn("Stored tokens have no refresh token; needs auth".to_string());
+        set_needs_auth(app);
+        return Err("No refresh token".to_string());
+    };
+
+    let new_access = match state_manager
+        .api_client
+        .refresh_token(refresh_token.expose_secret())
+        .await
+    {
+        Ok(access) => access,
+        Err(e) => {
+            logger.warn(format!("Token refresh failed, re-authentication required: {}", e));
+            set_needs_auth(app);
+            return Err(e);
+        }
+    };
+
+    let expires_at = now_secs() + ASSUMED_ACCESS_TTL_SECS;
+    let rotated = AuthTokens {
+        access_token: SecretString::new(new_access),
+        refresh_token: current.refresh_token.clone(),
+        expires_at: Some(expires_at),
+        user: current.user.clone(),
+    };
+    store.save(&rotated)?;
+
+    // Surface the still-authenticated session to the UI.
+    if let Some(user) = &rotated.user {
+        let avatar_url = user.avatar_url.clone().unwrap_or_default();
+        let new_state = AppState::Authenticated {
+            username: user.username.clone(),
+            avatar_url,
+        };
+        if let Ok(mut state) = state_manager.state.lock() {
+            *state = new_state.clone();
+        }
+        let _ = app.emit("app-state-changed", &new_state);
+    }
+
+    logger.info("Access token refreshed ahead of expiry".to_string());
+    Ok(expires_at)
+}
+
+/// Transition to [`AppState::NeedsAuth`] and notify the front end.
+fn set_needs_auth(app: &tauri::AppHandle) {
+    let state_manager = app.state::<AppStateManager>();
+    if let Ok(mut state) = state_manager.state.lock() {
+        *state = AppState::NeedsAuth;
+    }
+    let _ = app.emit("app-state-changed", AppState::NeedsAuth);
+}
+
+/// Spawn the background refresh timer, which sleeps until the configured window
+/// before the stored token's expiry, refreshes, and re-arms. Idempotent: a
+/// second call while the loop is running is a no-op.
+pub fn start(app: tauri::AppHandle) {
+    let state_manager = app.state::<AppStateManager>();
+    if state_manager.session_refresh_started.swap(true, Ordering::AcqRel) {
+        state_manager.debug_logger.debug("Session refresh already running".to_string());
+        return;
+    }
+    state_manager.debug_logger.info("Starting background session refresh".to_string());
+
+    tokio::spawn(async move {
+        loop {
+            // Work out how long until the next refresh from the stored expiry.
+            let expires_at = {
+                let store = match open_store() {
+                    Ok(store) => store,
+                    Err(_) => break,
+                };
+                match store.load() {
+                    Ok(Some(tokens)) => tokens.expires_at,
+                    Ok(None) => {
+                        // Not logged in; nothing to refresh. Stop and let the
+                        // device flow restart us after the next login.
+                        break;
+                    }
+                    Err(_) => break,
+                }
+            };
+
+            let window = refresh_window_secs().await;
+            let sleep = match expires_at {
+                Some(exp) => exp
+                    .saturating_sub(now_secs())
+                    .saturating_sub(window)
+                    .max(MIN_REARM_SECS),
+                // No known expiry: check again one refresh window before the
+                // end of an assumed lifetime.
+                None => ASSUMED_ACCESS_TTL_SECS
+                    .saturating_sub(window)
+                    .max(MIN_REARM_SECS),
+            };
+            tokio::time::sleep(std::time::Duration::from_secs(sleep)).await;
+
+            if refresh_once(&app).await.is_err() {
+                // refresh_once has moved us to NeedsAuth if the tokens were
+                // missing or the refresh was rejected; on any error, stop.
+                break;
+            }
+        }
+
+        // Allow a later login to spawn a fresh loop.
+        let state_manager = app.state::<AppStateManager>();
+        state_manager.session_refresh_started.store(false, Ordering::Release);
+    });
+}
@@ -0,0 +1,233 @@
+//! Rotating, per-session on-disk log backend for [`DebugLogger`].
+//!
+//! Each app launch opens a new *session directory* under the cache dir and
+//! streams structured log entries into numbered NDJSON files inside it. A file
+//! rolls to the next number once it passes `max_log_size_bytes`; the session as
+//! a whole is capped at `max_session_size_bytes` (oldest files pruned first);
+//! and only the most recent `max_sessions` directories are retained. Writes are
+//! append-only and flushed per entry, and the rotation check happens *before*
+//! each line is written so a line is never split across files by a roll.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+use crate::debug_logger::DebugLogEntry;
+
+/// Tunable size/retention limits for the session log backend.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionConfig {
+    /// Roll to a new numbered file once the current one passes this size.
+    pub max_log_size_bytes: u64,
+    /// Cap on the total bytes retained within one session directory; the
+    /// oldest numbered files are pruned when this is exceeded.
+    pub max_session_size_bytes: u64,
+    /// Number of session directories to keep before deleting the oldest.
+    pub max_sessions: usize,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            max_log_size_bytes: 1024 * 1024,
+            max_session_size_bytes: 10 * 1024 * 1024,
+            max_sessions: 10,
+        }
+    }
+}
+
+/// Append-only, rotating writer for a single app-launch session.
+pub struct SessionLogger {
+    session_dir: PathBuf,
+    config: SessionConfig,
+    /// Current numbered file handle and its size in bytes.
+    current: Option<(File, u64)>,
+    /// Index of the current numbered file (`0000.ndjson`, `0001.ndjson`, ...).
+    file_index: u32,
+}
+
+impl SessionLogger {
+    /// Open a fresh session under `root`, pruning the oldest sessions past the
+    /// retention limit. The session directory is named for the launch instant
+    /// so directories sort chronologically.
+    pub fn new(root: PathBuf, config: SessionConfig) -> Self {
+        let session_dir = root.join(format!("session_{}", Utc::now().format("%Y%m%d_%H%M%S%3f")));
+        let logger = Self {
+            session_dir,
+            config,
+            current: None,
+            file_index: 0,
+        };
+        prune_sessions(&root, config.max_sessions);
+        logger
+    }
+
+    /// Path of the session directory this logger writes to.
+    pub fn session_dir(&self) -> &Path {
+        &self.session_dir
+    }
+
+    fn file_name(index: u32) -> String {
+        format!("{:04}.ndjson", index)
+    }
+
+    /// Open (creating if needed) the numbered file at the current index.
+    fn open_current(&mut self) -> Result<(), String> {
+        fs::create_dir_all(&self.session_dir)
+            .map_err(|e| format!("Failed to create session directory: {}", e))?;
+        let path = self.session_dir.join(Self::file_name(self.file_index));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open session log file: {}", e))?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        self.current = Some((file, size));
+        Ok(())
+    }
+
+    /// Append one serialized entry line. Rotates to the next numbered file
+    /// first when the current one is full, then enforces the per-session byte
+    /// cap. Flushed before returning so a crash can lose at most the entry in
+    /// flight, never a half-written line.
+    pub fn append(&mut self, line: &str) -> Result<(), String> {
+        if self.current.is_none() {
+            self.open_current()?;
+        }
+        // Roll *before* writing so the line lands wholly in one file.
+        if let Some((_, size)) = &self.current {
+            if *size >= self.config.max_log_size_bytes {
+                self.file_index += 1;
+                self.open_current()?;
+            }
+        }
+
+        if let Some((file, size)) = self.current.as_mut() {
+            let bytes = line.as_bytes();
+            file.write_all(bytes)
+                .and_then(|_| file.write_all(b"\n"))
+                .and_then(|_| file.flush())
+                .map_err(|e| format!("Failed to append session log line: {}", e))?;
+            *size += bytes.len() as u64 + 1;
+        }
+
+        self.enforce_session_cap();
+        Ok(())
+    }
+
+    /// Prune the oldest numbered files in this session until the total size is
+    /// within `max_session_size_bytes`. The file currently being written is
+    /// never removed.
+    fn enforce_session_cap(&mut self) {
+        let mut files = numbered_files(&self.session_dir);
+        let current_name = Self::file_name(self.file_index);
+        let mut total: u64 = files
+            .iter()
+            .filter_map(|p| fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+
+        while total > self.config.max_session_size_bytes && files.len() > 1 {
+            let oldest = files.remove(0);
+            if oldest.file_name().and_then(|n| n.to_str()) == Some(current_name.as_str()) {
+                // Don't delete the active file; stop pruning.
+                break;
+            }
+            if let Ok(meta) = fs::metadata(&oldest) {
+                total = total.saturating_sub(meta.len());
+            }
+            let _ = fs::remove_file(&oldest);
+        }
+    }
+}
+
+/// Numbered log files in a session directory, oldest first.
+fn numbered_files(session_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(session_dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension().and_then(|e| e.to_str()) == Some("ndjson")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Session directories under `root`, oldest first.
+pub fn sessions(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(root)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_dir()
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with("session_"))
+                    .unwrap_or(false)
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// Delete session directories beyond `max_sessions`, oldest first.
+fn prune_sessions(root: &Path, max_sessions: usize) {
+    let dirs = sessions(root);
+    if dirs.len() > max_sessions {
+        for dir in &dirs[..dirs.len() - max_sessions] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Stream the entries of a session directory in timestamp (file-number) order.
+///
+/// Reads each numbered file with a buffered line reader and yields parsed
+/// [`DebugLogEntry`] values, so the frontend can assemble a support bundle
+/// without loading the whole history into memory at once.
+pub fn read_logs(session_dir: PathBuf) -> impl futures::Stream<Item = DebugLogEntry> {
+    use tokio::io::AsyncBufReadExt;
+
+    async_stream::stream! {
+        for path in numbered_files(&session_dir) {
+            let Ok(file) = tokio::fs::File::open(&path).await else { continue };
+            let mut lines = tokio::io::BufReader::new(file).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if let Ok(entry) = serde_json::from_str::<DebugLogEntry>(&line) {
+                    yield entry;
+                }
+            }
+        }
+    }
+}
+
+/// Synchronously collect every entry across all files in a session, oldest
+/// first. Used by report generation, which needs the full history in one shot.
+pub fn collect_session(session_dir: &Path) -> Vec<DebugLogEntry> {
+    let mut entries = Vec::new();
+    for path in numbered_files(session_dir) {
+        if let Ok(contents) = fs::read_to_string(&path) {
+            for line in contents.lines() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if let Ok(entry) = serde_json::from_str::<DebugLogEntry>(line) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+    entries
+}
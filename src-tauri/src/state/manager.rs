@@ -1,8 +1,13 @@
 //! Application state manager providing thread-safe access to app state.
 
+use std::sync::atomic::{AtomicBool, AtomicU64};
 use std::sync::{Arc, Mutex};
+use tauri::menu::MenuItem;
+use tauri::tray::TrayIcon;
 use crate::device_auth;
 use crate::debug_logger;
+use crate::db::ReplayDb;
+use crate::services::job_manager::JobManager;
 use crate::upload_manager::UploadManager;
 use super::AppState;
 
@@ -16,16 +21,61 @@ pub struct AppStateManager {
     pub upload_manager: Mutex<Option<Arc<UploadManager>>>,
     /// Debug logger for capturing application events
     pub debug_logger: Arc<debug_logger::DebugLogger>,
+    /// The system-tray icon handle, stored here so it outlives `setup()` and
+    /// can be updated live as upload state changes. `None` until the tray is
+    /// created during setup.
+    pub tray_icon: Mutex<Option<TrayIcon>>,
+    /// The tray menu's pause/resume item, kept so its label can be flipped.
+    pub tray_pause_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    /// Whether background uploads are currently paused from the tray.
+    pub uploads_paused: AtomicBool,
+    /// Set by [`crate::commands::queue::cancel_current_upload`] to abort the
+    /// in-flight batch at the next opportunity; [`UploadExecutor::execute`]
+    /// checks it between replays and clears it once consumed, so it never
+    /// leaks into the next batch.
+    ///
+    /// [`UploadExecutor::execute`]: crate::services::UploadExecutor::execute
+    pub upload_cancelled: AtomicBool,
+    /// Guard so the watcher and the periodic scheduler never run a scan at the
+    /// same time; a scheduler cycle or scan command arriving mid-scan skips,
+    /// and a watcher upload waits for the in-flight scan to finish.
+    pub scan_in_progress: AtomicBool,
+    /// Set once the periodic re-scan scheduler has been spawned, so repeated
+    /// `start_scan_scheduler` calls don't stack multiple loops.
+    pub scan_scheduler_started: AtomicBool,
+    /// Unix timestamp (seconds) of the last completed scan cycle, surfaced to
+    /// the UI as a "last checked" time. `0` until the first cycle finishes.
+    pub last_scan_at: AtomicU64,
+    /// Set once the background session-refresh timer has been spawned, so it is
+    /// started at most once per login.
+    pub session_refresh_started: AtomicBool,
+    /// Tracks the current (or most recently run) background scan-and-upload
+    /// job, resumable across restarts. See [`JobManager`].
+    pub job_manager: Arc<JobManager>,
+    /// Durable record of uploaded replays and detected-folder settings. See
+    /// [`ReplayDb`].
+    pub db: Arc<ReplayDb>,
 }
 
 impl AppStateManager {
     /// Create a new AppStateManager with default initial state
     pub fn new() -> Self {
+        let debug_logger = Arc::new(debug_logger::DebugLogger::new());
         Self {
             state: Mutex::new(AppState::DetectingFolder),
             api_client: device_auth::ApiClient::new(),
             upload_manager: Mutex::new(None),
-            debug_logger: Arc::new(debug_logger::DebugLogger::new()),
+            job_manager: Arc::new(JobManager::new(Arc::clone(&debug_logger))),
+            db: Arc::new(ReplayDb::open_sync(&debug_logger)),
+            debug_logger,
+            tray_icon: Mutex::new(None),
+            tray_pause_item: Mutex::new(None),
+            uploads_paused: AtomicBool::new(false),
+            upload_cancelled: AtomicBool::new(false),
+            scan_in_progress: AtomicBool::new(false),
+            scan_scheduler_started: AtomicBool::new(false),
+            last_scan_at: AtomicU64::new(0),
+            session_refresh_started: AtomicBool::new(false),
         }
     }
 }
@@ -104,6 +104,39 @@ impl TestHarness {
             .create()
     }
 
+    /// Mock the /api/settings GET endpoint returning an `ETag` header, so the
+    /// client caches the response for a later conditional request. Matches a
+    /// single request so a follow-up falls through to a 304 mock.
+    pub fn mock_get_settings_with_etag(&mut self, confirmed_names: Vec<&str>, etag: &str) -> Mock {
+        self.server.mock("GET", "/api/settings")
+            .match_header("authorization", mockito::Matcher::Regex(r"Bearer .+".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("etag", etag)
+            .with_body(json!({
+                "settings": {
+                    "discord_user_id": "test-user-123",
+                    "default_race": null,
+                    "favorite_builds": [],
+                    "confirmed_player_names": confirmed_names,
+                    "possible_player_names": {},
+                    "created_at": "2025-01-01T00:00:00Z",
+                    "updated_at": "2025-01-01T00:00:00Z"
+                }
+            }).to_string())
+            .expect(1)
+            .create()
+    }
+
+    /// Mock a `304 Not Modified` response with no body for a conditional GET
+    /// that carries a matching `If-None-Match` header.
+    pub fn mock_not_modified(&mut self, path: &str) -> Mock {
+        self.server.mock("GET", path)
+            .match_header("if-none-match", mockito::Matcher::Any)
+            .with_status(304)
+            .create()
+    }
+
     /// Mock an unauthorized response (401)
     pub fn mock_unauthorized(&mut self, path: &str) -> Mock {
         self.server.mock("POST", path)
@@ -126,7 +159,10 @@ mod tests {
 
     fn create_test_replay(dir: &Path, name: &str, contents: &[u8]) -> std::path::PathBuf {
         let path = dir.join(name);
-        fs::write(&path, contents).unwrap();
+        // Prefix with the MPQ magic so local pre-upload validation accepts it.
+        let mut bytes = b"MPQ\x1a".to_vec();
+        bytes.extend_from_slice(contents);
+        fs::write(&path, bytes).unwrap();
         path
     }
 
@@ -147,7 +183,7 @@ mod tests {
             "test-access-token".to_string(),
         );
 
-        let result = uploader.upload_replay(&replay_path, None, None, None, None).await;
+        let result = uploader.upload_replay(&replay_path, None, None, None, None, None).await;
 
         assert!(result.is_ok());
         let replay = result.unwrap();
@@ -197,6 +233,7 @@ mod tests {
             None,
             Some("1v1-ladder"),
             Some("NA"),
+            Some(1),
         ).await;
 
         assert!(result.is_ok());
@@ -221,7 +258,7 @@ mod tests {
             "invalid-token".to_string(),
         );
 
-        let result = uploader.upload_replay(&replay_path, None, None, None, None).await;
+        let result = uploader.upload_replay(&replay_path, None, None, None, None, None).await;
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("401"));
@@ -244,7 +281,7 @@ mod tests {
             "test-token".to_string(),
         );
 
-        let result = uploader.upload_replay(&replay_path, None, None, None, None).await;
+        let result = uploader.upload_replay(&replay_path, None, None, None, None, None).await;
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("500"));
@@ -336,4 +373,73 @@ mod tests {
         assert_eq!(settings.confirmed_player_names.len(), 2);
         assert!(settings.confirmed_player_names.contains(&"Lotus".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_conditional_get_uses_cache_on_304() {
+        let mut harness = TestHarness::new().await;
+        // First request: 200 with an ETag, which the client caches.
+        let _etag_mock = harness.mock_get_settings_with_etag(vec!["Lotus"], "\"v1\"");
+        // Second request: 304, so the client must serve the cached body.
+        let _not_modified = harness.mock_not_modified("/api/settings");
+
+        let uploader = ReplayUploader::new(harness.url(), "test-token".to_string());
+
+        let first = uploader.get_user_settings().await.unwrap();
+        assert_eq!(first.confirmed_player_names, vec!["Lotus".to_string()]);
+
+        // The 304 path returns no body; a correct implementation reuses the
+        // cached settings rather than failing to parse an empty response.
+        let second = uploader.get_user_settings().await.unwrap();
+        assert_eq!(second.confirmed_player_names, vec!["Lotus".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_expiring_token_is_refreshed_before_request() {
+        let mut harness = TestHarness::new().await;
+        // The refresh endpoint must be hit exactly once before the settings call.
+        let _refresh = harness
+            .server
+            .mock("POST", "/api/auth/refresh")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "access_token": "refreshed-token",
+                "refresh_token": "rotated-refresh-token",
+                "expires_in": 3600
+            }).to_string())
+            .expect(1)
+            .create();
+        // The settings request must carry the refreshed token.
+        let _settings = harness
+            .server
+            .mock("GET", "/api/settings")
+            .match_header("authorization", "Bearer refreshed-token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({
+                "settings": {
+                    "discord_user_id": "test-user-123",
+                    "default_race": null,
+                    "favorite_builds": [],
+                    "confirmed_player_names": ["Lotus"],
+                    "possible_player_names": {},
+                    "created_at": "2025-01-01T00:00:00Z",
+                    "updated_at": "2025-01-01T00:00:00Z"
+                }
+            }).to_string())
+            .create();
+
+        // Access token already expired, so a proactive refresh must fire.
+        let uploader = ReplayUploader::with_auth(
+            harness.url(),
+            "stale-token".to_string(),
+            Some("refresh-token".to_string()),
+            Some(0),
+            None,
+            None,
+        );
+
+        let settings = uploader.get_user_settings().await.unwrap();
+        assert_eq!(settings.confirmed_player_names, vec!["Lotus".to_string()]);
+    }
 }
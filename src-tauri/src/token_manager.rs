@@ -0,0 +1,314 @@
+//! Proactive access-token refresh driven by [`AuthTokens::expires_at`].
+//!
+//! Independently of the refresh logic inside
+//! [`ReplayUploader`](crate::replay_uploader), the [`TokenManager`] refreshes
+//! *ahead* of expiry: before an
+//! authenticated request it checks whether the access token is within a skew
+//! window of expiring and, if so, exchanges the refresh token for a fresh set
+//! of [`AuthTokens`] before the request goes out. A single-flight lock ensures a
+//! burst of upload tasks that all notice expiry at once fire exactly one refresh
+//! request and share its result.
+
+use crate::api_contracts::UserInfo;
+use crate::debug_logger::DebugLogger;
+use crate::types::{AuthTokens, SecretString, UserData};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default number of seconds before `expires_at` at which the access token is
+/// treated as stale and refreshed proactively.
+const DEFAULT_REFRESH_SKEW_SECS: u64 = 60;
+
+/// Error surfaced by [`TokenManager`] when a token cannot be produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    /// No tokens are stored, or the refresh grant was rejected and the stored
+    /// tokens were cleared. The UI must restart the device flow.
+    ReauthRequired,
+    /// A transport- or server-level error occurred during refresh.
+    Network(String),
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::ReauthRequired => write!(f, "Re-authentication required"),
+            TokenError::Network(e) => write!(f, "Token refresh failed: {}", e),
+        }
+    }
+}
+
+/// Body of a successful refresh: a fresh access/refresh token pair plus
+/// whatever user info the server chose to include, matching the shape of
+/// [`crate::device_auth::AuthResponse`] without requiring the full type.
+#[derive(Debug, Clone, Deserialize)]
+struct RefreshSuccess {
+    access_token: String,
+    refresh_token: String,
+    #[allow(dead_code)] // Part of the wire contract; not persisted separately.
+    token_type: String,
+    expires_in: u32,
+    #[serde(default)]
+    user: Option<UserInfo>,
+}
+
+/// Manages the lifecycle of a set of [`AuthTokens`]: proactive refresh,
+/// persistence, and single-flight coordination.
+#[allow(dead_code)] // Wired into the authenticated request path separately.
+pub struct TokenManager {
+    client: reqwest::Client,
+    base_url: String,
+    /// Current tokens, shared across request tasks. `None` once re-auth is
+    /// required.
+    tokens: Mutex<Option<AuthTokens>>,
+    /// Where the tokens are persisted (auth.json).
+    store_path: PathBuf,
+    /// Seconds before expiry at which a refresh is triggered.
+    skew_secs: u64,
+    /// Single-flight guard so concurrent callers that all see expiry fire one
+    /// refresh and the rest await its result.
+    refresh_lock: tokio::sync::Mutex<()>,
+    logger: Option<Arc<DebugLogger>>,
+}
+
+#[allow(dead_code)] // Public API consumed by the authenticated request path.
+impl TokenManager {
+    /// Create a manager seeded with the currently-stored tokens (if any).
+    pub fn new(
+        base_url: String,
+        store_path: PathBuf,
+        tokens: Option<AuthTokens>,
+        logger: Option<Arc<DebugLogger>>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            tokens: Mutex::new(tokens),
+            store_path,
+            skew_secs: DEFAULT_REFRESH_SKEW_SECS,
+            refresh_lock: tokio::sync::Mutex::new(()),
+            logger,
+        }
+    }
+
+    /// Override the refresh skew window (seconds before `expires_at`).
+    pub fn with_skew(mut self, skew_secs: u64) -> Self {
+        self.skew_secs = skew_secs;
+        self
+    }
+
+    /// Return a valid access token, refreshing first if it is within the skew
+    /// window of expiring.
+    pub async fn valid_access_token(&self) -> Result<String, TokenError> {
+        if !self.is_expiring() {
+            return self.current_access_token();
+        }
+
+        // Single-flight: only the first caller past this lock performs the
+        // network refresh; the rest wait and then observe the swapped-in token.
+        let _guard = self.refresh_lock.lock().await;
+        if !self.is_expiring() {
+            return self.current_access_token();
+        }
+
+        self.refresh().await?;
+        self.current_access_token()
+    }
+
+    /// Whether the stored access token is missing or within the skew window of
+    /// its `expires_at`. Tokens with no known expiry are treated as valid.
+    fn is_expiring(&self) -> bool {
+        let tokens = self.tokens.lock().unwrap();
+        match tokens.as_ref() {
+            None => true,
+            Some(t) => match t.expires_at {
+                None => false,
+                Some(expires_at) => now_secs() + self.skew_secs >= expires_at,
+            },
+        }
+    }
+
+    /// Snapshot the current access token, or require re-auth if none is stored.
+    fn current_access_token(&self) -> Result<String, TokenError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|t| t.access_token.expose_secret().to_string())
+            .ok_or(TokenError::ReauthRequired)
+    }
+
+    /// Exchange the stored refresh token for a fresh token set.
+    ///
+    /// On an `invalid_grant` / `400` the stored tokens are cleared and
+    /// [`TokenError::ReauthRequired`] is returned so the UI can restart the
+    /// device flow.
+    async fn refresh(&self) -> Result<(), TokenError> {
+        let refresh_token = {
+            let tokens = self.tokens.lock().unwrap();
+            match tokens
+                .as_ref()
+                .and_then(|t| t.refresh_token.as_ref())
+                .map(|t| t.expose_secret().to_string())
+            {
+                Some(rt) => rt,
+                None => return Err(TokenError::ReauthRequired),
+            }
+        };
+
+        if let Some(logger) = &self.logger {
+            logger.info("Refreshing access token before expiry".to_string());
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/api/auth/refresh", self.base_url))
+            .json(&serde_json::json!({ "refresh_token": refresh_token }))
+            .send()
+            .await
+            .map_err(|e| TokenError::Network(format!("Network error: {}", e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::BAD_REQUEST || status == reqwest::StatusCode::UNAUTHORIZED
+        {
+            // The refresh token is no longer valid: wipe local state and force a
+            // fresh device-flow login.
+            self.clear();
+            return Err(TokenError::ReauthRequired);
+        }
+        if !status.is_success() {
+            return Err(TokenError::Network(format!("Server error: {}", status)));
+        }
+
+        let success: RefreshSuccess = response
+            .json()
+            .await
+            .map_err(|e| TokenError::Network(format!("Failed to parse response: {}", e)))?;
+
+        let new_tokens = AuthTokens {
+            access_token: SecretString::new(success.access_token),
+            refresh_token: Some(SecretString::new(success.refresh_token)),
+            expires_at: Some(now_secs() + success.expires_in as u64),
+            user: success.user.map(|u| UserData {
+                id: Some(u.id),
+                username: u.username,
+                avatar_url: u.avatar_url,
+            }),
+        };
+
+        self.swap(new_tokens);
+        Ok(())
+    }
+
+    /// Atomically replace the in-memory tokens and persist them to disk.
+    fn swap(&self, new_tokens: AuthTokens) {
+        self.persist(&new_tokens);
+        *self.tokens.lock().unwrap() = Some(new_tokens);
+    }
+
+    /// Drop the in-memory tokens and remove the persisted copy.
+    fn clear(&self) {
+        *self.tokens.lock().unwrap() = None;
+        if self.store_path.exists() {
+            if let Err(e) = std::fs::remove_file(&self.store_path) {
+                if let Some(logger) = &self.logger {
+                    logger.warn(format!("Failed to clear auth tokens: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Encrypt and persist the tokens, logging but not failing on error.
+    fn persist(&self, tokens: &AuthTokens) {
+        let result = crate::token_store::SecureTokenStore::open(self.store_path.clone())
+            .and_then(|store| store.save(tokens));
+        if let Err(e) = result {
+            if let Some(logger) = &self.logger {
+                logger.error(format!("Failed to persist auth tokens: {}", e));
+            }
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tokens_expiring_in(secs: u64) -> AuthTokens {
+        AuthTokens {
+            access_token: SecretString::new("access"),
+            refresh_token: Some(SecretString::new("refresh")),
+            expires_at: Some(now_secs() + secs),
+            user: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_tokens_requires_reauth() {
+        let dir = TempDir::new().unwrap();
+        let manager = TokenManager::new(
+            "https://example.com".to_string(),
+            dir.path().join("auth.json"),
+            None,
+            None,
+        );
+        assert_eq!(
+            manager.valid_access_token().await,
+            Err(TokenError::ReauthRequired)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_valid_token_returned_without_refresh() {
+        let dir = TempDir::new().unwrap();
+        // Far from expiry: returned as-is, no network touched.
+        let manager = TokenManager::new(
+            "https://example.com".to_string(),
+            dir.path().join("auth.json"),
+            Some(tokens_expiring_in(3600)),
+            None,
+        );
+        assert_eq!(manager.valid_access_token().await.unwrap(), "access");
+    }
+
+    #[test]
+    fn test_is_expiring_respects_skew() {
+        let dir = TempDir::new().unwrap();
+        let manager = TokenManager::new(
+            "https://example.com".to_string(),
+            dir.path().join("auth.json"),
+            Some(tokens_expiring_in(30)),
+            None,
+        )
+        .with_skew(60);
+        // 30s to expiry is inside the 60s skew window.
+        assert!(manager.is_expiring());
+    }
+
+    #[test]
+    fn test_clear_removes_persisted_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("auth.json");
+        let manager = TokenManager::new(
+            "https://example.com".to_string(),
+            path.clone(),
+            Some(tokens_expiring_in(3600)),
+            None,
+        );
+        manager.persist(&tokens_expiring_in(3600));
+        assert!(path.exists());
+        manager.clear();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,340 @@
+//! Encrypted-at-rest storage for [`AuthTokens`].
+//!
+//! Tokens used to serialize straight to plaintext JSON on disk. This module
+//! encrypts them with AES-256-GCM under a 256-bit key held in the OS keychain
+//! (with a `0600` key file as a fallback when no keychain is available). Each
+//! write uses a fresh random 96-bit nonce and persists
+//! `base64(nonce || ciphertext || tag)`; decryption reverses that and fails
+//! loudly if the authentication tag does not verify.
+
+use crate::types::AuthTokens;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use std::path::PathBuf;
+
+/// Service/account identifiers for the keychain entry holding the master key.
+const KEYCHAIN_SERVICE: &str = "ladder-legends-uploader";
+const KEYCHAIN_ACCOUNT: &str = "token-store-key";
+/// Filename of the fallback key file (stored alongside the config).
+const KEY_FILE_NAME: &str = "token-store.key";
+/// AES-256-GCM nonce length in bytes (96 bits).
+const NONCE_LEN: usize = 12;
+
+/// Reads and writes the encrypted `auth.json` token blob.
+pub struct SecureTokenStore {
+    /// Path of the encrypted token blob (e.g. `auth.json`).
+    path: PathBuf,
+    /// The 256-bit AES key material.
+    key: [u8; 32],
+}
+
+impl SecureTokenStore {
+    /// Open the store at `path`, loading the master key from the OS keychain or
+    /// the fallback key file, generating a fresh key on first run.
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let key = load_or_create_key(&path)?;
+        Ok(Self { path, key })
+    }
+
+    /// Encrypt `tokens` and write them atomically to disk.
+    pub fn save(&self, tokens: &AuthTokens) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(tokens)
+            .map_err(|e| format!("Failed to serialize auth tokens: {}", e))?;
+        let blob = encrypt(&self.key, &plaintext)?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        // Temp-file-then-rename so a reader never sees a half-written blob.
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, blob.as_bytes())
+            .map_err(|e| format!("Failed to write auth tokens: {}", e))?;
+        std::fs::rename(&temp, &self.path)
+            .map_err(|e| format!("Failed to persist auth tokens: {}", e))?;
+        Ok(())
+    }
+
+    /// Load and decrypt the stored tokens, or `None` if nothing is stored.
+    pub fn load(&self) -> Result<Option<AuthTokens>, String> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let blob = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read auth tokens: {}", e))?;
+
+        // The happy path: an encrypted envelope we can decrypt.
+        if let Ok(plaintext) = decrypt(&self.key, blob.trim()) {
+            let tokens = serde_json::from_slice(&plaintext)
+                .map_err(|e| format!("Failed to parse auth tokens: {}", e))?;
+            return Ok(Some(tokens));
+        }
+
+        // First run after the upgrade: the file may still be a legacy plaintext
+        // `AuthTokens` JSON written before encryption existed. Migrate it in
+        // place by re-saving under the keyring-sealed key, then return it.
+        if let Ok(tokens) = serde_json::from_str::<AuthTokens>(&blob) {
+            self.save(&tokens)?;
+            return Ok(Some(tokens));
+        }
+
+        Err("Failed to decrypt auth tokens (authentication tag mismatch)".to_string())
+    }
+
+    /// Remove the stored tokens and the keyring-held master key, if any.
+    pub fn clear(&self) -> Result<(), String> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)
+                .map_err(|e| format!("Failed to delete auth tokens: {}", e))?;
+        }
+        // Drop the master key from the keychain too so a logout doesn't leave
+        // the sealing key behind; a missing entry or absent backend is not an
+        // error here. The `0600` fallback key file is shared with other sealed
+        // blobs, so it is intentionally left in place.
+        let _ = keychain_delete();
+        Ok(())
+    }
+}
+
+/// Encrypted-at-rest storage for an arbitrary named secret blob.
+///
+/// Shares the same master-key resolution ([`load_or_create_key`]) and
+/// AES-256-GCM envelope as [`SecureTokenStore`], so device-private material
+/// (the ed25519 signing key) lives alongside the auth tokens under the same
+/// keychain-sealed key rather than inventing a second key hierarchy.
+pub struct SecureBlobStore {
+    path: PathBuf,
+    key: [u8; 32],
+}
+
+impl SecureBlobStore {
+    /// Open the store at `path`, loading (or creating) the shared master key.
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let key = load_or_create_key(&path)?;
+        Ok(Self { path, key })
+    }
+
+    /// Encrypt `bytes` and write them atomically to disk.
+    pub fn save(&self, bytes: &[u8]) -> Result<(), String> {
+        let blob = encrypt(&self.key, bytes)?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, blob.as_bytes())
+            .map_err(|e| format!("Failed to write secret blob: {}", e))?;
+        std::fs::rename(&temp, &self.path)
+            .map_err(|e| format!("Failed to persist secret blob: {}", e))?;
+        Ok(())
+    }
+
+    /// Load and decrypt the stored bytes, or `None` if nothing is stored.
+    pub fn load(&self) -> Result<Option<Vec<u8>>, String> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let blob = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read secret blob: {}", e))?;
+        Ok(Some(decrypt(&self.key, blob.trim())?))
+    }
+}
+
+/// Encrypt `plaintext`, returning `base64(nonce || ciphertext || tag)`.
+fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<String, String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "Failed to encrypt auth tokens".to_string())?;
+
+    let mut combined = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    combined.extend_from_slice(nonce.as_slice());
+    combined.extend_from_slice(&ciphertext);
+    Ok(base64::engine::general_purpose::STANDARD.encode(combined))
+}
+
+/// Reverse [`encrypt`]. Fails loudly if the authentication tag does not verify.
+fn decrypt(key: &[u8; 32], blob: &str) -> Result<Vec<u8>, String> {
+    let combined = base64::engine::general_purpose::STANDARD
+        .decode(blob)
+        .map_err(|e| format!("Failed to decode auth token blob: {}", e))?;
+    if combined.len() < NONCE_LEN {
+        return Err("Auth token blob is truncated".to_string());
+    }
+    let (nonce_bytes, ciphertext) = combined.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| "Failed to decrypt auth tokens (authentication tag mismatch)".to_string())
+}
+
+/// Load the 256-bit master key, generating and persisting a new one on first
+/// run. Prefers the OS keychain and falls back to a `0600` key file.
+fn load_or_create_key(token_path: &PathBuf) -> Result<[u8; 32], String> {
+    // A keychain read error (no backend, locked keyring) is not fatal: fall
+    // through to the key file rather than refusing to start.
+    if let Ok(Some(key)) = keychain_get() {
+        return Ok(key);
+    }
+
+    let key_file = token_path
+        .parent()
+        .map(|p| p.join(KEY_FILE_NAME))
+        .ok_or("Could not determine key file directory")?;
+    if let Some(key) = read_key_file(&key_file)? {
+        return Ok(key);
+    }
+
+    // First run: generate a fresh random key and persist it.
+    let mut key = [0u8; 32];
+    use aes_gcm::aead::rand_core::RngCore;
+    OsRng.fill_bytes(&mut key);
+
+    if keychain_set(&key).is_err() {
+        write_key_file(&key_file, &key)?;
+    }
+    Ok(key)
+}
+
+/// Fetch the master key from the OS keychain, if present.
+fn keychain_get() -> Result<Option<[u8; 32]>, String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))?;
+    match entry.get_password() {
+        Ok(encoded) => decode_key(&encoded).map(Some),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read key from keychain: {}", e)),
+    }
+}
+
+/// Store the master key in the OS keychain.
+fn keychain_set(key: &[u8; 32]) -> Result<(), String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(key);
+    entry
+        .set_password(&encoded)
+        .map_err(|e| format!("Failed to store key in keychain: {}", e))
+}
+
+/// Delete the master key from the OS keychain, if present. A missing entry is
+/// treated as success so logout is idempotent.
+fn keychain_delete() -> Result<(), String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))?;
+    match entry.delete_credential() {
+        Ok(()) => Ok(()),
+        Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete key from keychain: {}", e)),
+    }
+}
+
+/// Read the fallback key file, if it exists.
+fn read_key_file(path: &PathBuf) -> Result<Option<[u8; 32]>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let encoded = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read key file: {}", e))?;
+    decode_key(encoded.trim()).map(Some)
+}
+
+/// Write the fallback key file with `0600` permissions on Unix.
+fn write_key_file(path: &PathBuf, key: &[u8; 32]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create key directory: {}", e))?;
+    }
+    let encoded = base64::engine::general_purpose::STANDARD.encode(key);
+    std::fs::write(path, encoded).map_err(|e| format!("Failed to write key file: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to set key file permissions: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Decode a base64-encoded 256-bit key.
+fn decode_key(encoded: &str) -> Result<[u8; 32], String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Failed to decode key: {}", e))?;
+    bytes
+        .try_into()
+        .map_err(|_| "Key material is not 256 bits".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SecretString;
+
+    fn sample_tokens() -> AuthTokens {
+        AuthTokens {
+            access_token: SecretString::new("access-token"),
+            refresh_token: Some(SecretString::new("refresh-token")),
+            expires_at: Some(1234567890),
+            user: None,
+        }
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let key = [7u8; 32];
+        let plaintext = b"hello tokens";
+        let blob = encrypt(&key, plaintext).unwrap();
+        let recovered = decrypt(&key, &blob).unwrap();
+        assert_eq!(recovered, plaintext);
+    }
+
+    #[test]
+    fn test_ciphertext_is_not_plaintext() {
+        let key = [3u8; 32];
+        let blob = encrypt(&key, b"super-secret").unwrap();
+        assert!(!blob.contains("super-secret"));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_tampered_blob() {
+        let key = [9u8; 32];
+        let blob = encrypt(&key, b"payload").unwrap();
+        let mut raw = base64::engine::general_purpose::STANDARD
+            .decode(&blob)
+            .unwrap();
+        // Flip a ciphertext byte so the auth tag no longer verifies.
+        let last = raw.len() - 1;
+        raw[last] ^= 0xff;
+        let tampered = base64::engine::general_purpose::STANDARD.encode(raw);
+        assert!(decrypt(&key, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key() {
+        let blob = encrypt(&[1u8; 32], b"payload").unwrap();
+        assert!(decrypt(&[2u8; 32], &blob).is_err());
+    }
+
+    #[test]
+    fn test_store_save_load_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = SecureTokenStore {
+            path: dir.path().join("auth.json"),
+            key: [5u8; 32],
+        };
+        store.save(&sample_tokens()).unwrap();
+
+        // On-disk blob must not contain the plaintext tokens.
+        let raw = std::fs::read_to_string(&store.path).unwrap();
+        assert!(!raw.contains("access-token"));
+
+        let loaded = store.load().unwrap().unwrap();
+        assert_eq!(loaded.access_token.expose_secret(), "access-token");
+        store.clear().unwrap();
+        assert!(store.load().unwrap().is_none());
+    }
+}
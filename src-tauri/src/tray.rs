@@ -0,0 +1,158 @@
+//! System-tray status integration.
+//!
+//! Keeps the tray icon and tooltip in sync with the upload manager's live
+//! state and drives the pause/resume control. The tray handle itself lives in
+//! [`AppStateManager`] so it survives past `setup()` without being forgotten.
+
+use std::sync::atomic::Ordering;
+
+use tauri::image::Image;
+use tauri::{AppHandle, Manager};
+
+use crate::upload_manager::{UploadManagerState, UploadStatus};
+use crate::AppStateManager;
+
+/// The three visual states the tray icon can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayStatus {
+    /// Authenticated and watching, but nothing in flight.
+    Idle,
+    /// Actively uploading one or more replays.
+    Active,
+    /// The most recent upload failed.
+    Error,
+}
+
+impl TrayStatus {
+    /// Solid RGBA tint used to render the status icon.
+    fn tint(self) -> [u8; 4] {
+        match self {
+            TrayStatus::Idle => [0x9e, 0x9e, 0x9e, 0xff],
+            TrayStatus::Active => [0x3c, 0xb3, 0x71, 0xff],
+            TrayStatus::Error => [0xd0, 0x45, 0x3a, 0xff],
+        }
+    }
+}
+
+/// Render a small solid-colour status icon for the given state.
+///
+/// The image is built from raw RGBA at runtime so no extra icon assets need to
+/// ship in the bundle; the colour alone communicates idle/active/error.
+fn status_icon(status: TrayStatus) -> Image<'static> {
+    const SIZE: u32 = 32;
+    let [r, g, b, a] = status.tint();
+    let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for _ in 0..(SIZE * SIZE) {
+        rgba.extend_from_slice(&[r, g, b, a]);
+    }
+    Image::new_owned(rgba, SIZE, SIZE)
+}
+
+/// Pick the icon status from the current upload state.
+fn status_for(state: &UploadManagerState) -> TrayStatus {
+    match state.current_upload {
+        Some(UploadStatus::Uploading { .. }) | Some(UploadStatus::Pending { .. }) => {
+            TrayStatus::Active
+        }
+        Some(UploadStatus::Failed { .. }) => TrayStatus::Error,
+        _ => TrayStatus::Idle,
+    }
+}
+
+/// Compose the tooltip text shown on hover.
+pub fn tooltip_for(state: &UploadManagerState, paused: bool) -> String {
+    if paused {
+        return "Ladder Legends — uploads paused".to_string();
+    }
+
+    match state.current_upload {
+        Some(UploadStatus::Uploading { .. }) => {
+            let done = state.total_uploaded;
+            let total = done + state.pending_count;
+            format!("Uploading {} of {}…", done + 1, total)
+        }
+        _ if state.is_watching => format!("Watching — {} uploaded", state.total_uploaded),
+        _ => format!("Idle — {} uploaded", state.total_uploaded),
+    }
+}
+
+/// Refresh the tray icon and tooltip from the app's current upload state.
+///
+/// Safe to call from any command or event handler; it is a no-op until the
+/// tray has been created and stored in [`AppStateManager`].
+pub fn refresh(app: &AppHandle) {
+    let manager = app.state::<AppStateManager>();
+    let paused = manager.uploads_paused.load(Ordering::Relaxed);
+
+    let state = {
+        let upload_manager = manager
+            .upload_manager
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        upload_manager.as_ref().map(|m| m.get_state())
+    };
+    let state = state.unwrap_or(UploadManagerState {
+        total_uploaded: 0,
+        current_upload: None,
+        pending_count: 0,
+        is_watching: false,
+        bytes_total: 0,
+        bytes_uploaded: 0,
+        last_skipped: Vec::new(),
+    });
+
+    let status = if paused { TrayStatus::Idle } else { status_for(&state) };
+    let tooltip = tooltip_for(&state, paused);
+
+    let tray = manager.tray_icon.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(tray) = tray.as_ref() {
+        if let Err(e) = tray.set_tooltip(Some(tooltip.as_str())) {
+            manager.debug_logger.warn(format!("Failed to set tray tooltip: {}", e));
+        }
+        if let Err(e) = tray.set_icon(Some(status_icon(status))) {
+            manager.debug_logger.warn(format!("Failed to set tray icon: {}", e));
+        }
+    }
+}
+
+/// Flip the paused flag, relabel the menu item, persist the queue's paused
+/// state, emit the change to the webview, and refresh the tray. Returns the
+/// new paused value.
+pub fn toggle_paused(app: &AppHandle) -> bool {
+    use tauri::Emitter;
+
+    let manager = app.state::<AppStateManager>();
+    let new_paused = !manager.uploads_paused.load(Ordering::Relaxed);
+    manager.uploads_paused.store(new_paused, Ordering::Relaxed);
+
+    {
+        let item = manager.tray_pause_item.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(item) = item.as_ref() {
+            let label = if new_paused { "Resume uploads" } else { "Pause uploads" };
+            if let Err(e) = item.set_text(label) {
+                manager.debug_logger.warn(format!("Failed to relabel pause item: {}", e));
+            }
+        }
+    }
+
+    // Mirror the flag into the durable queue so a restart keeps it paused.
+    let app_for_queue = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Ok(mut queue) = crate::upload_queue::UploadQueue::load().await {
+            queue.paused = new_paused;
+            let _ = queue.save().await;
+        }
+        let _ = app_for_queue;
+    });
+
+    manager
+        .debug_logger
+        .info(format!("Uploads {} from tray", if new_paused { "paused" } else { "resumed" }));
+
+    if let Err(e) = app.emit("uploads-paused-changed", new_paused) {
+        manager.debug_logger.warn(format!("Failed to emit uploads-paused-changed: {}", e));
+    }
+
+    refresh(app);
+    new_paused
+}
@@ -3,6 +3,68 @@
 //! This module contains the data structures used for storing
 //! authentication tokens and user profile information.
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
+
+/// A string holding credential material (OAuth access/refresh tokens).
+///
+/// The inner value is zeroized when the container is dropped and is never
+/// rendered through `Debug`, so tokens cannot leak into the debug log exported
+/// by `export_debug_log`. On the wire it (de)serializes as a plain JSON string,
+/// so the storage format is unchanged; the encrypted-at-rest wrapping lives in
+/// [`crate::token_store`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Wrap a plaintext secret.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Borrow the underlying secret. Call sites should keep the exposed value
+    /// as short-lived as possible and avoid logging it.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl std::fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretString(\"[redacted]\")")
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(String::deserialize(deserializer)?))
+    }
+}
+
 /// User profile data from Discord
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct UserData {
@@ -15,8 +77,8 @@ pub struct UserData {
 /// Authentication tokens and associated user data
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AuthTokens {
-    pub access_token: String,
-    pub refresh_token: Option<String>,
+    pub access_token: SecretString,
+    pub refresh_token: Option<SecretString>,
     pub expires_at: Option<u64>,
     pub user: Option<UserData>,
 }
@@ -59,8 +121,8 @@ mod tests {
     #[test]
     fn test_auth_tokens_serialize() {
         let auth_tokens = AuthTokens {
-            access_token: "test-access-token".to_string(),
-            refresh_token: Some("test-refresh-token".to_string()),
+            access_token: SecretString::new("test-access-token"),
+            refresh_token: Some(SecretString::new("test-refresh-token")),
             expires_at: Some(1234567890),
             user: Some(UserData {
                 id: None,
@@ -89,8 +151,11 @@ mod tests {
         }"#;
 
         let auth_tokens: AuthTokens = serde_json::from_str(json).unwrap();
-        assert_eq!(auth_tokens.access_token, "test-access-token");
-        assert_eq!(auth_tokens.refresh_token, Some("test-refresh-token".to_string()));
+        assert_eq!(auth_tokens.access_token.expose_secret(), "test-access-token");
+        assert_eq!(
+            auth_tokens.refresh_token.as_ref().map(|t| t.expose_secret()),
+            Some("test-refresh-token")
+        );
         assert_eq!(auth_tokens.expires_at, Some(1234567890));
         assert!(auth_tokens.user.is_some());
 
@@ -109,7 +174,7 @@ mod tests {
         }"#;
 
         let auth_tokens: AuthTokens = serde_json::from_str(json).unwrap();
-        assert_eq!(auth_tokens.access_token, "test-access-token");
+        assert_eq!(auth_tokens.access_token.expose_secret(), "test-access-token");
         assert_eq!(auth_tokens.refresh_token, None);
         assert_eq!(auth_tokens.expires_at, None);
         assert_eq!(auth_tokens.user, None);
@@ -118,8 +183,8 @@ mod tests {
     #[test]
     fn test_auth_tokens_clone() {
         let auth_tokens = AuthTokens {
-            access_token: "test-access-token".to_string(),
-            refresh_token: Some("test-refresh-token".to_string()),
+            access_token: SecretString::new("test-access-token"),
+            refresh_token: Some(SecretString::new("test-refresh-token")),
             expires_at: Some(1234567890),
             user: Some(UserData {
                 id: None,
@@ -135,6 +200,23 @@ mod tests {
         assert_eq!(auth_tokens.user.as_ref().unwrap().username, cloned.user.as_ref().unwrap().username);
     }
 
+    #[test]
+    fn test_secret_string_redacts_debug() {
+        let secret = SecretString::new("super-secret-token");
+        let rendered = format!("{:?}", secret);
+        assert!(!rendered.contains("super-secret-token"));
+        assert!(rendered.contains("redacted"));
+    }
+
+    #[test]
+    fn test_secret_string_roundtrips_as_plain_string() {
+        let secret = SecretString::new("abc123");
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"abc123\"");
+        let back: SecretString = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.expose_secret(), "abc123");
+    }
+
     #[test]
     fn test_user_data_clone() {
         let user_data = UserData {
@@ -1,9 +1,12 @@
+use crate::replay_parser;
 use crate::replay_tracker::{ReplayTracker, ReplayFileInfo};
 use crate::replay_uploader::ReplayUploader;
 use crate::debug_logger::DebugLogger;
-use crate::services::{ReplayScanner, UploadExecutor};
+use crate::services::{ReplayScanner, SkippedReplay, UploadExecutor, UploadPolicy, DEFAULT_UPLOAD_CONCURRENCY};
+use crate::upload_queue::{QueueWorker, UploadQueue};
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use notify::{Watcher, RecursiveMode, Event};
@@ -19,6 +22,9 @@ pub fn is_sc2_replay(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Upper bound on replays uploaded per automatic background scan cycle.
+pub const SCHEDULER_SCAN_LIMIT: usize = 1000;
+
 /// Get the delay in milliseconds to wait before processing a new replay file.
 /// Windows needs more time due to antivirus scanning and file locking.
 #[inline]
@@ -29,73 +35,158 @@ pub const fn get_file_processing_delay_ms() -> u64 {
     { 500 }
 }
 
-/// Represents a group of replays with the same game type and player name
+/// Number of times a replay may fail [`replay_parser::validate_replay`] before
+/// the watch loop gives up on it and logs an error instead of retrying again.
+const MAX_VALIDATION_ATTEMPTS: u32 = 5;
+
+/// Confirm a replay file has finished being written before it is dispatched.
+///
+/// SC2 emits several modify events while flushing a replay, and on Windows an
+/// antivirus scanner may briefly hold the file open. This reads the size and
+/// mtime twice ~200ms apart and reports the file stable only when both reads
+/// agree, the file is non-empty, and it can be opened for reading.
+async fn is_file_stable(path: &Path) -> bool {
+    fn signature(path: &Path) -> Option<(u64, std::time::SystemTime)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.len(), metadata.modified().ok()?))
+    }
+
+    let Some(first) = signature(path) else { return false };
+    if first.0 == 0 {
+        return false;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let Some(second) = signature(path) else { return false };
+
+    first == second && std::fs::File::open(path).is_ok()
+}
+
+/// Grouping granularity for [`group_replays_by_type_and_player`].
+///
+/// Each variant is a superset of the previous one: callers that want
+/// per-matchup or per-MMR-bracket stats opt in without changing how the
+/// simple (game_type, player_name) grouping behaves for everyone else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupingKey {
+    /// Group by (game_type, player_name) only.
+    TypeAndPlayer,
+    /// Also split by race matchup (e.g. `"ZvP"`).
+    TypePlayerMatchup,
+    /// Also split by race matchup and MMR bucket.
+    TypePlayerMatchupMmr,
+}
+
+/// Round an MMR value to the nearest 250, e.g. for bucketing stats.
+pub fn mmr_bucket(mmr: i32) -> i32 {
+    (mmr as f64 / 250.0).round() as i32 * 250
+}
+
+/// Per-replay data needed to group it: its file info, coarse game type, the
+/// user's name in that game, the derived 1v1 matchup (if any), and the
+/// user's MMR bucket at game start (if known).
+pub type ReplayGroupingEntry = (ReplayFileInfo, String, String, Option<String>, Option<i32>);
+
+/// Represents a group of replays sharing the same grouping key
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ReplayGroup {
     pub game_type: String,
     pub player_name: String,
+    /// Race matchup (e.g. `"ZvP"`), present when `grouping` included it.
+    pub matchup: Option<String>,
+    /// MMR rounded to the nearest 250, present when `grouping` included it.
+    pub mmr_bucket: Option<i32>,
     pub hashes: Vec<String>,
 }
 
-/// Group replay hashes by (game_type, player_name) for batch uploading
-/// Returns groups sorted by game_type then player_name
+/// Group replay hashes for batch uploading, at the requested [`GroupingKey`]
+/// granularity. Returns groups sorted by game_type, then player_name, then
+/// matchup, then ascending MMR bucket.
 pub fn group_replays_by_type_and_player(
     hashes: &[String],
-    replay_map: &HashMap<String, (ReplayFileInfo, String, String)>,
+    replay_map: &HashMap<String, ReplayGroupingEntry>,
+    grouping: GroupingKey,
 ) -> Vec<ReplayGroup> {
-    let mut groups: HashMap<(String, String), Vec<String>> = HashMap::new();
+    let mut groups: HashMap<(String, String, Option<String>, Option<i32>), Vec<String>> = HashMap::new();
 
     for hash in hashes {
-        if let Some((_, game_type_str, player_name)) = replay_map.get(hash) {
-            groups.entry((game_type_str.clone(), player_name.clone()))
+        if let Some((_, game_type_str, player_name, matchup, mmr_bucket)) = replay_map.get(hash) {
+            let matchup = match grouping {
+                GroupingKey::TypeAndPlayer => None,
+                GroupingKey::TypePlayerMatchup | GroupingKey::TypePlayerMatchupMmr => matchup.clone(),
+            };
+            let mmr_bucket = match grouping {
+                GroupingKey::TypeAndPlayer | GroupingKey::TypePlayerMatchup => None,
+                GroupingKey::TypePlayerMatchupMmr => *mmr_bucket,
+            };
+            groups.entry((game_type_str.clone(), player_name.clone(), matchup, mmr_bucket))
                 .or_default()
                 .push(hash.clone());
         }
     }
 
-    // Sort groups by game_type then player_name for consistent ordering
+    // Sort groups by game_type, then player_name, then matchup, then MMR bucket.
     let mut sorted_groups: Vec<_> = groups.into_iter()
-        .map(|((game_type, player_name), hashes)| ReplayGroup {
+        .map(|((game_type, player_name, matchup, mmr_bucket), hashes)| ReplayGroup {
             game_type,
             player_name,
+            matchup,
+            mmr_bucket,
             hashes,
         })
         .collect();
 
     sorted_groups.sort_by(|a, b| {
-        match a.game_type.cmp(&b.game_type) {
-            std::cmp::Ordering::Equal => a.player_name.cmp(&b.player_name),
-            other => other,
-        }
+        a.game_type.cmp(&b.game_type)
+            .then_with(|| a.player_name.cmp(&b.player_name))
+            .then_with(|| a.matchup.cmp(&b.matchup))
+            .then_with(|| a.mmr_bucket.cmp(&b.mmr_bucket))
     });
 
     sorted_groups
 }
 
-/// Player statistics for user detection
+/// Player statistics for user detection, keyed by the stable account handle
+/// rather than the display name so a mid-history rename doesn't split one
+/// human into two candidates.
 #[derive(Debug, Clone)]
 struct PlayerStats {
+    handle: String,
+    /// Most-recently-seen display name for this handle.
     name: String,
     frequency: usize,
     co_occurrences: HashMap<String, usize>,
 }
 
-/// Detect likely user player names from replay data using frequency and co-occurrence analysis
+/// A user account detected from scanned replays.
+///
+/// `handle` is the permanent `m_toon`-derived account id used for matching;
+/// `name` is the most-recently-seen display name, for showing to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedPlayer {
+    pub handle: String,
+    pub name: String,
+}
+
+/// Detect likely user accounts from replay data using frequency and co-occurrence analysis
 ///
 /// Algorithm:
-/// 1. Count frequency of each player across all replays
-/// 2. Track co-occurrences (how often players appear together)
+/// 1. Count frequency of each account (by handle) across all replays
+/// 2. Track co-occurrences (how often accounts appear together)
 /// 3. Sort by frequency (descending)
-/// 4. Filter out players who frequently co-occur with higher-frequency players
+/// 4. Filter out accounts who frequently co-occur with higher-frequency accounts
 ///    - These are likely practice partners/teammates, not the user
-/// 5. Return top 1-2 players after filtering
+/// 5. Return top 1-2 accounts after filtering
 ///
 /// # Arguments
-/// * `replays` - List of (replay_path, players) tuples where players is Vec<(name, is_observer)>
+/// * `replays` - List of (replay_path, players) tuples, ordered most-recent-first,
+///   where players is Vec<(handle, name, is_observer)>. The handle is the stable
+///   `m_toon`-derived account id; the name is that account's display name in the
+///   replay it appeared in.
 ///
 /// # Returns
-/// * Vec of detected user player names, sorted by confidence (highest first)
-pub fn detect_user_player_names(replays: &[(String, Vec<(String, bool)>)]) -> Vec<String> {
+/// * Vec of detected accounts, sorted by confidence (highest first). A player
+///   who renamed mid-history still collapses into a single entry, keyed by handle.
+pub fn detect_user_player_names(replays: &[(String, Vec<(String, String, bool)>)]) -> Vec<DetectedPlayer> {
     if replays.is_empty() {
         return Vec::new();
     }
@@ -104,21 +195,24 @@ pub fn detect_user_player_names(replays: &[(String, Vec<(String, bool)>)]) -> Ve
     let mut player_stats: HashMap<String, PlayerStats> = HashMap::new();
 
     for (_replay_path, players) in replays {
-        // Get non-observer player names
-        let active_players: Vec<String> = players.iter()
-            .filter(|(_, is_observer)| !is_observer)
-            .map(|(name, _)| name.clone())
+        // Get non-observer accounts
+        let active_players: Vec<(String, String)> = players.iter()
+            .filter(|(_, _, is_observer)| !is_observer)
+            .map(|(handle, name, _)| (handle.clone(), name.clone()))
             .collect();
 
         if active_players.is_empty() {
             continue;
         }
 
-        // Update frequencies
-        for player in &active_players {
-            player_stats.entry(player.clone())
+        // Update frequencies. `replays` is most-recent-first, so the first time
+        // a handle is seen gives its most-recent display name; later sightings
+        // (older replays) don't overwrite it.
+        for (handle, name) in &active_players {
+            player_stats.entry(handle.clone())
                 .or_insert_with(|| PlayerStats {
-                    name: player.clone(),
+                    handle: handle.clone(),
+                    name: name.clone(),
                     frequency: 0,
                     co_occurrences: HashMap::new(),
                 })
@@ -129,13 +223,13 @@ pub fn detect_user_player_names(replays: &[(String, Vec<(String, bool)>)]) -> Ve
         for i in 0..active_players.len() {
             for j in 0..active_players.len() {
                 if i != j {
-                    let player = &active_players[i];
-                    let other_player = &active_players[j];
+                    let (handle, _) = &active_players[i];
+                    let (other_handle, _) = &active_players[j];
 
-                    player_stats.get_mut(player)
+                    player_stats.get_mut(handle)
                         .unwrap()
                         .co_occurrences
-                        .entry(other_player.clone())
+                        .entry(other_handle.clone())
                         .and_modify(|count| *count += 1)
                         .or_insert(1);
                 }
@@ -155,10 +249,10 @@ pub fn detect_user_player_names(replays: &[(String, Vec<(String, bool)>)]) -> Ve
     const AI_PLAYER_NAMES: &[&str] = &["Computer", "A.I.", "AI", "Bot"];
     sorted_players.retain(|p| !AI_PLAYER_NAMES.iter().any(|ai_name| p.name.eq_ignore_ascii_case(ai_name)));
 
-    // Step 3: Filter out players who frequently co-occur with higher-frequency players
+    // Step 3: Filter out accounts who frequently co-occur with higher-frequency accounts
     // Requirements for user candidates:
     // 1. Must appear in more than 1 game (frequency > 1)
-    // 2. Must NOT frequently co-occur with any higher-frequency player
+    // 2. Must NOT frequently co-occur with any higher-frequency account
     //    (co-occurrence rate > 50% means they're a practice partner/teammate)
     let mut user_candidates = Vec::new();
 
@@ -170,10 +264,10 @@ pub fn detect_user_player_names(replays: &[(String, Vec<(String, bool)>)]) -> Ve
 
         let mut is_user_candidate = true;
 
-        // Requirement 2: Check if this player frequently co-occurs with any higher-frequency player
+        // Requirement 2: Check if this account frequently co-occurs with any higher-frequency account
         for higher_freq_player in &sorted_players[0..idx] {
-            if let Some(&co_occurrence_count) = player.co_occurrences.get(&higher_freq_player.name) {
-                // If this player appears with a higher-frequency player in >50% of their games,
+            if let Some(&co_occurrence_count) = player.co_occurrences.get(&higher_freq_player.handle) {
+                // If this account appears with a higher-frequency account in >50% of their games,
                 // they're likely a practice partner/teammate, not the user
                 let co_occurrence_rate = co_occurrence_count as f64 / player.frequency as f64;
                 if co_occurrence_rate > 0.5 {
@@ -184,13 +278,59 @@ pub fn detect_user_player_names(replays: &[(String, Vec<(String, bool)>)]) -> Ve
         }
 
         if is_user_candidate {
-            user_candidates.push(player.name.clone());
+            user_candidates.push(DetectedPlayer {
+                handle: player.handle.clone(),
+                name: player.name.clone(),
+            });
         }
     }
 
     user_candidates
 }
 
+/// Fetch player names to filter replays by from user settings, falling back
+/// to an empty list (which tells [`crate::services::ReplayScanner`] to detect
+/// the user from the replays themselves) if settings can't be fetched.
+///
+/// Shared between [`UploadManager::scan_and_upload`] and the tracked job
+/// subsystem (`crate::services::job_manager`) so both filter replays the same
+/// way.
+pub async fn fetch_player_names(uploader: &ReplayUploader, logger: &DebugLogger) -> Vec<String> {
+    logger.info("Fetching user settings for player name filtering".to_string());
+
+    match uploader.get_user_settings().await {
+        Ok(settings) => {
+            let mut names = settings.confirmed_player_names.clone();
+            names.extend(settings.possible_player_names.keys().cloned());
+
+            if names.is_empty() {
+                logger.info("No player names configured yet - will detect from replays".to_string());
+            } else {
+                logger.info(format!(
+                    "Filtering for {} player name(s): {}",
+                    names.len(),
+                    names.join(", ")
+                ));
+            }
+            names
+        }
+        Err(e) => {
+            logger.warn(format!(
+                "Could not fetch user settings: {}, will detect from replays",
+                e
+            ));
+            Vec::new()
+        }
+    }
+}
+
+/// Event name carrying a single replay's [`UploadStatus`] to the frontend.
+pub const UPLOAD_STATUS_EVENT: &str = "upload-status";
+
+/// Emitted once an auto-upload triggered by the folder watcher finishes, so
+/// the UI can toast it instead of only learning about it via a full scan.
+pub const AUTO_UPLOAD_EVENT: &str = "auto-upload-complete";
+
 /// Upload status for a single replay
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "status", rename_all = "lowercase")]
@@ -198,6 +338,9 @@ pub enum UploadStatus {
     Pending { filename: String },
     Uploading { filename: String },
     Completed { filename: String },
+    /// A transient failure was re-queued: the drainer will try again in
+    /// `next_retry_in_secs`, with `attempt` counting attempts made so far.
+    Retrying { filename: String, attempt: u32, next_retry_in_secs: u64 },
     Failed { filename: String, error: String },
 }
 
@@ -208,15 +351,33 @@ pub struct UploadManagerState {
     pub current_upload: Option<UploadStatus>,
     pub pending_count: usize,
     pub is_watching: bool,
+    /// Total bytes across the replays in the batch currently executing (0
+    /// when idle), for a global progress bar alongside the replay count.
+    #[serde(default)]
+    pub bytes_total: u64,
+    /// Bytes uploaded so far within that batch.
+    #[serde(default)]
+    pub bytes_uploaded: u64,
+    /// Every replay (and unreadable folder) skipped by the most recent scan,
+    /// with why, for display alongside the upload results.
+    #[serde(default)]
+    pub last_skipped: Vec<SkippedReplay>,
 }
 
 /// Manages replay uploads and file watching
 pub struct UploadManager {
-    replay_folders: Vec<PathBuf>,
+    replay_folders: Mutex<Vec<PathBuf>>,
     tracker: Arc<Mutex<ReplayTracker>>,
     uploader: Arc<ReplayUploader>,
     state: Arc<Mutex<UploadManagerState>>,
     logger: Arc<DebugLogger>,
+    /// Durable record of which replays have already been uploaded, kept in
+    /// sync as each batch finishes; see [`crate::db::ReplayDb`].
+    db: Arc<crate::db::ReplayDb>,
+    /// Set by [`Self::stop_watching`] and polled by the watcher's debounce
+    /// loop so a running watcher can actually be torn down, rather than just
+    /// having its status flag flipped.
+    watcher_cancelled: Arc<AtomicBool>,
 }
 
 impl UploadManager {
@@ -225,7 +386,9 @@ impl UploadManager {
         replay_folders: Vec<PathBuf>,
         base_url: String,
         access_token: String,
+        proxy_url: Option<String>,
         logger: Arc<DebugLogger>,
+        db: Arc<crate::db::ReplayDb>,
     ) -> Result<Self, String> {
         logger.info(format!("Loading replay tracker for {} folder(s)...", replay_folders.len()));
         for folder in &replay_folders {
@@ -234,20 +397,85 @@ impl UploadManager {
         let tracker = ReplayTracker::load()?;
         logger.info("Replay tracker loaded successfully".to_string());
 
+        // Recover the persisted refresh token and expiry so the uploader can
+        // transparently refresh the access token as it nears expiry. The
+        // command only forwards the access token, so the rest comes from disk.
+        // This is a small one-time startup read, so a synchronous read keeps the
+        // constructor non-async without stalling the runtime in practice.
+        let stored = crate::config_utils::config_file_path("auth.json")
+            .ok()
+            .and_then(|path| crate::token_store::SecureTokenStore::open(path).ok())
+            .and_then(|store| store.load().ok().flatten());
+        let (refresh_token, expires_at) = match stored {
+            Some(tokens) => (
+                tokens
+                    .refresh_token
+                    .map(|t| t.expose_secret().to_string()),
+                tokens.expires_at,
+            ),
+            None => (None, None),
+        };
+
+        let uploader = ReplayUploader::with_auth(
+            base_url,
+            access_token,
+            refresh_token,
+            expires_at,
+            proxy_url,
+            Some(logger.clone()),
+        );
+        // Attach the per-device signer so uploads are signed rather than
+        // bearer-only; a failure to load/create one is logged and degrades
+        // to bearer-only auth rather than blocking startup.
+        let uploader = match crate::device_identity::DeviceSigner::load_or_create() {
+            Ok(signer) => uploader.with_device_signer(Arc::new(signer)),
+            Err(e) => {
+                logger.warn(format!("Failed to load device signer, uploads will be bearer-only: {}", e));
+                uploader
+            }
+        };
+
         Ok(Self {
-            replay_folders,
+            replay_folders: Mutex::new(replay_folders),
             tracker: Arc::new(Mutex::new(tracker)),
-            uploader: Arc::new(ReplayUploader::with_logger(base_url, access_token, Some(logger.clone()))),
+            uploader: Arc::new(uploader),
             state: Arc::new(Mutex::new(UploadManagerState {
                 total_uploaded: 0,
                 current_upload: None,
                 pending_count: 0,
                 is_watching: false,
+                bytes_total: 0,
+                bytes_uploaded: 0,
+                last_skipped: Vec::new(),
             })),
             logger,
+            db,
+            watcher_cancelled: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Record every replay in `succeeded` (from an [`UploadResult`]) into
+    /// [`crate::db::ReplayDb`], deriving `account_id`/`region` from whichever
+    /// watched folder the path falls under via
+    /// [`crate::sc2_detector::folder_account_and_region`]. Best-effort: a
+    /// failure to record is logged and otherwise ignored, since the tracker
+    /// JSON remains the source of truth for what's been uploaded.
+    fn record_uploads(&self, succeeded: &[(String, String)]) {
+        let folders = self.replay_folders();
+        let now = crate::services::hash_cache::modified_time_to_unix_secs(std::time::SystemTime::now());
+
+        for (hash, path) in succeeded {
+            let owning_folder = folders.iter().find(|folder| Path::new(path).starts_with(folder));
+            let (account_id, region) = owning_folder
+                .and_then(|folder| crate::sc2_detector::folder_account_and_region(folder))
+                .unwrap_or_default();
+
+            if let Err(e) = self.db.record_upload(hash, path, &account_id, &region, crate::db::UploadRecordStatus::Uploaded, now) {
+                self.logger.warn(format!("Failed to record upload for {}: {}", path, e));
+            }
+        }
+    }
+
     /// Get current state
     pub fn get_state(&self) -> UploadManagerState {
         // Use unwrap_or_else to recover from poisoned mutex
@@ -256,13 +484,50 @@ impl UploadManager {
         state.clone()
     }
 
+    /// Record the skip list from the most recently completed scan so it's
+    /// visible via [`Self::get_state`] alongside the upload results.
+    fn set_last_skipped(&self, skipped: Vec<SkippedReplay>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.last_skipped = skipped;
+    }
+
+    /// Folders this manager watches, for callers that drive their own scan
+    /// pipeline (e.g. the tracked job subsystem).
+    pub fn replay_folders(&self) -> Vec<PathBuf> {
+        self.replay_folders.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replace the watched folder list, e.g. when the user adds or removes a
+    /// folder. Takes effect for the next scan or watcher (re)start; an
+    /// already-running watcher must be restarted to pick it up.
+    pub fn set_replay_folders(&self, replay_folders: Vec<PathBuf>) {
+        let mut folders = self.replay_folders.lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *folders = replay_folders;
+    }
+
+    /// Shared uploader handle.
+    pub fn uploader(&self) -> Arc<ReplayUploader> {
+        Arc::clone(&self.uploader)
+    }
+
+    /// Snapshot of the current replay tracker, safe to use without holding
+    /// the lock across an `await`.
+    pub fn tracker_snapshot(&self) -> Result<ReplayTracker, String> {
+        self.tracker.lock()
+            .map(|t| t.clone())
+            .map_err(|_| "Failed to lock tracker".to_string())
+    }
+
     /// Scan for new replays and upload them (up to limit)
     /// Uses two-layer deduplication: local tracker + server check
     ///
     /// This method delegates to ReplayScanner and UploadExecutor services
     /// for better separation of concerns and testability.
-    pub async fn scan_and_upload(&self, limit: usize, app: &tauri::AppHandle) -> Result<usize, String> {
-        self.logger.info(format!("Starting scan and upload (limit: {})", limit));
+    pub async fn scan_and_upload(&self, limit: usize, full_rescan: bool, app: &tauri::AppHandle) -> Result<usize, String> {
+        self.logger.info(format!("Starting scan and upload (limit: {}, full_rescan: {})", limit, full_rescan));
 
         // Emit start event
         if let Err(e) = app.emit("upload-start", serde_json::json!({
@@ -271,6 +536,12 @@ impl UploadManager {
             self.logger.warn(format!("Failed to emit upload-start: {}", e));
         }
 
+        // Step 0: Drain any due jobs left over from a previous run (or earlier
+        // failures this session) before scanning for new replays. This is what
+        // makes the queue survive restarts: pending/failed uploads are retried
+        // automatically with backoff rather than forgotten.
+        let retried = self.drain_queue(app).await;
+
         // Step 1: Fetch player names from user settings
         let player_names = self.fetch_player_names().await;
 
@@ -280,13 +551,15 @@ impl UploadManager {
             .clone();
 
         // Step 3: Use ReplayScanner to prepare replays
-        let scanner = ReplayScanner::new(self.replay_folders.clone(), Arc::clone(&self.logger));
+        let scanner = ReplayScanner::new(self.replay_folders(), Arc::clone(&self.logger));
         let scan_result = scanner.scan_and_prepare(
             &tracker,
             &self.uploader,
             player_names,
             limit,
+            full_rescan,
         ).await?;
+        self.set_last_skipped(scan_result.skipped.clone());
 
         // Emit check events
         if let Err(e) = app.emit("upload-checking", serde_json::json!({
@@ -306,11 +579,11 @@ impl UploadManager {
         if scan_result.prepared_replays.is_empty() {
             self.logger.info("No new replays to upload".to_string());
             if let Err(e) = app.emit("upload-complete", serde_json::json!({
-                "count": 0
+                "count": retried
             })) {
                 self.logger.warn(format!("Failed to emit upload-complete: {}", e));
             }
-            return Ok(0);
+            return Ok(retried);
         }
 
         // Step 4: Use UploadExecutor to execute uploads
@@ -321,51 +594,129 @@ impl UploadManager {
             Arc::clone(&self.logger),
         );
 
-        let upload_result = executor.execute(scan_result.prepared_replays, app).await?;
+        let concurrency = crate::app_config::load_app_config(&self.logger)
+            .await
+            .upload_concurrency
+            .unwrap_or(DEFAULT_UPLOAD_CONCURRENCY);
+
+        let upload_result = executor
+            .execute(scan_result.prepared_replays, UploadPolicy::ContinueOnError, concurrency, app)
+            .await?;
+        self.record_uploads(&upload_result.succeeded);
 
+        let uploaded = retried + upload_result.uploaded_count;
         self.logger.info(format!(
-            "Scan and upload complete: {} replays uploaded",
-            upload_result.uploaded_count
+            "Scan and upload complete: {} uploaded ({} retried, {} newly queued for retry)",
+            uploaded, retried, upload_result.enqueued_count
         ));
 
         // Emit completion event
         if let Err(e) = app.emit("upload-complete", serde_json::json!({
-            "count": upload_result.uploaded_count
+            "count": uploaded,
+            "enqueued": upload_result.enqueued_count
         })) {
             self.logger.warn(format!("Failed to emit upload-complete: {}", e));
         }
 
-        Ok(upload_result.uploaded_count)
+        Ok(uploaded)
+    }
+
+    /// Drain the durable upload queue of any jobs whose retry time is due,
+    /// emitting per-item transitions as they are retried. Best-effort: a queue
+    /// that can't be loaded or drained is logged and treated as empty so a
+    /// normal scan still proceeds. Returns how many queued jobs uploaded.
+    async fn drain_queue(&self, app: &tauri::AppHandle) -> usize {
+        let queue = match UploadQueue::load().await {
+            Ok(q) => q,
+            Err(e) => {
+                self.logger.warn(format!("Failed to load upload queue: {}", e));
+                return 0;
+            }
+        };
+        if queue.pending.is_empty() {
+            return 0;
+        }
+
+        self.logger.info(format!("Draining {} pending queued upload(s)", queue.pending.len()));
+        let worker = QueueWorker::new(
+            Arc::new(Mutex::new(queue)),
+            Arc::clone(&self.uploader),
+            Arc::clone(&self.tracker),
+            Arc::clone(&self.logger),
+            Some(app.clone()),
+        );
+        match worker.drain_due().await {
+            Ok(count) => count,
+            Err(e) => {
+                self.logger.warn(format!("Queue drain failed: {}", e));
+                0
+            }
+        }
     }
 
     /// Fetch player names from user settings API
     async fn fetch_player_names(&self) -> Vec<String> {
-        self.logger.info("Fetching user settings for player name filtering".to_string());
+        fetch_player_names(&self.uploader, &self.logger).await
+    }
 
-        match self.uploader.get_user_settings().await {
-            Ok(settings) => {
-                let mut names = settings.confirmed_player_names.clone();
-                names.extend(settings.possible_player_names.keys().cloned());
+    /// Incrementally scan and upload a specific set of settled file paths,
+    /// as reported by the folder watcher. Unlike [`Self::scan_and_upload`],
+    /// this skips the full-folder rescan and recency sort and goes straight
+    /// from the watcher's settled paths into the same filter/hash/check
+    /// pipeline, scoped to just what changed. Emits [`AUTO_UPLOAD_EVENT`] once
+    /// the upload finishes so the UI can toast it.
+    pub async fn upload_paths(&self, paths: Vec<PathBuf>, app: &tauri::AppHandle) -> Result<usize, String> {
+        if paths.is_empty() {
+            return Ok(0);
+        }
+        self.logger.info(format!("Auto-uploading {} settled replay(s)", paths.len()));
 
-                if names.is_empty() {
-                    self.logger.info("No player names configured yet - will detect from replays".to_string());
-                } else {
-                    self.logger.info(format!(
-                        "Filtering for {} player name(s): {}",
-                        names.len(),
-                        names.join(", ")
-                    ));
-                }
-                names
-            }
-            Err(e) => {
-                self.logger.warn(format!(
-                    "Could not fetch user settings: {}, will detect from replays",
-                    e
-                ));
-                Vec::new()
-            }
+        let player_names = self.fetch_player_names().await;
+        let tracker = self.tracker.lock()
+            .map_err(|_| "Failed to lock tracker")?
+            .clone();
+
+        let scanner = ReplayScanner::new(self.replay_folders(), Arc::clone(&self.logger));
+        let scan_result = scanner
+            .scan_and_prepare_for_paths(&tracker, &self.uploader, player_names, paths)
+            .await?;
+        self.set_last_skipped(scan_result.skipped.clone());
+
+        if scan_result.prepared_replays.is_empty() {
+            self.logger.info("Watched replay already uploaded or filtered out".to_string());
+            return Ok(0);
         }
+
+        let executor = UploadExecutor::new(
+            Arc::clone(&self.uploader),
+            Arc::clone(&self.tracker),
+            Arc::clone(&self.state),
+            Arc::clone(&self.logger),
+        );
+
+        let concurrency = crate::app_config::load_app_config(&self.logger)
+            .await
+            .upload_concurrency
+            .unwrap_or(DEFAULT_UPLOAD_CONCURRENCY);
+
+        let upload_result = executor
+            .execute(scan_result.prepared_replays, UploadPolicy::ContinueOnError, concurrency, app)
+            .await?;
+        self.record_uploads(&upload_result.succeeded);
+
+        self.logger.info(format!(
+            "Auto-upload complete: {} uploaded, {} newly queued for retry",
+            upload_result.uploaded_count, upload_result.enqueued_count
+        ));
+
+        if let Err(e) = app.emit(AUTO_UPLOAD_EVENT, serde_json::json!({
+            "count": upload_result.uploaded_count,
+            "enqueued": upload_result.enqueued_count,
+        })) {
+            self.logger.warn(format!("Failed to emit {}: {}", AUTO_UPLOAD_EVENT, e));
+        }
+
+        Ok(upload_result.uploaded_count)
     }
 
     /// Start watching all replay folders for new files
@@ -378,9 +729,13 @@ impl UploadManager {
     {
         let (tx, mut rx) = mpsc::channel(100);
 
-        let folders = self.replay_folders.clone();
+        let folders = self.replay_folders();
         let logger = self.logger.clone();
         let logger_for_watcher = self.logger.clone();
+        // Reset in case this is a restart (e.g. after `save_folder_paths`
+        // picks up a changed folder list) of a watcher stopped earlier.
+        self.watcher_cancelled.store(false, Ordering::Relaxed);
+        let watcher_cancelled = Arc::clone(&self.watcher_cancelled);
 
         // Create file watcher
         let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
@@ -430,29 +785,184 @@ impl UploadManager {
             // Keep watcher alive by moving it into this long-running task
             let _watcher = watcher;
 
-            while let Some(path) = rx.recv().await {
-                // Add delay to ensure file is fully written
-                let delay_ms = get_file_processing_delay_ms();
-                logger_for_task.debug(format!("Waiting {}ms before processing: {}", delay_ms, path.display()));
-                tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-                logger_for_task.info(format!("Processing new replay file: {}", path.display()));
-                on_new_file(path);
-            }
+            // Debounce qualifying events: a path's deadline is pushed forward on
+            // every event it receives, so the burst of modify events SC2 emits
+            // while writing a replay collapses into a single dispatch once the
+            // file has been quiet for `quiet_period`.
+            let quiet_period =
+                std::time::Duration::from_millis(get_file_processing_delay_ms());
+            let mut pending: HashMap<PathBuf, std::time::Instant> = HashMap::new();
+            // Consecutive validate_replay() failures per path, reset on success
+            // or once MAX_VALIDATION_ATTEMPTS is hit and the file is dropped.
+            let mut validation_attempts: HashMap<PathBuf, u32> = HashMap::new();
+            let mut tick = tokio::time::interval(std::time::Duration::from_millis(100));
+
+            loop {
+                if watcher_cancelled.load(Ordering::Relaxed) {
+                    logger_for_task.info("File watcher stopped".to_string());
+                    break;
+                }
+
+                tokio::select! {
+                    maybe_path = rx.recv() => {
+                        match maybe_path {
+                            Some(path) => {
+                                logger_for_task.debug(format!(
+                                    "Debouncing replay event: {}", path.display()
+                                ));
+                                let deadline = std::time::Instant::now() + quiet_period;
+                                pending.insert(path, deadline);
+                            }
+                            None => {
+                                // Only reached if the channel is closed (shouldn't happen).
+                                logger_for_task.warn(
+                                    "File watcher channel closed unexpectedly".to_string());
+                                break;
+                            }
+                        }
+                    }
+                    _ = tick.tick() => {
+                        let now = std::time::Instant::now();
+                        let ready: Vec<PathBuf> = pending
+                            .iter()
+                            .filter(|(_, deadline)| **deadline <= now)
+                            .map(|(path, _)| path.clone())
+                            .collect();
+                        for path in ready {
+                            pending.remove(&path);
+                            // Confirm the file has finished being written before
+                            // dispatching: two identical size+mtime reads ~200ms
+                            // apart, non-empty, and openable (no longer locked by
+                            // antivirus).
+                            if !is_file_stable(&path).await {
+                                // Still settling — re-arm for another quiet period.
+                                logger_for_task.debug(format!(
+                                    "Replay not yet stable, re-queuing: {}", path.display()));
+                                pending.insert(path, std::time::Instant::now() + quiet_period);
+                                continue;
+                            }
 
-            // This point is only reached if the channel is closed (which shouldn't happen)
-            logger_for_task.warn("File watcher channel closed unexpectedly".to_string());
+                            // A stable size/mtime doesn't guarantee the MPQ
+                            // container itself is complete, so round-trip
+                            // decode it before handing it off: this turns the
+                            // fixed wait into a correctness gate.
+                            match replay_parser::validate_replay(&path) {
+                                Ok(()) => {
+                                    validation_attempts.remove(&path);
+                                    logger_for_task.info(format!(
+                                        "Processing new replay file: {}", path.display()));
+                                    on_new_file(path);
+                                }
+                                Err(e) => {
+                                    let attempts = validation_attempts
+                                        .entry(path.clone())
+                                        .and_modify(|n| *n += 1)
+                                        .or_insert(1);
+                                    if *attempts >= MAX_VALIDATION_ATTEMPTS {
+                                        logger_for_task.error(format!(
+                                            "Replay failed validation after {} attempt(s), giving up: {} ({})",
+                                            attempts, path.display(), e
+                                        ));
+                                        validation_attempts.remove(&path);
+                                    } else {
+                                        logger_for_task.debug(format!(
+                                            "Replay failed validation (attempt {}/{}), re-queuing: {} ({})",
+                                            attempts, MAX_VALIDATION_ATTEMPTS, path.display(), e
+                                        ));
+                                        pending.insert(path, std::time::Instant::now() + quiet_period);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
         });
 
         Ok(())
     }
 
-    /// Stop watching (not implemented - watcher lives for app lifetime)
-    #[allow(dead_code)]
+    /// Stop the active watcher, if one is running. Signals the debounce loop
+    /// to exit on its next tick, which drops the underlying `notify` watcher.
     pub fn stop_watching(&self) {
+        self.watcher_cancelled.store(true, Ordering::Relaxed);
         let mut state = self.state.lock()
             .unwrap_or_else(|e| e.into_inner());
         state.is_watching = false;
     }
+
+    /// Spawn the periodic background re-scan loop.
+    ///
+    /// The watcher only catches files created while it is live, so replays
+    /// saved while the app was closed (or during a watcher hiccup) would linger
+    /// until a manual scan. This loop runs `scan_and_upload` on the configured
+    /// interval (see [`crate::commands::settings::load_scan_interval_secs`]),
+    /// coalescing with the watcher via `scan_in_progress` so the same file is
+    /// never processed twice, and backing off geometrically while offline or
+    /// unauthenticated. Each completed cycle emits `scan-cycle-complete` so the
+    /// UI can show a "last checked" time.
+    pub fn start_scan_scheduler(manager: Arc<Self>, app: tauri::AppHandle) {
+        use crate::state::AppStateManager;
+        use tauri::Manager;
+
+        let logger = manager.logger.clone();
+        tokio::spawn(async move {
+            // Consecutive-failure counter that drives the offline/unauth backoff.
+            let mut failures: u32 = 0;
+            loop {
+                let base = crate::commands::settings::load_scan_interval_secs().await;
+                // Geometric backoff while failing, capped at one hour.
+                let wait = if failures == 0 {
+                    base
+                } else {
+                    base.saturating_mul(1u64 << failures.min(6)).min(3600)
+                };
+                tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+
+                let sm = app.state::<AppStateManager>();
+                if sm.uploads_paused.load(Ordering::Relaxed) {
+                    logger.debug("Scan scheduler: uploads paused, skipping cycle".to_string());
+                    continue;
+                }
+
+                // Coalesce with a watcher-triggered scan already in flight.
+                if sm.scan_in_progress.swap(true, Ordering::AcqRel) {
+                    logger.debug("Scan scheduler: scan already running, skipping cycle".to_string());
+                    continue;
+                }
+
+                let result = manager.scan_and_upload(SCHEDULER_SCAN_LIMIT, false, &app).await;
+                sm.scan_in_progress.store(false, Ordering::Release);
+
+                match result {
+                    Ok(count) => {
+                        failures = 0;
+                        let now = now_unix_secs();
+                        sm.last_scan_at.store(now, Ordering::Relaxed);
+                        if let Err(e) = app.emit("scan-cycle-complete", serde_json::json!({
+                            "uploaded": count,
+                            "last_checked": now,
+                        })) {
+                            logger.warn(format!("Failed to emit scan-cycle-complete: {}", e));
+                        }
+                    }
+                    Err(e) => {
+                        failures = failures.saturating_add(1);
+                        logger.warn(format!("Scheduled scan failed (backing off): {}", e));
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Current Unix time in seconds, saturating to `0` if the clock is before the
+/// epoch.
+pub(crate) fn now_unix_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 #[cfg(test)]
@@ -509,6 +1019,9 @@ mod tests {
             }),
             pending_count: 3,
             is_watching: true,
+            bytes_total: 0,
+            bytes_uploaded: 0,
+            last_skipped: Vec::new(),
         };
 
         let json = serde_json::to_string(&state).unwrap();
@@ -546,7 +1059,9 @@ mod tests {
             vec![temp_dir.path().to_path_buf()],
             "https://example.com".to_string(),
             "test-token".to_string(),
-            logger,
+            None,
+            logger.clone(),
+            Arc::new(crate::db::ReplayDb::open_sync(&logger)),
         );
 
         assert!(manager.is_ok());
@@ -568,7 +1083,9 @@ mod tests {
             vec![temp_dir1.path().to_path_buf(), temp_dir2.path().to_path_buf()],
             "https://example.com".to_string(),
             "test-token".to_string(),
-            logger,
+            None,
+            logger.clone(),
+            Arc::new(crate::db::ReplayDb::open_sync(&logger)),
         );
 
         assert!(manager.is_ok(), "Should accept multiple folders");
@@ -583,7 +1100,9 @@ mod tests {
             vec![temp_dir.path().to_path_buf()],
             "https://example.com".to_string(),
             "test-token".to_string(),
-            logger,
+            None,
+            logger.clone(),
+            Arc::new(crate::db::ReplayDb::open_sync(&logger)),
         ).unwrap();
 
         let state = manager.get_state();
@@ -602,7 +1121,9 @@ mod tests {
             vec![temp_dir.path().to_path_buf()],
             "https://example.com".to_string(),
             "test-token".to_string(),
-            logger,
+            None,
+            logger.clone(),
+            Arc::new(crate::db::ReplayDb::open_sync(&logger)),
         ).unwrap();
 
         let detected_files = Arc::new(Mutex::new(Vec::new()));
@@ -630,9 +1151,9 @@ mod tests {
     #[test]
     fn test_group_replays_by_type_and_player_empty() {
         let hashes: Vec<String> = vec![];
-        let replay_map: HashMap<String, (ReplayFileInfo, String, String)> = HashMap::new();
+        let replay_map: HashMap<String, ReplayGroupingEntry> = HashMap::new();
 
-        let groups = group_replays_by_type_and_player(&hashes, &replay_map);
+        let groups = group_replays_by_type_and_player(&hashes, &replay_map, GroupingKey::TypeAndPlayer);
 
         assert_eq!(groups.len(), 0, "Empty input should produce no groups");
     }
@@ -654,6 +1175,8 @@ mod tests {
             },
             "1v1-ladder".to_string(),
             "lotus".to_string(),
+            None,
+            None,
         ));
         replay_map.insert("hash2".to_string(), (
             ReplayFileInfo {
@@ -664,9 +1187,11 @@ mod tests {
             },
             "1v1-ladder".to_string(),
             "lotus".to_string(),
+            None,
+            None,
         ));
 
-        let groups = group_replays_by_type_and_player(&hashes, &replay_map);
+        let groups = group_replays_by_type_and_player(&hashes, &replay_map, GroupingKey::TypeAndPlayer);
 
         assert_eq!(groups.len(), 1, "Should have one group for same type/player");
         assert_eq!(groups[0].game_type, "1v1-ladder");
@@ -693,6 +1218,8 @@ mod tests {
             },
             "1v1-ladder".to_string(),
             "lotus".to_string(),
+            None,
+            None,
         ));
         replay_map.insert("hash2".to_string(), (
             ReplayFileInfo {
@@ -703,9 +1230,11 @@ mod tests {
             },
             "1v1-ladder".to_string(),
             "lotusAlt".to_string(),
+            None,
+            None,
         ));
 
-        let groups = group_replays_by_type_and_player(&hashes, &replay_map);
+        let groups = group_replays_by_type_and_player(&hashes, &replay_map, GroupingKey::TypeAndPlayer);
 
         assert_eq!(groups.len(), 2, "Should have two groups for different players");
         // Groups should be sorted by player name
@@ -732,6 +1261,8 @@ mod tests {
             },
             "1v1-ladder".to_string(),
             "lotus".to_string(),
+            None,
+            None,
         ));
         replay_map.insert("hash2".to_string(), (
             ReplayFileInfo {
@@ -742,9 +1273,11 @@ mod tests {
             },
             "2v2-ladder".to_string(),
             "lotus".to_string(),
+            None,
+            None,
         ));
 
-        let groups = group_replays_by_type_and_player(&hashes, &replay_map);
+        let groups = group_replays_by_type_and_player(&hashes, &replay_map, GroupingKey::TypeAndPlayer);
 
         assert_eq!(groups.len(), 2, "Should have two groups for different types");
         // Groups should be sorted by game type
@@ -766,22 +1299,22 @@ mod tests {
         let mut replay_map = HashMap::new();
         replay_map.insert("hash1".to_string(), (
             ReplayFileInfo { path: replay_path1, filename: "replay1.SC2Replay".to_string(), filesize: 5, modified_time: SystemTime::UNIX_EPOCH },
-            "1v1-ladder".to_string(), "lotus".to_string(),
+            "1v1-ladder".to_string(), "lotus".to_string(), None, None,
         ));
         replay_map.insert("hash2".to_string(), (
             ReplayFileInfo { path: replay_path2, filename: "replay2.SC2Replay".to_string(), filesize: 5, modified_time: SystemTime::UNIX_EPOCH },
-            "1v1-ladder".to_string(), "lotusAlt".to_string(),
+            "1v1-ladder".to_string(), "lotusAlt".to_string(), None, None,
         ));
         replay_map.insert("hash3".to_string(), (
             ReplayFileInfo { path: replay_path3, filename: "replay3.SC2Replay".to_string(), filesize: 5, modified_time: SystemTime::UNIX_EPOCH },
-            "2v2-ladder".to_string(), "lotus".to_string(),
+            "2v2-ladder".to_string(), "lotus".to_string(), None, None,
         ));
         replay_map.insert("hash4".to_string(), (
             ReplayFileInfo { path: replay_path4, filename: "replay4.SC2Replay".to_string(), filesize: 5, modified_time: SystemTime::UNIX_EPOCH },
-            "2v2-ladder".to_string(), "lotusAlt".to_string(),
+            "2v2-ladder".to_string(), "lotusAlt".to_string(), None, None,
         ));
 
-        let groups = group_replays_by_type_and_player(&hashes, &replay_map);
+        let groups = group_replays_by_type_and_player(&hashes, &replay_map, GroupingKey::TypeAndPlayer);
 
         assert_eq!(groups.len(), 4, "Should have four groups (2 types Ã— 2 players)");
 
@@ -812,9 +1345,11 @@ mod tests {
             },
             "1v1-ladder".to_string(),
             "lotus".to_string(),
+            None,
+            None,
         ));
 
-        let groups = group_replays_by_type_and_player(&hashes, &replay_map);
+        let groups = group_replays_by_type_and_player(&hashes, &replay_map, GroupingKey::TypeAndPlayer);
 
         assert_eq!(groups.len(), 1, "Should skip missing hash and create one group");
         assert_eq!(groups[0].hashes.len(), 1);
@@ -834,34 +1369,34 @@ mod tests {
     fn test_detect_user_player_names_single_player_1v1() {
         // User plays 1v1 against different opponents
         let replays = vec![
-            ("replay1".to_string(), vec![("Lotus".to_string(), false), ("Opponent1".to_string(), false)]),
-            ("replay2".to_string(), vec![("Lotus".to_string(), false), ("Opponent2".to_string(), false)]),
-            ("replay3".to_string(), vec![("Lotus".to_string(), false), ("Opponent3".to_string(), false)]),
-            ("replay4".to_string(), vec![("Lotus".to_string(), false), ("Opponent4".to_string(), false)]),
+            ("replay1".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent1".to_string(), "Opponent1".to_string(), false)]),
+            ("replay2".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent2".to_string(), "Opponent2".to_string(), false)]),
+            ("replay3".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent3".to_string(), "Opponent3".to_string(), false)]),
+            ("replay4".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent4".to_string(), "Opponent4".to_string(), false)]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 1, "Should detect one user");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as the user");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as the user");
     }
 
     #[test]
     fn test_detect_user_player_names_filters_practice_partner() {
         // User plays 1v1, but has a frequent practice partner
         let replays = vec![
-            ("replay1".to_string(), vec![("Lotus".to_string(), false), ("PracticePartner".to_string(), false)]),
-            ("replay2".to_string(), vec![("Lotus".to_string(), false), ("PracticePartner".to_string(), false)]),
-            ("replay3".to_string(), vec![("Lotus".to_string(), false), ("PracticePartner".to_string(), false)]),
-            ("replay4".to_string(), vec![("Lotus".to_string(), false), ("Opponent1".to_string(), false)]),
-            ("replay5".to_string(), vec![("Lotus".to_string(), false), ("Opponent2".to_string(), false)]),
+            ("replay1".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("PracticePartner".to_string(), "PracticePartner".to_string(), false)]),
+            ("replay2".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("PracticePartner".to_string(), "PracticePartner".to_string(), false)]),
+            ("replay3".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("PracticePartner".to_string(), "PracticePartner".to_string(), false)]),
+            ("replay4".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent1".to_string(), "Opponent1".to_string(), false)]),
+            ("replay5".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent2".to_string(), "Opponent2".to_string(), false)]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 1, "Should detect one user");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as the user, not practice partner");
-        assert!(!detected.contains(&"PracticePartner".to_string()), "Should filter out practice partner");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as the user, not practice partner");
+        assert!(!detected.iter().any(|p| p.name == "PracticePartner"), "Should filter out practice partner");
     }
 
     #[test]
@@ -869,54 +1404,54 @@ mod tests {
         // User plays 2v2 with a frequent teammate
         let replays = vec![
             ("replay1".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("FrequentTeammate".to_string(), false),
-                ("Enemy1".to_string(), false),
-                ("Enemy2".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("FrequentTeammate".to_string(), "FrequentTeammate".to_string(), false),
+                ("Enemy1".to_string(), "Enemy1".to_string(), false),
+                ("Enemy2".to_string(), "Enemy2".to_string(), false),
             ]),
             ("replay2".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("FrequentTeammate".to_string(), false),
-                ("Enemy3".to_string(), false),
-                ("Enemy4".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("FrequentTeammate".to_string(), "FrequentTeammate".to_string(), false),
+                ("Enemy3".to_string(), "Enemy3".to_string(), false),
+                ("Enemy4".to_string(), "Enemy4".to_string(), false),
             ]),
             ("replay3".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("FrequentTeammate".to_string(), false),
-                ("Enemy5".to_string(), false),
-                ("Enemy6".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("FrequentTeammate".to_string(), "FrequentTeammate".to_string(), false),
+                ("Enemy5".to_string(), "Enemy5".to_string(), false),
+                ("Enemy6".to_string(), "Enemy6".to_string(), false),
             ]),
             ("replay4".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("RandomTeammate".to_string(), false),
-                ("Enemy7".to_string(), false),
-                ("Enemy8".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("RandomTeammate".to_string(), "RandomTeammate".to_string(), false),
+                ("Enemy7".to_string(), "Enemy7".to_string(), false),
+                ("Enemy8".to_string(), "Enemy8".to_string(), false),
             ]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 1, "Should detect one user");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as the user");
-        assert!(!detected.contains(&"FrequentTeammate".to_string()), "Should filter out frequent teammate");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as the user");
+        assert!(!detected.iter().any(|p| p.name == "FrequentTeammate"), "Should filter out frequent teammate");
     }
 
     #[test]
     fn test_detect_user_player_names_multiple_smurfs() {
         // User has multiple accounts (smurfs)
         let replays = vec![
-            ("replay1".to_string(), vec![("Lotus".to_string(), false), ("Opponent1".to_string(), false)]),
-            ("replay2".to_string(), vec![("Lotus".to_string(), false), ("Opponent2".to_string(), false)]),
-            ("replay3".to_string(), vec![("Lotus".to_string(), false), ("Opponent3".to_string(), false)]),
-            ("replay4".to_string(), vec![("LotusAlt".to_string(), false), ("Opponent4".to_string(), false)]),
-            ("replay5".to_string(), vec![("LotusAlt".to_string(), false), ("Opponent5".to_string(), false)]),
+            ("replay1".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent1".to_string(), "Opponent1".to_string(), false)]),
+            ("replay2".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent2".to_string(), "Opponent2".to_string(), false)]),
+            ("replay3".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent3".to_string(), "Opponent3".to_string(), false)]),
+            ("replay4".to_string(), vec![("LotusAlt".to_string(), "LotusAlt".to_string(), false), ("Opponent4".to_string(), "Opponent4".to_string(), false)]),
+            ("replay5".to_string(), vec![("LotusAlt".to_string(), "LotusAlt".to_string(), false), ("Opponent5".to_string(), "Opponent5".to_string(), false)]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 2, "Should detect two user accounts");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as primary account (highest frequency)");
-        assert_eq!(detected[1], "LotusAlt", "Should detect 'LotusAlt' as secondary account");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as primary account (highest frequency)");
+        assert_eq!(detected[1].name, "LotusAlt", "Should detect 'LotusAlt' as secondary account");
     }
 
     #[test]
@@ -924,28 +1459,28 @@ mod tests {
         // Some replays have observers, should ignore them
         let replays = vec![
             ("replay1".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("Opponent1".to_string(), false),
-                ("Observer1".to_string(), true),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("Opponent1".to_string(), "Opponent1".to_string(), false),
+                ("Observer1".to_string(), "Observer1".to_string(), true),
             ]),
             ("replay2".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("Opponent2".to_string(), false),
-                ("Observer2".to_string(), true),
-                ("Observer3".to_string(), true),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("Opponent2".to_string(), "Opponent2".to_string(), false),
+                ("Observer2".to_string(), "Observer2".to_string(), true),
+                ("Observer3".to_string(), "Observer3".to_string(), true),
             ]),
             ("replay3".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("Opponent3".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("Opponent3".to_string(), "Opponent3".to_string(), false),
             ]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 1, "Should detect one user");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as the user");
-        assert!(!detected.contains(&"Observer1".to_string()), "Should not detect observers");
-        assert!(!detected.contains(&"Observer2".to_string()), "Should not detect observers");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as the user");
+        assert!(!detected.iter().any(|p| p.name == "Observer1"), "Should not detect observers");
+        assert!(!detected.iter().any(|p| p.name == "Observer2"), "Should not detect observers");
     }
 
     #[test]
@@ -953,33 +1488,33 @@ mod tests {
         // Mix of 1v1 and 2v2 games with multiple accounts
         let replays = vec![
             // 1v1 games on main account
-            ("1v1_1".to_string(), vec![("Lotus".to_string(), false), ("Opponent1".to_string(), false)]),
-            ("1v1_2".to_string(), vec![("Lotus".to_string(), false), ("Opponent2".to_string(), false)]),
-            ("1v1_3".to_string(), vec![("Lotus".to_string(), false), ("Opponent3".to_string(), false)]),
+            ("1v1_1".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent1".to_string(), "Opponent1".to_string(), false)]),
+            ("1v1_2".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent2".to_string(), "Opponent2".to_string(), false)]),
+            ("1v1_3".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent3".to_string(), "Opponent3".to_string(), false)]),
             // 2v2 games on main account with frequent teammate
             ("2v2_1".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("FrequentTeammate".to_string(), false),
-                ("Enemy1".to_string(), false),
-                ("Enemy2".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("FrequentTeammate".to_string(), "FrequentTeammate".to_string(), false),
+                ("Enemy1".to_string(), "Enemy1".to_string(), false),
+                ("Enemy2".to_string(), "Enemy2".to_string(), false),
             ]),
             ("2v2_2".to_string(), vec![
-                ("Lotus".to_string(), false),
-                ("FrequentTeammate".to_string(), false),
-                ("Enemy3".to_string(), false),
-                ("Enemy4".to_string(), false),
+                ("Lotus".to_string(), "Lotus".to_string(), false),
+                ("FrequentTeammate".to_string(), "FrequentTeammate".to_string(), false),
+                ("Enemy3".to_string(), "Enemy3".to_string(), false),
+                ("Enemy4".to_string(), "Enemy4".to_string(), false),
             ]),
             // 1v1 games on alt account
-            ("1v1_alt_1".to_string(), vec![("LotusAlt".to_string(), false), ("Opponent4".to_string(), false)]),
-            ("1v1_alt_2".to_string(), vec![("LotusAlt".to_string(), false), ("Opponent5".to_string(), false)]),
+            ("1v1_alt_1".to_string(), vec![("LotusAlt".to_string(), "LotusAlt".to_string(), false), ("Opponent4".to_string(), "Opponent4".to_string(), false)]),
+            ("1v1_alt_2".to_string(), vec![("LotusAlt".to_string(), "LotusAlt".to_string(), false), ("Opponent5".to_string(), "Opponent5".to_string(), false)]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 2, "Should detect two user accounts");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as primary");
-        assert_eq!(detected[1], "LotusAlt", "Should detect 'LotusAlt' as secondary");
-        assert!(!detected.contains(&"FrequentTeammate".to_string()), "Should filter out frequent teammate");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as primary");
+        assert_eq!(detected[1].name, "LotusAlt", "Should detect 'LotusAlt' as secondary");
+        assert!(!detected.iter().any(|p| p.name == "FrequentTeammate"), "Should filter out frequent teammate");
     }
 
     #[test]
@@ -987,8 +1522,8 @@ mod tests {
         // Edge case: all players are observers
         let replays = vec![
             ("replay1".to_string(), vec![
-                ("Observer1".to_string(), true),
-                ("Observer2".to_string(), true),
+                ("Observer1".to_string(), "Observer1".to_string(), true),
+                ("Observer2".to_string(), "Observer2".to_string(), true),
             ]),
         ];
 
@@ -1001,38 +1536,56 @@ mod tests {
     fn test_detect_user_player_names_filters_single_occurrence() {
         // Players who appear only once should be filtered out
         let replays = vec![
-            ("replay1".to_string(), vec![("Lotus".to_string(), false), ("Opponent1".to_string(), false)]),
-            ("replay2".to_string(), vec![("Lotus".to_string(), false), ("Opponent2".to_string(), false)]),
-            ("replay3".to_string(), vec![("Lotus".to_string(), false), ("Opponent3".to_string(), false)]),
+            ("replay1".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent1".to_string(), "Opponent1".to_string(), false)]),
+            ("replay2".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent2".to_string(), "Opponent2".to_string(), false)]),
+            ("replay3".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Opponent3".to_string(), "Opponent3".to_string(), false)]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 1, "Should detect one user");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as the user");
-        assert!(!detected.contains(&"Opponent1".to_string()), "Should filter out single-occurrence players");
-        assert!(!detected.contains(&"Opponent2".to_string()), "Should filter out single-occurrence players");
-        assert!(!detected.contains(&"Opponent3".to_string()), "Should filter out single-occurrence players");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as the user");
+        assert!(!detected.iter().any(|p| p.name == "Opponent1"), "Should filter out single-occurrence players");
+        assert!(!detected.iter().any(|p| p.name == "Opponent2"), "Should filter out single-occurrence players");
+        assert!(!detected.iter().any(|p| p.name == "Opponent3"), "Should filter out single-occurrence players");
     }
 
     #[test]
     fn test_detect_user_player_names_filters_ai_players() {
         // AI player names should be filtered out
         let replays = vec![
-            ("ai1".to_string(), vec![("Lotus".to_string(), false), ("Computer".to_string(), false)]),
-            ("ai2".to_string(), vec![("Lotus".to_string(), false), ("Computer".to_string(), false)]),
-            ("ai3".to_string(), vec![("Lotus".to_string(), false), ("Computer".to_string(), false)]),
-            ("ai4".to_string(), vec![("Lotus".to_string(), false), ("A.I.".to_string(), false)]),
-            ("ai5".to_string(), vec![("Lotus".to_string(), false), ("Bot".to_string(), false)]),
+            ("ai1".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Computer".to_string(), "Computer".to_string(), false)]),
+            ("ai2".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Computer".to_string(), "Computer".to_string(), false)]),
+            ("ai3".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Computer".to_string(), "Computer".to_string(), false)]),
+            ("ai4".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("A.I.".to_string(), "A.I.".to_string(), false)]),
+            ("ai5".to_string(), vec![("Lotus".to_string(), "Lotus".to_string(), false), ("Bot".to_string(), "Bot".to_string(), false)]),
         ];
 
         let detected = detect_user_player_names(&replays);
 
         assert_eq!(detected.len(), 1, "Should detect one user");
-        assert_eq!(detected[0], "Lotus", "Should detect 'Lotus' as the user");
-        assert!(!detected.contains(&"Computer".to_string()), "Should filter out 'Computer' AI name");
-        assert!(!detected.contains(&"A.I.".to_string()), "Should filter out 'A.I.' AI name");
-        assert!(!detected.contains(&"Bot".to_string()), "Should filter out 'Bot' AI name");
+        assert_eq!(detected[0].name, "Lotus", "Should detect 'Lotus' as the user");
+        assert!(!detected.iter().any(|p| p.name == "Computer"), "Should filter out 'Computer' AI name");
+        assert!(!detected.iter().any(|p| p.name == "A.I."), "Should filter out 'A.I.' AI name");
+        assert!(!detected.iter().any(|p| p.name == "Bot"), "Should filter out 'Bot' AI name");
+    }
+
+    #[test]
+    fn test_detect_user_player_names_collapses_renamed_player() {
+        // Same handle, different display name over time (most recent first).
+        // The rename must not split one human into two detected accounts.
+        let replays = vec![
+            ("replay4".to_string(), vec![("handle-1".to_string(), "NewName".to_string(), false), ("Opponent4".to_string(), "Opponent4".to_string(), false)]),
+            ("replay3".to_string(), vec![("handle-1".to_string(), "NewName".to_string(), false), ("Opponent3".to_string(), "Opponent3".to_string(), false)]),
+            ("replay2".to_string(), vec![("handle-1".to_string(), "OldName".to_string(), false), ("Opponent2".to_string(), "Opponent2".to_string(), false)]),
+            ("replay1".to_string(), vec![("handle-1".to_string(), "OldName".to_string(), false), ("Opponent1".to_string(), "Opponent1".to_string(), false)]),
+        ];
+
+        let detected = detect_user_player_names(&replays);
+
+        assert_eq!(detected.len(), 1, "A rename should collapse into a single account");
+        assert_eq!(detected[0].handle, "handle-1");
+        assert_eq!(detected[0].name, "NewName", "Should surface the most-recently-seen display name");
     }
 
     // Tests for is_sc2_replay helper function
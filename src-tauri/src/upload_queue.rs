@@ -0,0 +1,482 @@
+//! Durable background upload queue with retry and exponential backoff.
+//!
+//! Replays that fail to upload (offline, 5xx, token refresh mid-batch) should
+//! not be dropped on the floor: they are enqueued here, persisted to the config
+//! directory, and retried automatically with exponential backoff until they
+//! either succeed or exhaust their attempt budget and land in a dead-letter
+//! list surfaced to the UI.
+//!
+//! The queue file is written through the atomic `config_utils` helpers so a
+//! crash mid-write never corrupts pending jobs.
+
+use crate::config_utils::{load_config_file, save_config_file};
+use crate::debug_logger::DebugLogger;
+use crate::replay_tracker::ReplayTracker;
+use crate::replay_uploader::ReplayUploader;
+use crate::upload_manager::{UploadStatus, UPLOAD_STATUS_EVENT};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// Event name carrying per-item queue transitions to the frontend.
+pub const QUEUE_ITEM_EVENT: &str = "queue-item-status";
+
+/// Event name carrying the queue's aggregate depth, emitted whenever
+/// `pending`/`dead_letter` change so the UI doesn't have to poll
+/// `get_upload_queue` to keep a queue-depth indicator live.
+pub const QUEUE_CHANGED_EVENT: &str = "upload-queue-changed";
+
+/// Emit the queue's current depth, best-effort. A missing app handle (e.g. in
+/// tests) or a failed emit is logged but never aborts the caller.
+pub fn emit_queue_changed(app: Option<&AppHandle>, logger: &DebugLogger, queue: &UploadQueue) {
+    let Some(app) = app else { return };
+    if let Err(e) = app.emit(
+        QUEUE_CHANGED_EVENT,
+        serde_json::json!({
+            "pending": queue.pending.len(),
+            "dead_letter": queue.dead_letter.len(),
+            "paused": queue.paused,
+        }),
+    ) {
+        logger.warn(format!("Failed to emit {}: {}", QUEUE_CHANGED_EVENT, e));
+    }
+}
+
+/// Lifecycle states a queued replay moves through, emitted to the webview so
+/// it can render the queue live: `queued → uploading → done`/`failed`, with
+/// `dead` once a job exhausts its attempt budget.
+#[derive(Debug, Clone, Copy)]
+pub enum ItemStatus {
+    Queued,
+    Uploading,
+    Done,
+    Failed,
+    Dead,
+}
+
+impl ItemStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            ItemStatus::Queued => "queued",
+            ItemStatus::Uploading => "uploading",
+            ItemStatus::Done => "done",
+            ItemStatus::Failed => "failed",
+            ItemStatus::Dead => "dead",
+        }
+    }
+}
+
+/// Emit a per-item status transition, best-effort. A missing app handle (e.g.
+/// in tests) or a failed emit is logged but never aborts the upload.
+pub fn emit_item_status(
+    app: Option<&AppHandle>,
+    logger: &DebugLogger,
+    hash: &str,
+    path: &Path,
+    status: ItemStatus,
+    error: Option<&str>,
+) {
+    let Some(app) = app else { return };
+    if let Err(e) = app.emit(
+        QUEUE_ITEM_EVENT,
+        serde_json::json!({
+            "hash": hash,
+            "path": path.to_string_lossy(),
+            "status": status.as_str(),
+            "error": error,
+        }),
+    ) {
+        logger.warn(format!("Failed to emit {}: {}", QUEUE_ITEM_EVENT, e));
+    }
+}
+
+/// Filename used to persist the queue in the config directory.
+const QUEUE_FILE: &str = "upload_queue.json";
+
+/// Base backoff delay in seconds (doubled on each attempt).
+const BACKOFF_BASE_SECS: u64 = 5;
+/// Maximum backoff delay in seconds regardless of attempt count.
+const BACKOFF_CAP_SECS: u64 = 3600;
+/// Number of attempts after which a job is moved to the dead-letter list.
+const MAX_ATTEMPTS: u32 = 8;
+
+/// Optional metadata captured when the replay was queued, used to pre-fill the
+/// upload arguments without re-parsing the file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct JobMetadata {
+    pub player_name: Option<String>,
+    pub game_type: Option<String>,
+    pub region: Option<String>,
+    /// Battle.net realm within `region` (e.g. 1 = US, 2 = LATAM for `NA`); see
+    /// [`crate::services::upload_executor::ReplayRegion`].
+    #[serde(default)]
+    pub realm: Option<u8>,
+}
+
+/// A single queued upload job.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadJob {
+    /// SHA-256 content hash, used for de-duplication across restarts.
+    pub hash: String,
+    /// Absolute path to the replay file on disk.
+    pub path: PathBuf,
+    /// Metadata used to pre-fill the upload request.
+    pub metadata: JobMetadata,
+    /// Number of upload attempts made so far.
+    pub attempts: u32,
+    /// Earliest Unix timestamp (seconds) at which the next attempt may run.
+    pub next_attempt_at: u64,
+    /// Last error message, if the most recent attempt failed.
+    pub last_error: Option<String>,
+}
+
+impl UploadJob {
+    /// Create a fresh job that is due immediately.
+    pub fn new(hash: String, path: PathBuf, metadata: JobMetadata) -> Self {
+        Self {
+            hash,
+            path,
+            metadata,
+            attempts: 0,
+            next_attempt_at: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// Persisted queue state: the pending jobs, a dead-letter list, and the
+/// paused flag toggled from the UI.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UploadQueue {
+    pub pending: Vec<UploadJob>,
+    pub dead_letter: Vec<UploadJob>,
+    pub paused: bool,
+}
+
+impl UploadQueue {
+    /// Load the queue from disk, falling back to an empty queue if absent.
+    pub async fn load() -> Result<Self, String> {
+        Ok(load_config_file(QUEUE_FILE).await?.unwrap_or_default())
+    }
+
+    /// Persist the queue atomically.
+    pub async fn save(&self) -> Result<(), String> {
+        save_config_file(QUEUE_FILE, self).await.map(|_| ())
+    }
+
+    /// Enqueue a job, skipping it if a job with the same hash is already pending
+    /// or dead-lettered. De-duplication against the server is done by the caller
+    /// via `check_hashes` before enqueueing.
+    pub fn enqueue(&mut self, job: UploadJob) -> bool {
+        if self.pending.iter().any(|j| j.hash == job.hash)
+            || self.dead_letter.iter().any(|j| j.hash == job.hash)
+        {
+            return false;
+        }
+        self.pending.push(job);
+        true
+    }
+
+    /// Return indices of jobs whose `next_attempt_at` is due relative to `now`.
+    fn due_indices(&self, now: u64) -> Vec<usize> {
+        self.pending
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| job.next_attempt_at <= now)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Compute the backoff delay for a given attempt number.
+///
+/// `delay = min(base * 2^attempts, cap)` with a jitter of up to the computed
+/// delay added on top to avoid thundering-herd retries; the caller derives the
+/// jitter fraction from the job hash ([`hash_jitter`]).
+fn backoff_delay_secs(attempts: u32, jitter_fraction: f64) -> u64 {
+    let exp = base_delay(attempts);
+    let jitter = (exp as f64 * jitter_fraction.clamp(0.0, 1.0)) as u64;
+    exp.saturating_add(jitter)
+}
+
+/// The un-jittered, capped exponential portion of the backoff.
+fn base_delay(attempts: u32) -> u64 {
+    BACKOFF_BASE_SECS
+        .checked_shl(attempts)
+        .map(|d| d.min(BACKOFF_CAP_SECS))
+        .unwrap_or(BACKOFF_CAP_SECS)
+}
+
+/// Current Unix time in seconds.
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A jitter value in `[0, 1)` derived from the job hash so the queue module
+/// stays free of `rand` and remains deterministic in tests.
+fn hash_jitter(hash: &str) -> f64 {
+    let byte = hash.bytes().next().unwrap_or(0);
+    byte as f64 / 256.0
+}
+
+/// Background worker that drains the queue and retries failed uploads.
+pub struct QueueWorker {
+    queue: Arc<Mutex<UploadQueue>>,
+    uploader: Arc<ReplayUploader>,
+    /// Shared tracker used to drop jobs already uploaded out-of-band (e.g. by a
+    /// concurrent manual scan) before re-hitting the network.
+    tracker: Arc<Mutex<ReplayTracker>>,
+    logger: Arc<DebugLogger>,
+    /// App handle used to emit per-item status transitions; `None` in tests.
+    app: Option<AppHandle>,
+}
+
+impl QueueWorker {
+    pub fn new(
+        queue: Arc<Mutex<UploadQueue>>,
+        uploader: Arc<ReplayUploader>,
+        tracker: Arc<Mutex<ReplayTracker>>,
+        logger: Arc<DebugLogger>,
+        app: Option<AppHandle>,
+    ) -> Self {
+        Self {
+            queue,
+            uploader,
+            tracker,
+            logger,
+            app,
+        }
+    }
+
+    /// Drain all jobs that are currently due, uploading each and applying the
+    /// retry/backoff/dead-letter policy. Returns the number of jobs that
+    /// uploaded successfully in this pass.
+    pub async fn drain_due(&self) -> Result<usize, String> {
+        // Snapshot due jobs without holding the lock across the awaits below.
+        let (due_jobs, paused) = {
+            let queue = self.queue.lock().map_err(|_| "Queue mutex poisoned")?;
+            if queue.paused {
+                (Vec::new(), true)
+            } else {
+                let now = now_secs();
+                (
+                    queue
+                        .due_indices(now)
+                        .into_iter()
+                        .map(|i| queue.pending[i].clone())
+                        .collect::<Vec<_>>(),
+                    false,
+                )
+            }
+        };
+
+        if paused {
+            self.logger.debug("Upload queue paused; skipping drain".to_string());
+            return Ok(0);
+        }
+
+        let mut succeeded = 0;
+        for job in due_jobs {
+            // Drop jobs already uploaded out-of-band (e.g. a manual scan landed
+            // the same replay while this job waited) rather than re-uploading.
+            let already_uploaded = self
+                .tracker
+                .lock()
+                .map(|t| t.is_uploaded(&job.hash))
+                .unwrap_or(false);
+            if already_uploaded {
+                let snapshot = {
+                    let mut queue = self.queue.lock().map_err(|_| "Queue mutex poisoned")?;
+                    queue.pending.retain(|j| j.hash != job.hash);
+                    queue.clone()
+                };
+                snapshot.save().await?;
+                emit_queue_changed(self.app.as_ref(), &self.logger, &snapshot);
+                self.logger.info(format!(
+                    "Dropping queued upload {}; already tracked as uploaded", job.hash
+                ));
+                emit_item_status(
+                    self.app.as_ref(),
+                    &self.logger,
+                    &job.hash,
+                    &job.path,
+                    ItemStatus::Done,
+                    None,
+                );
+                continue;
+            }
+
+            emit_item_status(
+                self.app.as_ref(),
+                &self.logger,
+                &job.hash,
+                &job.path,
+                ItemStatus::Uploading,
+                None,
+            );
+
+            let result = self
+                .uploader
+                .upload_replay(
+                    &job.path,
+                    job.metadata.player_name.as_deref(),
+                    None,
+                    job.metadata.game_type.as_deref(),
+                    job.metadata.region.as_deref(),
+                    job.metadata.realm,
+                )
+                .await;
+
+            // Mutate under the lock, then snapshot and drop the guard before the
+            // async save so we never hold a std mutex guard across an await.
+            let (snapshot, transition, error, retry_status) = {
+                let mut queue = self.queue.lock().map_err(|_| "Queue mutex poisoned")?;
+                let (transition, error, retry_status) = match result {
+                    Ok(_) => {
+                        queue.pending.retain(|j| j.hash != job.hash);
+                        succeeded += 1;
+                        self.logger.info(format!("Queued upload succeeded: {}", job.hash));
+                        (ItemStatus::Done, None, None)
+                    }
+                    Err(e) => {
+                        let dead = self.record_failure(&mut queue, &job.hash, e.clone());
+                        if dead {
+                            (ItemStatus::Dead, Some(e), None)
+                        } else {
+                            // The job was rescheduled; surface its next retry so
+                            // the UI can count down rather than show a dead end.
+                            let retry = queue
+                                .pending
+                                .iter()
+                                .find(|j| j.hash == job.hash)
+                                .map(|j| UploadStatus::Retrying {
+                                    filename: job
+                                        .path
+                                        .file_name()
+                                        .and_then(|n| n.to_str())
+                                        .unwrap_or_default()
+                                        .to_string(),
+                                    attempt: j.attempts,
+                                    next_retry_in_secs: j.next_attempt_at.saturating_sub(now_secs()),
+                                });
+                            (ItemStatus::Failed, Some(e), retry)
+                        }
+                    }
+                };
+                (queue.clone(), transition, error, retry_status)
+            };
+            snapshot.save().await?;
+            emit_queue_changed(self.app.as_ref(), &self.logger, &snapshot);
+            emit_item_status(
+                self.app.as_ref(),
+                &self.logger,
+                &job.hash,
+                &job.path,
+                transition,
+                error.as_deref(),
+            );
+            if let (Some(app), Some(status)) = (self.app.as_ref(), retry_status) {
+                if let Err(e) = app.emit(UPLOAD_STATUS_EVENT, &status) {
+                    self.logger.warn(format!("Failed to emit {}: {}", UPLOAD_STATUS_EVENT, e));
+                }
+            }
+        }
+
+        Ok(succeeded)
+    }
+
+    /// Apply the retry policy to a failed job: bump the attempt counter and
+    /// either reschedule it with backoff or move it to the dead-letter list.
+    /// Returns `true` when the job was dead-lettered.
+    fn record_failure(&self, queue: &mut UploadQueue, hash: &str, error: String) -> bool {
+        if let Some(pos) = queue.pending.iter().position(|j| j.hash == hash) {
+            let mut job = queue.pending.remove(pos);
+            job.attempts += 1;
+            job.last_error = Some(error.clone());
+
+            if job.attempts >= MAX_ATTEMPTS {
+                self.logger.warn(format!(
+                    "Upload job {} dead-lettered after {} attempts: {}",
+                    hash, job.attempts, error
+                ));
+                queue.dead_letter.push(job);
+                return true;
+            }
+
+            let delay = backoff_delay_secs(job.attempts, hash_jitter(hash));
+            job.next_attempt_at = now_secs().saturating_add(delay);
+            self.logger.info(format!(
+                "Upload job {} retry {} scheduled in {}s: {}",
+                hash, job.attempts, delay, error
+            ));
+            queue.pending.push(job);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(hash: &str) -> UploadJob {
+        UploadJob::new(hash.to_string(), PathBuf::from("/tmp/x.SC2Replay"), JobMetadata::default())
+    }
+
+    #[test]
+    fn test_enqueue_dedupes_by_hash() {
+        let mut queue = UploadQueue::default();
+        assert!(queue.enqueue(job("abc")));
+        assert!(!queue.enqueue(job("abc")), "duplicate hash should be rejected");
+        assert_eq!(queue.pending.len(), 1);
+    }
+
+    #[test]
+    fn test_enqueue_skips_dead_lettered_hash() {
+        let mut queue = UploadQueue::default();
+        queue.dead_letter.push(job("dead"));
+        assert!(!queue.enqueue(job("dead")));
+        assert!(queue.pending.is_empty());
+    }
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        // Without jitter the delay doubles each attempt up to the cap.
+        assert_eq!(backoff_delay_secs(0, 0.0), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_delay_secs(1, 0.0), BACKOFF_BASE_SECS * 2);
+        assert_eq!(backoff_delay_secs(2, 0.0), BACKOFF_BASE_SECS * 4);
+        assert_eq!(backoff_delay_secs(40, 0.0), BACKOFF_CAP_SECS, "huge attempt counts clamp to cap");
+    }
+
+    #[test]
+    fn test_backoff_jitter_never_below_base() {
+        let base = base_delay(1);
+        let jittered = backoff_delay_secs(1, 0.5);
+        assert!(jittered >= base, "jitter should only add, never subtract");
+    }
+
+    #[test]
+    fn test_emit_queue_changed_without_app_handle_is_a_noop() {
+        let logger = DebugLogger::new();
+        let queue = UploadQueue::default();
+        // No app handle (as in a headless test) should just skip the emit.
+        emit_queue_changed(None, &logger, &queue);
+    }
+
+    #[test]
+    fn test_due_indices_respects_next_attempt_at() {
+        let mut queue = UploadQueue::default();
+        let mut future = job("future");
+        future.next_attempt_at = now_secs() + 10_000;
+        queue.pending.push(future);
+        queue.pending.push(job("now"));
+
+        let due = queue.due_indices(now_secs());
+        assert_eq!(due, vec![1], "only the immediately-due job should be returned");
+    }
+}